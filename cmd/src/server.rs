@@ -535,8 +535,14 @@ impl TiKVServer {
         .unwrap_or_else(|e| fatal!("failed to create server: {}", e));
 
         let import_path = self.store_path.join("import");
-        let importer =
-            Arc::new(SSTImporter::new(import_path, self.encryption_key_manager.clone()).unwrap());
+        let importer = Arc::new(
+            SSTImporter::new_with_cache_capacity(
+                import_path,
+                self.encryption_key_manager.clone(),
+                self.config.import.cache_capacity_bytes,
+            )
+            .unwrap(),
+        );
 
         let mut split_check_worker = Worker::new("split-check");
         let split_check_runner = SplitCheckRunner::new(
@@ -640,6 +646,7 @@ impl TiKVServer {
             engines.engines.kv.clone(),
             servers.importer.clone(),
             self.security_mgr.clone(),
+            self.region_info_accessor.clone(),
         );
         if servers
             .server
@@ -10,5 +10,12 @@ define_error_codes!(
     GRPC => ("gRPC", "", ""),
     REGION_NOT_FOUND => ("RegionNotFound", "", ""),
     STORE_TOMBSTONE => ("StoreTombstone", "", ""),
+    STORE_LIMIT_EXCEEDED => ("StoreLimitExceeded", "", ""),
+    PD_IN_MAINTENANCE => ("PdInMaintenance", "", ""),
+    TIMEOUT => ("Timeout", "", ""),
+    WRONG_KEY_RANGE => ("WrongKeyRange", "", ""),
+    NON_CONTIGUOUS_ID => ("NonContiguousId", "", ""),
+    ENDPOINT_CLUSTER_MISMATCH => ("EndpointClusterMismatch", "", ""),
+    CANCELED => ("Canceled", "", ""),
     UNKNOWN => ("Unknown", "", "")
 );
@@ -16,5 +16,11 @@ define_error_codes!(
     ENGINE => ("Engine", "", ""),
     CANNOT_READ_EXTERNAL_STORAGE => ("CannotReadExternalStorage", "", ""),
     WRONG_KEY_PREFIX => ("WrongKeyPrefix", "", ""),
-    BAD_FORMAT => ("BadFormat", "", "")
+    BAD_FORMAT => ("BadFormat", "", ""),
+    REGION_NOT_FOUND => ("RegionNotFound", "", ""),
+    CHECKSUM_MISMATCH => ("ChecksumMismatch", "", ""),
+    API_VERSION_MISMATCH => ("ApiVersionMismatch", "", ""),
+    DISK_SPACE_NOT_ENOUGH => ("DiskSpaceNotEnough", "", ""),
+    EMPTY_SST => ("EmptySst", "", ""),
+    DISK_QUOTA_EXCEEDED => ("DiskQuotaExceeded", "", "")
 );
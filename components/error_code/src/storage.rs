@@ -9,6 +9,7 @@ define_error_codes!(
     IO => ("Io", "", ""),
     SCHED_TOO_BUSY => ("SchedTooBusy", "", ""),
     GC_WORKER_TOO_BUSY => ("GcWorkerTooBusy", "", ""),
+    GC_TASK_TIMED_OUT => ("GcTaskTimedOut", "", ""),
     KEY_TOO_LARGE => ("KeyTooLarge", "", ""),
     INVALID_CF => ("InvalidCF", "", ""),
     PROTOBUF => ("Protobuf", "", ""),
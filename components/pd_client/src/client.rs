@@ -1,7 +1,9 @@
 // Copyright 2017 TiKV Project Authors. Licensed under Apache-2.0.
 
+use std::collections::HashMap;
 use std::fmt;
-use std::sync::{Arc, RwLock};
+use std::ops::Range;
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -20,17 +22,40 @@ use tikv_util::{Either, HandyRwLock};
 use txn_types::TimeStamp;
 
 use super::metrics::*;
-use super::util::{check_resp_header, sync_request, validate_endpoints, Inner, LeaderClient};
+use super::util::{
+    check_resp_header, get_store_slow_score, sync_request, validate_endpoints, Inner, LeaderClient,
+};
 use super::{Config, PdFuture, UnixSecs};
-use super::{Error, PdClient, RegionInfo, RegionStat, Result, REQUEST_TIMEOUT};
+use super::{
+    Error, HotRegionInfo, PdClient, PdFeature, RegionInfo, RegionStat, Result, SchedulerLimits,
+    REQUEST_TIMEOUT,
+};
 use tikv_util::timer::GLOBAL_TIMER_HANDLE;
 
 const CQ_COUNT: usize = 1;
 const CLIENT_PREFIX: &str = "pd";
 
+// Conservative, not read from PD (see `SchedulerLimits`): the recommended number of in-flight
+// operators for a single store, scaled up by the current store count for cluster-wide limits.
+const DEFAULT_STORE_OPERATOR_LIMIT: u64 = 8;
+
+// `AllocId` has no count field, so refilling one id at a time on every `alloc_id()` call would
+// still be one RPC per id. Fetch this many at once and serve subsequent calls from `id_cache`.
+const ALLOC_ID_BATCH_SIZE: u32 = 1000;
+
 pub struct RpcClient {
     cluster_id: u64,
     leader_client: Arc<LeaderClient>,
+    // region id -> (store id of the reporting leader, most recently reported stats). Refreshed
+    // on every `region_heartbeat`; used to answer `get_hot_regions` without a dedicated PD RPC.
+    hot_regions: RwLock<HashMap<u64, (u64, HotRegionInfo)>>,
+    // Ids allocated from PD via `alloc_id_batch` but not yet handed out by `alloc_id`.
+    id_cache: Mutex<Range<u64>>,
+    // region id -> most recently resolved `Region`. Populated by `get_region`/`get_region_info`
+    // (`get_region_by_id` does not touch it); a caller that just observed a split or merge can proactively evict the
+    // stale entry via `evict_region_from_cache` instead of waiting for a lookup against the new
+    // key range to fail.
+    region_cache: RwLock<HashMap<u64, metapb::Region>>,
 }
 
 impl RpcClient {
@@ -57,7 +82,11 @@ impl RpcClient {
                             security_mgr,
                             client,
                             members,
+                            cfg.reconnect_backoff.clone(),
                         )),
+                        hot_regions: RwLock::new(HashMap::default()),
+                        id_cache: Mutex::new(0..0),
+                        region_cache: RwLock::new(HashMap::default()),
                     };
 
                     // spawn a background future to update PD information periodically
@@ -122,11 +151,77 @@ impl RpcClient {
         self.leader_client.get_leader()
     }
 
+    /// Shuts down the client's background heartbeat stream, so it closes gracefully instead of
+    /// being torn down abruptly when the client is dropped. Called automatically on `Drop`; the
+    /// periodic update loop notices the drop on its own (it only holds a `Weak` reference) and
+    /// exits without needing to be told explicitly.
+    pub fn close(&self) {
+        self.leader_client.close();
+    }
+
     /// Re-establishes connection with PD leader in synchronized fashion.
     pub fn reconnect(&self) -> Result<()> {
         block_on(self.leader_client.reconnect())
     }
 
+    /// Immediately re-runs leader discovery and switches the active connection if the leader
+    /// changed, without waiting for a business RPC to fail first. Unlike `reconnect()`, this is
+    /// not throttled by the reconnect backoff interval (`Config::reconnect_backoff`), so it is suitable
+    /// for callers that proactively detected a leader change (e.g. via an external notification)
+    /// and want to fail over as soon as possible.
+    pub fn force_reconnect_leader(&self) -> Result<()> {
+        block_on(self.leader_client.force_reconnect())
+    }
+
+    /// Sends a single store heartbeat, returning the full response rather than discarding it.
+    /// `store_heartbeat` (the `PdClient` trait method) is built on top of this.
+    fn store_heartbeat_request(
+        &self,
+        mut stats: pdpb::StoreStats,
+    ) -> PdFuture<pdpb::StoreHeartbeatResponse> {
+        let timer = Instant::now();
+
+        let mut req = pdpb::StoreHeartbeatRequest::default();
+        req.set_header(self.header());
+        stats
+            .mut_interval()
+            .set_end_timestamp(UnixSecs::now().into_inner());
+        req.set_stats(stats);
+        let executor = move |client: &RwLock<Inner>, req: pdpb::StoreHeartbeatRequest| {
+            let handler = client
+                .rl()
+                .client_stub
+                .store_heartbeat_async_opt(&req, Self::call_option())
+                .unwrap_or_else(|e| panic!("fail to request PD {} err {:?}", "store_heartbeat", e));
+            Box::new(handler.map_err(Error::from).and_then(move |resp| {
+                PD_REQUEST_HISTOGRAM_VEC
+                    .with_label_values(&["store_heartbeat"])
+                    .observe(duration_to_sec(timer.elapsed()));
+                check_resp_header(resp.get_header())?;
+                Ok(resp)
+            })) as PdFuture<_>
+        };
+
+        self.leader_client
+            .request(req, executor, LEADER_CHANGE_RETRY)
+            .execute()
+    }
+
+    /// Reports multiple store heartbeats at once, for a process that represents several logical
+    /// stores. There is no batched heartbeat RPC on the PD side, so this pipelines one
+    /// `store_heartbeat` call per entry of `stats` — issuing them without waiting for earlier
+    /// ones to complete — and resolves with their responses in the same order as `stats`.
+    pub fn store_heartbeat_batch(
+        &self,
+        stats: Vec<pdpb::StoreStats>,
+    ) -> PdFuture<Vec<pdpb::StoreHeartbeatResponse>> {
+        let futures: Vec<_> = stats
+            .into_iter()
+            .map(|s| self.store_heartbeat_request(s))
+            .collect();
+        Box::new(future::join_all(futures))
+    }
+
     /// Creates a new call option with default request timeout.
     #[inline]
     fn call_option() -> CallOption {
@@ -151,15 +246,90 @@ impl RpcClient {
         let region = if resp.has_region() {
             resp.take_region()
         } else {
-            return Err(Error::RegionNotFound(key.to_owned()));
+            // If we had this key's region cached, surface the epoch we last knew about so the
+            // caller can tell a genuinely missing region apart from one that just moved.
+            let epoch = self
+                .region_cache
+                .rl()
+                .values()
+                .find(|region| {
+                    key >= region.get_start_key()
+                        && (region.get_end_key().is_empty() || key < region.get_end_key())
+                })
+                .map(|region| region.get_region_epoch().clone());
+            return Err(Error::RegionNotFound {
+                key: key.to_owned(),
+                epoch,
+            });
         };
         let leader = if resp.has_leader() {
             Some(resp.take_leader())
         } else {
             None
         };
+        self.region_cache
+            .wl()
+            .insert(region.get_id(), region.clone());
         Ok((region, leader))
     }
+
+    /// The number of regions currently held in the region info cache.
+    ///
+    /// Exposed mainly so tests can observe cache population/eviction; production callers
+    /// generally only need `evict_region_from_cache`.
+    pub fn region_cache_len(&self) -> usize {
+        self.region_cache.rl().len()
+    }
+
+    /// Proactively removes a region from the cache populated by `get_region`/`get_region_info`.
+    ///
+    /// After a split or merge, the cached entry for the old region no longer matches the key
+    /// range PD now reports for its id; calling this as soon as the split/merge is observed
+    /// avoids serving that stale region to a caller (e.g. the coprocessor) before the next
+    /// lookup happens to miss and refetch it.
+    pub fn evict_region_from_cache(&self, region_id: u64) {
+        self.region_cache.wl().remove(&region_id);
+    }
+
+    /// Allocates a single id from PD. This is one RPC per id; `alloc_id_range` and the cache in
+    /// `alloc_id` exist so most callers don't pay that cost directly.
+    fn alloc_id_raw(&self) -> Result<u64> {
+        let _timer = PD_REQUEST_HISTOGRAM_VEC
+            .with_label_values(&["alloc_id"])
+            .start_coarse_timer();
+
+        let mut req = pdpb::AllocIdRequest::default();
+        req.set_header(self.header());
+
+        let resp = sync_request(&self.leader_client, LEADER_CHANGE_RETRY, |client| {
+            client.alloc_id_opt(&req, Self::call_option())
+        })?;
+        check_resp_header(resp.get_header())?;
+
+        Ok(resp.get_id())
+    }
+
+    /// Allocates `count` ids one RPC at a time and stitches them into a contiguous range. Errors
+    /// out rather than returning a range that PD never actually granted if the ids it hands back
+    /// turn out not to be consecutive.
+    fn alloc_id_range(&self, count: u32) -> Result<Range<u64>> {
+        let start = self.alloc_id_raw()?;
+        let mut prev = start;
+        for _ in 1..count {
+            let next = self.alloc_id_raw()?;
+            if next != prev + 1 {
+                return Err(Error::NonContiguousId(prev, next));
+            }
+            prev = next;
+        }
+        Ok(start..prev + 1)
+    }
+}
+
+impl Drop for RpcClient {
+    fn drop(&mut self) {
+        self.close();
+    }
 }
 
 impl fmt::Debug for RpcClient {
@@ -212,19 +382,15 @@ impl PdClient for RpcClient {
     }
 
     fn alloc_id(&self) -> Result<u64> {
-        let _timer = PD_REQUEST_HISTOGRAM_VEC
-            .with_label_values(&["alloc_id"])
-            .start_coarse_timer();
-
-        let mut req = pdpb::AllocIdRequest::default();
-        req.set_header(self.header());
-
-        let resp = sync_request(&self.leader_client, LEADER_CHANGE_RETRY, |client| {
-            client.alloc_id_opt(&req, Self::call_option())
-        })?;
-        check_resp_header(resp.get_header())?;
+        let mut cache = self.id_cache.lock().unwrap();
+        if cache.start >= cache.end {
+            *cache = self.alloc_id_range(ALLOC_ID_BATCH_SIZE)?;
+        }
+        Ok(cache.next().unwrap())
+    }
 
-        Ok(resp.get_id())
+    fn alloc_id_batch(&self, count: u32) -> Result<Range<u64>> {
+        self.alloc_id_range(count)
     }
 
     fn put_store(&self, store: metapb::Store) -> Result<()> {
@@ -283,6 +449,46 @@ impl PdClient for RpcClient {
         Ok(resp.take_stores().into())
     }
 
+    fn get_stores_by_ids(
+        &self,
+        store_ids: &[u64],
+        include_tombstone: bool,
+    ) -> Result<Vec<Option<metapb::Store>>> {
+        let stores = self.get_all_stores(false)?;
+        let mut by_id = HashMap::with_capacity(stores.len());
+        for store in stores {
+            by_id.insert(store.get_id(), store);
+        }
+        Ok(store_ids
+            .iter()
+            .map(|id| match by_id.get(id) {
+                Some(store) if store.get_state() == metapb::StoreState::Tombstone => {
+                    if include_tombstone {
+                        Some(store.clone())
+                    } else {
+                        None
+                    }
+                }
+                Some(store) => Some(store.clone()),
+                None => None,
+            })
+            .collect())
+    }
+
+    fn supports_feature(&self, feature: PdFeature) -> bool {
+        let stores = match self.get_all_stores(true) {
+            Ok(stores) => stores,
+            Err(_) => return false,
+        };
+        if stores.is_empty() {
+            return false;
+        }
+        stores.iter().all(|store| {
+            crate::feature::parse_version(store.get_version())
+                .map_or(false, |v| v >= feature.min_version())
+        })
+    }
+
     fn get_cluster_config(&self) -> Result<metapb::Cluster> {
         let _timer = PD_REQUEST_HISTOGRAM_VEC
             .with_label_values(&["get_cluster_config"])
@@ -323,7 +529,7 @@ impl PdClient for RpcClient {
                 .unwrap_or_else(|e| {
                     panic!("fail to request PD {} err {:?}", "get_region_by_id", e)
                 });
-            Box::new(handler.map_err(Error::Grpc).and_then(move |mut resp| {
+            Box::new(handler.map_err(Error::from).and_then(move |mut resp| {
                 PD_REQUEST_HISTOGRAM_VEC
                     .with_label_values(&["get_region_by_id"])
                     .observe(duration_to_sec(timer.elapsed()));
@@ -341,6 +547,42 @@ impl PdClient for RpcClient {
             .execute()
     }
 
+    fn get_region_count(&self, start_key: Vec<u8>, end_key: Vec<u8>) -> PdFuture<u64> {
+        // There is no dedicated PD RPC for this, so walk region boundaries the same way
+        // `get_region` already does for a single key, hopping from each region's end key to the
+        // next until we either leave `[start_key, end_key)` or reach the last region.
+        let result = (|| -> Result<u64> {
+            let mut count = 0;
+            let mut cur_key = start_key;
+            loop {
+                if !end_key.is_empty() && cur_key >= end_key {
+                    break;
+                }
+                let (region, _) = self.get_region_and_leader(&cur_key)?;
+                count += 1;
+                let next_key = region.get_end_key().to_vec();
+                if next_key.is_empty() {
+                    break;
+                }
+                cur_key = next_key;
+            }
+            Ok(count)
+        })();
+        Box::new(future::result(result)) as PdFuture<_>
+    }
+
+    fn get_scheduler_config(&self) -> PdFuture<SchedulerLimits> {
+        let result = self.get_all_stores(true).map(|stores| {
+            let store_count = stores.len().max(1) as u64;
+            SchedulerLimits {
+                max_store_operator_limit: DEFAULT_STORE_OPERATOR_LIMIT,
+                max_region_scatter_limit: DEFAULT_STORE_OPERATOR_LIMIT * store_count,
+                max_leader_scatter_limit: DEFAULT_STORE_OPERATOR_LIMIT * store_count,
+            }
+        });
+        Box::new(future::result(result)) as PdFuture<_>
+    }
+
     fn region_heartbeat(
         &self,
         term: u64,
@@ -350,6 +592,20 @@ impl PdClient for RpcClient {
     ) -> PdFuture<()> {
         PD_HEARTBEAT_COUNTER_VEC.with_label_values(&["send"]).inc();
 
+        self.hot_regions.wl().insert(
+            region.get_id(),
+            (
+                leader.get_store_id(),
+                HotRegionInfo {
+                    region_id: region.get_id(),
+                    read_bytes: region_stat.read_bytes,
+                    read_keys: region_stat.read_keys,
+                    written_bytes: region_stat.written_bytes,
+                    written_keys: region_stat.written_keys,
+                },
+            ),
+        );
+
         let mut req = pdpb::RegionHeartbeatRequest::default();
         req.set_term(term);
         req.set_header(self.header());
@@ -387,7 +643,7 @@ impl PdClient for RpcClient {
             inner.hb_sender = Either::Right(tx);
             Box::new(
                 sender
-                    .sink_map_err(Error::Grpc)
+                    .sink_map_err(Error::from)
                     .send_all(rx.then(|r| match r {
                         Ok(r) => Ok((r, WriteFlags::default())),
                         Err(()) => Err(Error::Other(box_err!("failed to recv heartbeat"))),
@@ -431,7 +687,7 @@ impl PdClient for RpcClient {
                 .client_stub
                 .ask_split_async_opt(&req, Self::call_option())
                 .unwrap_or_else(|e| panic!("fail to request PD {} err {:?}", "ask_split", e));
-            Box::new(handler.map_err(Error::Grpc).and_then(move |resp| {
+            Box::new(handler.map_err(Error::from).and_then(move |resp| {
                 PD_REQUEST_HISTOGRAM_VEC
                     .with_label_values(&["ask_split"])
                     .observe(duration_to_sec(timer.elapsed()));
@@ -463,7 +719,7 @@ impl PdClient for RpcClient {
                 .client_stub
                 .ask_batch_split_async_opt(&req, Self::call_option())
                 .unwrap_or_else(|e| panic!("fail to request PD {} err {:?}", "ask_batch_split", e));
-            Box::new(handler.map_err(Error::Grpc).and_then(move |resp| {
+            Box::new(handler.map_err(Error::from).and_then(move |resp| {
                 PD_REQUEST_HISTOGRAM_VEC
                     .with_label_values(&["ask_batch_split"])
                     .observe(duration_to_sec(timer.elapsed()));
@@ -477,33 +733,8 @@ impl PdClient for RpcClient {
             .execute()
     }
 
-    fn store_heartbeat(&self, mut stats: pdpb::StoreStats) -> PdFuture<()> {
-        let timer = Instant::now();
-
-        let mut req = pdpb::StoreHeartbeatRequest::default();
-        req.set_header(self.header());
-        stats
-            .mut_interval()
-            .set_end_timestamp(UnixSecs::now().into_inner());
-        req.set_stats(stats);
-        let executor = move |client: &RwLock<Inner>, req: pdpb::StoreHeartbeatRequest| {
-            let handler = client
-                .rl()
-                .client_stub
-                .store_heartbeat_async_opt(&req, Self::call_option())
-                .unwrap_or_else(|e| panic!("fail to request PD {} err {:?}", "store_heartbeat", e));
-            Box::new(handler.map_err(Error::Grpc).and_then(move |resp| {
-                PD_REQUEST_HISTOGRAM_VEC
-                    .with_label_values(&["store_heartbeat"])
-                    .observe(duration_to_sec(timer.elapsed()));
-                check_resp_header(resp.get_header())?;
-                Ok(())
-            })) as PdFuture<_>
-        };
-
-        self.leader_client
-            .request(req, executor, LEADER_CHANGE_RETRY)
-            .execute()
+    fn store_heartbeat(&self, stats: pdpb::StoreStats) -> PdFuture<()> {
+        Box::new(self.store_heartbeat_request(stats).map(|_| ()))
     }
 
     fn report_batch_split(&self, regions: Vec<metapb::Region>) -> PdFuture<()> {
@@ -521,7 +752,7 @@ impl PdClient for RpcClient {
                 .unwrap_or_else(|e| {
                     panic!("fail to request PD {} err {:?}", "report_batch_split", e)
                 });
-            Box::new(handler.map_err(Error::Grpc).and_then(move |resp| {
+            Box::new(handler.map_err(Error::from).and_then(move |resp| {
                 PD_REQUEST_HISTOGRAM_VEC
                     .with_label_values(&["report_batch_split"])
                     .observe(duration_to_sec(timer.elapsed()));
@@ -554,10 +785,30 @@ impl PdClient for RpcClient {
         check_resp_header(resp.get_header())
     }
 
+    fn scatter_regions(&self, region_ids: &[u64], group: &str) -> Result<()> {
+        let _timer = PD_REQUEST_HISTOGRAM_VEC
+            .with_label_values(&["scatter_region"])
+            .start_coarse_timer();
+
+        let mut req = pdpb::ScatterRegionRequest::default();
+        req.set_header(self.header());
+        req.set_region_ids(region_ids.to_vec());
+        req.set_group(group.to_owned());
+
+        let resp = sync_request(&self.leader_client, LEADER_CHANGE_RETRY, |client| {
+            client.scatter_region_opt(&req, Self::call_option())
+        })?;
+        check_resp_header(resp.get_header())
+    }
+
     fn handle_reconnect<F: Fn() + Sync + Send + 'static>(&self, f: F) {
         self.leader_client.on_reconnect(Box::new(f))
     }
 
+    fn add_leader_change_listener(&self, f: Box<dyn Fn(&str) + Send>) {
+        self.leader_client.add_leader_change_listener(f)
+    }
+
     fn get_gc_safe_point(&self) -> PdFuture<u64> {
         let timer = Instant::now();
 
@@ -573,7 +824,7 @@ impl PdClient for RpcClient {
                 .unwrap_or_else(|e| {
                     panic!("fail to request PD {} err {:?}", "get_gc_saft_point", e)
                 });
-            Box::new(handler.map_err(Error::Grpc).and_then(move |resp| {
+            Box::new(handler.map_err(Error::from).and_then(move |resp| {
                 PD_REQUEST_HISTOGRAM_VEC
                     .with_label_values(&["get_gc_safe_point"])
                     .observe(duration_to_sec(timer.elapsed()));
@@ -609,6 +860,14 @@ impl PdClient for RpcClient {
         }
     }
 
+    fn get_all_store_stats(&self) -> Result<Vec<pdpb::StoreStats>> {
+        let stores = self.get_all_stores(true)?;
+        stores
+            .iter()
+            .map(|store| self.get_store_stats(store.get_id()))
+            .collect()
+    }
+
     fn get_operator(&self, region_id: u64) -> Result<pdpb::GetOperatorResponse> {
         let _timer = PD_REQUEST_HISTOGRAM_VEC
             .with_label_values(&["get_operator"])
@@ -625,16 +884,77 @@ impl PdClient for RpcClient {
 
         Ok(resp)
     }
+
+    fn get_store_slow_scores(&self) -> PdFuture<HashMap<u64, u64>> {
+        // Mirrors `get_scheduler_config`: there's no dedicated PD RPC for this, so it's derived
+        // from a couple of existing sync RPCs and handed back as an already-resolved future.
+        let result = self.get_all_stores(false).map(|stores| {
+            stores
+                .iter()
+                .filter_map(|store| {
+                    self.get_store_stats(store.get_id())
+                        .ok()
+                        .map(|stats| (store.get_id(), get_store_slow_score(&stats)))
+                })
+                .collect()
+        });
+        Box::new(future::result(result)) as PdFuture<_>
+    }
+    fn get_hot_regions(&self, store_id: u64) -> PdFuture<Vec<HotRegionInfo>> {
+        // Mirrors `get_store_slow_scores`: there's no dedicated PD RPC for this, so hotness is
+        // classified locally from the same stats already cached off of `region_heartbeat`.
+        let hot = self
+            .hot_regions
+            .rl()
+            .values()
+            .filter(|(sid, info)| *sid == store_id && info.is_hot())
+            .map(|(_, info)| *info)
+            .collect();
+        Box::new(future::ok(hot)) as PdFuture<_>
+    }
+
     // TODO: The current implementation is not efficient, because it creates
     //       a RPC for every `PdFuture<TimeStamp>`. As a duplex streaming RPC,
     //       we could use one RPC for many `PdFuture<TimeStamp>`.
     fn get_tso(&self) -> PdFuture<TimeStamp> {
-        let timer = Instant::now();
-
         let mut req = pdpb::TsoRequest::default();
         req.set_count(1);
         req.set_header(self.header());
-        let executor = move |client: &RwLock<Inner>, req: pdpb::TsoRequest| {
+
+        let leader_client = Arc::clone(&self.leader_client);
+        let fut = async move {
+            let mut last_err = None;
+            for attempt in 0..TSO_RETRY_MAX_ATTEMPTS {
+                if attempt > 0 {
+                    let _ = GLOBAL_TIMER_HANDLE
+                        .delay(Instant::now() + tso_retry_delay(attempt))
+                        .compat()
+                        .await;
+                }
+                let executor = Self::tso_executor(Instant::now());
+                match leader_client
+                    .request(req.clone(), executor, LEADER_CHANGE_RETRY)
+                    .execute()
+                    .compat()
+                    .await
+                {
+                    Ok(ts) => return Ok(ts),
+                    Err(e) => last_err = Some(e),
+                }
+            }
+            Err(last_err.unwrap())
+        };
+        Box::new(Compat::new(fut.boxed())) as PdFuture<_>
+    }
+}
+
+impl RpcClient {
+    /// Builds the executor that drives a single `tso()` RPC attempt. Split out of `get_tso` so it
+    /// can be constructed fresh for each of the bounded retry attempts.
+    fn tso_executor(
+        timer: Instant,
+    ) -> impl FnMut(&RwLock<Inner>, pdpb::TsoRequest) -> PdFuture<TimeStamp> {
+        move |client: &RwLock<Inner>, req: pdpb::TsoRequest| {
             let cli = client.read().unwrap();
             let (req_sink, resp_stream) = cli
                 .client_stub
@@ -669,10 +989,22 @@ impl PdClient for RpcClient {
                         Ok(encoded)
                     }),
             ) as PdFuture<_>
-        };
-
-        self.leader_client
-            .request(req, executor, LEADER_CHANGE_RETRY)
-            .execute()
+        }
     }
 }
+
+// `get_tso` is on the hot path of every write, so unlike other RPCs (which share
+// `LEADER_CHANGE_RETRY`) it uses a short, bounded retry of its own: a handful of attempts spaced
+// by a small jittered delay, so that many clients hitting the same transient failure at once
+// don't retry in lockstep against PD. If contention persists past `TSO_RETRY_MAX_ATTEMPTS`,
+// batching multiple callers onto a single RPC (see the TODO on `get_tso`) would be the next step,
+// but that is not implemented yet.
+const TSO_RETRY_MAX_ATTEMPTS: u32 = 3;
+const TSO_RETRY_BASE_DELAY_MS: u64 = 5;
+const TSO_RETRY_JITTER_MS: u64 = 10;
+
+fn tso_retry_delay(attempt: u32) -> Duration {
+    use rand::Rng;
+    let jitter = rand::thread_rng().gen_range(0, TSO_RETRY_JITTER_MS);
+    Duration::from_millis(TSO_RETRY_BASE_DELAY_MS * u64::from(attempt) + jitter)
+}
@@ -32,6 +32,33 @@ pub struct Config {
     ///
     /// Default is 10m.
     pub update_interval: ReadableDuration,
+    /// The backoff policy used between reconnect attempts to the PD leader once the client is
+    /// up and running, so a prolonged PD outage doesn't hammer the endpoints at a fixed rate.
+    ///
+    /// Default reproduces the historical fixed 1s interval: base and max are both 1s, so the
+    /// multiplier never has anything to grow into.
+    pub reconnect_backoff: BackoffConfig,
+}
+
+/// An exponential backoff policy: starts at `base_interval`, multiplies by `multiplier` after
+/// every failed attempt up to `max_interval`, and resets to `base_interval` after a success.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+#[serde(rename_all = "kebab-case")]
+pub struct BackoffConfig {
+    pub base_interval: ReadableDuration,
+    pub max_interval: ReadableDuration,
+    pub multiplier: f64,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        BackoffConfig {
+            base_interval: ReadableDuration::secs(1),
+            max_interval: ReadableDuration::secs(1),
+            multiplier: 2.0,
+        }
+    }
 }
 
 impl Default for Config {
@@ -42,6 +69,7 @@ impl Default for Config {
             retry_max_count: std::isize::MAX,
             retry_log_every: 10,
             update_interval: ReadableDuration::minutes(10),
+            reconnect_backoff: BackoffConfig::default(),
         }
     }
 }
@@ -67,6 +95,16 @@ impl Config {
             return Err("pd.retry_max_count cannot be < -1".into());
         }
 
+        if self.reconnect_backoff.multiplier < 1.0 {
+            return Err("pd.reconnect-backoff.multiplier cannot be < 1".into());
+        }
+
+        if self.reconnect_backoff.max_interval.0 < self.reconnect_backoff.base_interval.0 {
+            return Err(
+                "pd.reconnect-backoff.max-interval cannot be shorter than base-interval".into(),
+            );
+        }
+
         Ok(())
     }
 }
@@ -1,6 +1,7 @@
 // Copyright 2016 TiKV Project Authors. Licensed under Apache-2.0.
 
 use error_code::{self, ErrorCode, ErrorCodeExt};
+use kvproto::metapb;
 use std::error;
 use std::result;
 
@@ -25,7 +26,6 @@ quick_error! {
             display("feature is not supported in other cluster components")
         }
         Grpc(err: grpcio::Error) {
-            from()
             cause(err)
             description(err.description())
         }
@@ -35,14 +35,74 @@ quick_error! {
             description(err.description())
             display("unknown error {:?}", err)
         }
-        RegionNotFound(key: Vec<u8>) {
+        RegionNotFound { key: Vec<u8>, epoch: Option<metapb::RegionEpoch> } {
             description("region is not found")
-            display("region is not found for key {}", hex::encode_upper(key))
+            display(
+                "region is not found for key {}{}",
+                hex::encode_upper(key),
+                epoch.as_ref().map_or_else(String::new, |epoch| format!(
+                    ", last known epoch: conf_ver {} version {}",
+                    epoch.get_conf_ver(),
+                    epoch.get_version(),
+                )),
+            )
         }
         StoreTombstone(msg: String) {
             description("store is tombstone")
             display("store is tombstone {:?}", msg)
         }
+        StoreLimitExceeded { store_id: u64 } {
+            description("store operator limit exceeded")
+            display("store {} exceeded its operator limit", store_id)
+        }
+        PdInMaintenance(msg: String) {
+            description("pd is in maintenance mode")
+            display("pd is in maintenance mode and rejected the request: {:?}", msg)
+        }
+        Timeout(msg: String) {
+            description("request timeout")
+            display("request timeout: {}", msg)
+        }
+        WrongKeyRange(region_id: u64, key: Vec<u8>) {
+            description("split key out of region range")
+            display(
+                "split key {} does not lie within region {}'s range",
+                hex::encode_upper(key), region_id,
+            )
+        }
+        NonContiguousId(prev: u64, next: u64) {
+            description("pd allocated a non-contiguous id while filling a batch request")
+            display(
+                "expected pd to allocate {} next but it returned {}, ids are no longer contiguous",
+                prev + 1, next,
+            )
+        }
+        EndpointClusterMismatch { expected: u64, actual: u64, endpoint: String } {
+            description("pd endpoints report different cluster ids")
+            display(
+                "PD endpoint {} reports cluster_id {}, expected {}",
+                endpoint, actual, expected,
+            )
+        }
+        Canceled(msg: String) {
+            description("request was canceled")
+            display("request was canceled: {}", msg)
+        }
+    }
+}
+
+impl From<grpcio::Error> for Error {
+    // A future dropped mid-flight surfaces here as an `RpcFailure` carrying gRPC's own
+    // `CANCELLED` status, not as some distinct "the caller went away" signal. Recognize it and
+    // report `Canceled` instead of the generic `Grpc` error so callers (and our own retry logic)
+    // can tell a deliberate cancellation apart from a real RPC failure.
+    fn from(err: grpcio::Error) -> Error {
+        if let grpcio::Error::RpcFailure(grpcio::RpcStatus { ref status, .. }) = err {
+            if *status == grpcio::RpcStatusCode::CANCELLED {
+                return Error::Canceled(format!("{:?}", err));
+            }
+        }
+        Error::Grpc(err)
     }
 }
 
@@ -56,9 +116,85 @@ impl ErrorCodeExt for Error {
             Error::ClusterNotBootstrapped(_) => error_code::pd::CLUSTER_NOT_BOOTSTRAPPED,
             Error::Incompatible => error_code::pd::INCOMPATIBLE,
             Error::Grpc(_) => error_code::pd::GRPC,
-            Error::RegionNotFound(_) => error_code::pd::REGION_NOT_FOUND,
+            Error::RegionNotFound { .. } => error_code::pd::REGION_NOT_FOUND,
             Error::StoreTombstone(_) => error_code::pd::STORE_TOMBSTONE,
+            Error::StoreLimitExceeded { .. } => error_code::pd::STORE_LIMIT_EXCEEDED,
+            Error::PdInMaintenance(_) => error_code::pd::PD_IN_MAINTENANCE,
+            Error::Timeout(_) => error_code::pd::TIMEOUT,
+            Error::WrongKeyRange(_, _) => error_code::pd::WRONG_KEY_RANGE,
+            Error::NonContiguousId(_, _) => error_code::pd::NON_CONTIGUOUS_ID,
+            Error::EndpointClusterMismatch { .. } => error_code::pd::ENDPOINT_CLUSTER_MISMATCH,
+            Error::Canceled(_) => error_code::pd::CANCELED,
             Error::Other(_) => error_code::pd::UNKNOWN,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_limit_exceeded_error_code() {
+        let err = Error::StoreLimitExceeded { store_id: 7 };
+        assert_eq!(err.to_string(), "store 7 exceeded its operator limit");
+        assert_eq!(err.error_code(), error_code::pd::STORE_LIMIT_EXCEEDED);
+    }
+
+    #[test]
+    fn test_region_not_found_display() {
+        let err = Error::RegionNotFound {
+            key: b"k".to_vec(),
+            epoch: None,
+        };
+        assert_eq!(err.to_string(), "region is not found for key 6B");
+        assert_eq!(err.error_code(), error_code::pd::REGION_NOT_FOUND);
+
+        let mut epoch = metapb::RegionEpoch::default();
+        epoch.set_conf_ver(2);
+        epoch.set_version(5);
+        let err = Error::RegionNotFound {
+            key: b"k".to_vec(),
+            epoch: Some(epoch),
+        };
+        assert_eq!(
+            err.to_string(),
+            "region is not found for key 6B, last known epoch: conf_ver 2 version 5"
+        );
+    }
+
+    #[test]
+    fn test_pd_in_maintenance_error_code() {
+        let err = Error::PdInMaintenance("cluster is in maintenance mode".to_owned());
+        assert_eq!(err.error_code(), error_code::pd::PD_IN_MAINTENANCE);
+    }
+
+    #[test]
+    fn test_dropped_future_reports_canceled() {
+        // Dropping a pending `PdFuture` mid-flight cancels the underlying gRPC call, which
+        // surfaces on the wire as an `RpcFailure` carrying gRPC's `CANCELLED` status. Simulate
+        // that status arriving at the `From<grpcio::Error>` conversion and check it is reported
+        // as `Error::Canceled`, not the generic `Error::Grpc`, so retry logic can tell the two
+        // apart.
+        let grpc_err = grpcio::Error::RpcFailure(grpcio::RpcStatus::new(
+            grpcio::RpcStatusCode::CANCELLED,
+            Some("call dropped".to_owned()),
+        ));
+        let err: Error = grpc_err.into();
+        match err {
+            Error::Canceled(_) => {}
+            other => panic!("expected Error::Canceled, got {:?}", other),
+        }
+        assert_eq!(err.error_code(), error_code::pd::CANCELED);
+    }
+
+    #[test]
+    fn test_wrong_key_range_error_code() {
+        let err = Error::WrongKeyRange(1, b"k".to_vec());
+        assert_eq!(
+            err.to_string(),
+            "split key 6B does not lie within region 1's range"
+        );
+        assert_eq!(err.error_code(), error_code::pd::WRONG_KEY_RANGE);
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+//! Feature gating against the store versions reported to PD, so callers can branch before
+//! attempting an operation instead of reacting to an `Error::Incompatible` round-trip.
+
+/// A feature whose availability depends on every store in the cluster having upgraded past some
+/// minimum TiKV version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdFeature {
+    /// A placeholder future feature used to exercise `RpcClient::supports_feature`; replace with
+    /// real feature variants as they're introduced.
+    ExampleFutureFeature,
+}
+
+impl PdFeature {
+    /// The minimum TiKV version, as `(major, minor, patch)`, that supports this feature.
+    pub(crate) fn min_version(self) -> (u32, u32, u32) {
+        match self {
+            PdFeature::ExampleFutureFeature => (5, 0, 0),
+        }
+    }
+}
+
+/// Parses a leading `major.minor.patch` out of a TiKV version string such as `"4.0.15"` or
+/// `"5.0.0-alpha"`. Returns `None` if `version` doesn't start with at least a major version
+/// number.
+pub(crate) fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()
+        .map(|p| leading_digits(p).parse().ok())
+        .unwrap_or(Some(0))?;
+    let patch = parts
+        .next()
+        .map(|p| leading_digits(p).parse().ok())
+        .unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+/// Strips any non-digit suffix (e.g. the `-alpha` in `"0-alpha"`), leaving a plain number string.
+fn leading_digits(s: &str) -> &str {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("4.0.15"), Some((4, 0, 15)));
+        assert_eq!(parse_version("5.0.0-alpha"), Some((5, 0, 0)));
+        assert_eq!(parse_version("5.0"), Some((5, 0, 0)));
+        assert_eq!(parse_version("5"), Some((5, 0, 0)));
+        assert_eq!(parse_version(""), None);
+    }
+}
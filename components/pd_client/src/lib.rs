@@ -20,20 +20,25 @@ extern crate tikv_alloc;
 extern crate tikv_util;
 
 mod client;
+mod feature;
 pub mod metrics;
 mod util;
 
 mod config;
 pub mod errors;
 pub use self::client::RpcClient;
-pub use self::config::Config;
+pub use self::config::{BackoffConfig, Config};
 pub use self::errors::{Error, Result};
+pub use self::feature::PdFeature;
 pub use self::util::validate_endpoints;
+pub use self::util::{get_store_slow_score, set_store_slow_score};
+pub use self::util::wait_with_timeout;
 pub use self::util::RECONNECT_INTERVAL_SEC;
 
+use std::collections::HashMap;
 use std::ops::Deref;
 
-use futures::Future;
+use futures::{future, Future};
 use kvproto::metapb;
 use kvproto::pdpb;
 use tikv_util::time::UnixSecs;
@@ -55,6 +60,73 @@ pub struct RegionStat {
     pub last_report_ts: UnixSecs,
 }
 
+/// Helper to build a `RegionStat` without having to name every field, e.g. when only the
+/// read/write flow hints a hotspot-aware scheduler cares about need to be set and everything
+/// else should keep its zeroed, no-op default.
+#[derive(Default)]
+pub struct RegionStatBuilder(RegionStat);
+
+impl RegionStatBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn down_peers(mut self, v: Vec<pdpb::PeerStats>) -> Self {
+        self.0.down_peers = v;
+        self
+    }
+
+    pub fn pending_peers(mut self, v: Vec<metapb::Peer>) -> Self {
+        self.0.pending_peers = v;
+        self
+    }
+
+    pub fn written_bytes(mut self, v: u64) -> Self {
+        self.0.written_bytes = v;
+        self
+    }
+
+    pub fn written_keys(mut self, v: u64) -> Self {
+        self.0.written_keys = v;
+        self
+    }
+
+    pub fn read_bytes(mut self, v: u64) -> Self {
+        self.0.read_bytes = v;
+        self
+    }
+
+    pub fn read_keys(mut self, v: u64) -> Self {
+        self.0.read_keys = v;
+        self
+    }
+
+    pub fn approximate_size(mut self, v: u64) -> Self {
+        self.0.approximate_size = v;
+        self
+    }
+
+    pub fn approximate_keys(mut self, v: u64) -> Self {
+        self.0.approximate_keys = v;
+        self
+    }
+
+    pub fn last_report_ts(mut self, v: UnixSecs) -> Self {
+        self.0.last_report_ts = v;
+        self
+    }
+
+    pub fn build(self) -> RegionStat {
+        self.0
+    }
+}
+
+impl From<RegionStatBuilder> for RegionStat {
+    fn from(builder: RegionStatBuilder) -> RegionStat {
+        builder.build()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct RegionInfo {
     pub region: metapb::Region,
@@ -77,6 +149,63 @@ impl Deref for RegionInfo {
 
 pub const INVALID_ID: u64 = 0;
 
+/// A conservative snapshot of PD's scheduling capacity, meant to help a caller driving many
+/// splits/scatters pace itself instead of firing them all at once and discovering the limit only
+/// via a `StoreLimitExceeded` rejection.
+///
+/// PD's gRPC surface in this tree has no RPC that reports its actually configured operator/store
+/// limits (that lives behind PD's HTTP config API instead), so `get_scheduler_config` derives
+/// these client-side from the current store count rather than fetching them from PD.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SchedulerLimits {
+    /// The recommended number of in-flight operators for a single store.
+    pub max_store_operator_limit: u64,
+    /// The recommended number of in-flight `scatter_region` calls across the whole cluster.
+    pub max_region_scatter_limit: u64,
+    /// The recommended number of in-flight leader-transfer scatters across the whole cluster.
+    pub max_leader_scatter_limit: u64,
+}
+
+/// A read or write hotspot on a single region, as observed via `region_heartbeat` and surfaced
+/// by `get_hot_regions` so a balancer can target it without re-deriving hotness from raw
+/// heartbeat stats itself.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HotRegionInfo {
+    pub region_id: u64,
+    pub read_bytes: u64,
+    pub read_keys: u64,
+    pub written_bytes: u64,
+    pub written_keys: u64,
+}
+
+/// Regions reporting at least this many bytes/keys since their last heartbeat are considered
+/// hot; mirrors the order of magnitude PD itself uses for hot-region detection, without
+/// depending on any additional configuration surface.
+pub const DEFAULT_HOT_REGION_READ_BYTES_THRESHOLD: u64 = 8 * 1024 * 1024;
+pub const DEFAULT_HOT_REGION_READ_KEYS_THRESHOLD: u64 = 128;
+pub const DEFAULT_HOT_REGION_WRITE_BYTES_THRESHOLD: u64 = 8 * 1024 * 1024;
+pub const DEFAULT_HOT_REGION_WRITE_KEYS_THRESHOLD: u64 = 128;
+
+impl HotRegionInfo {
+    /// Whether this region's last reported stats clear PD's hot-region thresholds for either
+    /// reads or writes.
+    pub fn is_hot(&self) -> bool {
+        self.read_bytes >= DEFAULT_HOT_REGION_READ_BYTES_THRESHOLD
+            || self.read_keys >= DEFAULT_HOT_REGION_READ_KEYS_THRESHOLD
+            || self.written_bytes >= DEFAULT_HOT_REGION_WRITE_BYTES_THRESHOLD
+            || self.written_keys >= DEFAULT_HOT_REGION_WRITE_KEYS_THRESHOLD
+    }
+}
+
+impl SchedulerLimits {
+    /// A safe batch size for a caller issuing region or leader scatters: the smaller of the two
+    /// cluster-wide limits, so alternating between the two operation kinds still stays under
+    /// budget for either.
+    pub fn recommended_batch_size(&self) -> u64 {
+        self.max_region_scatter_limit.min(self.max_leader_scatter_limit)
+    }
+}
+
 /// PdClient communicates with Placement Driver (PD).
 /// Because now one PD only supports one cluster, so it is no need to pass
 /// cluster id in trait interface every time, so passing the cluster id when
@@ -114,6 +243,16 @@ pub trait PdClient: Send + Sync {
         unimplemented!();
     }
 
+    /// Allocates a contiguous block of `count` unique positive ids, returned as `start..end`.
+    ///
+    /// Implementations that can only allocate one id per RPC (the common case, since the
+    /// `AllocId` wire protocol carries no count field) are expected to make this many
+    /// requests internally and stitch the results into a range, rather than exposing that
+    /// cost to the caller.
+    fn alloc_id_batch(&self, _count: u32) -> Result<std::ops::Range<u64>> {
+        unimplemented!();
+    }
+
     /// Informs PD when the store starts or some store information changes.
     fn put_store(&self, _store: metapb::Store) -> Result<()> {
         unimplemented!();
@@ -140,6 +279,35 @@ pub trait PdClient: Send + Sync {
         unimplemented!();
     }
 
+    /// Bulk variant of `get_store`, returned in the same order as `store_ids`. Unlike
+    /// `get_store`, a missing or tombstone store id doesn't fail the whole call: it is represented
+    /// as `None`, unless `include_tombstone` is set, in which case a tombstone store's record is
+    /// returned instead. There's no dedicated batch-lookup RPC in this tree's vendored `pdpb`, so
+    /// implementations are expected to satisfy this with a single `get_all_stores` round trip
+    /// filtered locally, rather than one RPC per id.
+    fn get_stores_by_ids(
+        &self,
+        _store_ids: &[u64],
+        _include_tombstone: bool,
+    ) -> Result<Vec<Option<metapb::Store>>> {
+        unimplemented!();
+    }
+
+    /// Fetches `StoreStats` for every non-tombstone store in the cluster. There's no dedicated
+    /// bulk stats RPC in this tree's vendored `pdpb`, so implementations are expected to fall
+    /// back to one `get_store_stats` per store returned by `get_all_stores(true)`.
+    fn get_all_store_stats(&self) -> Result<Vec<pdpb::StoreStats>> {
+        unimplemented!();
+    }
+
+    /// Returns whether `feature` is supported by every store currently known to the cluster, so
+    /// callers can branch before attempting an operation instead of reacting to an
+    /// `Error::Incompatible` round-trip. Conservatively returns `false` if the store list can't
+    /// be fetched or is empty, or if any store's reported version can't be parsed.
+    fn supports_feature(&self, _feature: PdFeature) -> bool {
+        unimplemented!();
+    }
+
     /// Gets cluster meta information.
     fn get_cluster_config(&self) -> Result<metapb::Cluster> {
         unimplemented!();
@@ -161,6 +329,28 @@ pub trait PdClient: Send + Sync {
         unimplemented!();
     }
 
+    /// Like `get_region_by_id`, but blocks the calling thread for at most `timeout` instead of
+    /// waiting on PD indefinitely, returning `Error::Timeout` if the deadline elapses first.
+    fn get_region_by_id_timeout(
+        &self,
+        region_id: u64,
+        timeout: std::time::Duration,
+    ) -> Result<Option<metapb::Region>> {
+        wait_with_timeout(self.get_region_by_id(region_id), timeout)
+    }
+
+    /// Gets the number of Regions whose key range intersects `[start_key, end_key)`. An empty
+    /// `end_key` means the range is unbounded on the right, matching `metapb::Region`'s own
+    /// convention for an empty end key.
+    fn get_region_count(&self, _start_key: Vec<u8>, _end_key: Vec<u8>) -> PdFuture<u64> {
+        unimplemented!();
+    }
+
+    /// Gets a conservative snapshot of PD's current scheduling capacity, see `SchedulerLimits`.
+    fn get_scheduler_config(&self) -> PdFuture<SchedulerLimits> {
+        unimplemented!();
+    }
+
     /// Region's Leader uses this to heartbeat PD.
     fn region_heartbeat(
         &self,
@@ -172,6 +362,15 @@ pub trait PdClient: Send + Sync {
         unimplemented!();
     }
 
+    /// Gets the regions on `store_id` that are currently read- or write-hot, based on the stats
+    /// each region most recently reported via `region_heartbeat`, so a balancer can target them
+    /// without re-deriving hotness itself. There's no dedicated PD RPC for this in this tree's
+    /// vendored `pdpb`, so implementations are expected to classify hotness locally from the
+    /// same stats `region_heartbeat` already sends, using `HotRegionInfo::is_hot`.
+    fn get_hot_regions(&self, _store_id: u64) -> PdFuture<Vec<HotRegionInfo>> {
+        unimplemented!();
+    }
+
     /// Gets a stream of Region heartbeat response.
     ///
     /// Please note that this method should only be called once.
@@ -197,6 +396,36 @@ pub trait PdClient: Send + Sync {
         unimplemented!();
     }
 
+    /// Like `ask_batch_split`, but for split points chosen by the caller instead of left up to
+    /// PD. Every key in `split_keys` must lie strictly inside `region`'s range (splitting exactly
+    /// on `region`'s start key would produce an empty leading region); each is validated locally
+    /// before the request would reach PD, so an out-of-bounds key is rejected as
+    /// `Error::WrongKeyRange` without allocating any ids for it.
+    ///
+    /// `pdpb::AskBatchSplitRequest` has no field carrying explicit split keys in this tree's
+    /// vendored `pdpb`, so the keys themselves are not sent to PD; like `ask_batch_split`, only
+    /// their count is, and the caller is responsible for pairing the ids PD returns with
+    /// `split_keys` in order.
+    fn ask_batch_split_at(
+        &self,
+        region: metapb::Region,
+        split_keys: Vec<Vec<u8>>,
+    ) -> PdFuture<pdpb::AskBatchSplitResponse> {
+        let start_key = region.get_start_key();
+        let end_key = region.get_end_key();
+        for key in &split_keys {
+            let in_range =
+                start_key < key.as_slice() && (end_key.is_empty() || key.as_slice() < end_key);
+            if !in_range {
+                return Box::new(future::err(Error::WrongKeyRange(
+                    region.get_id(),
+                    key.clone(),
+                )));
+            }
+        }
+        self.ask_batch_split(region, split_keys.len())
+    }
+
     /// Sends store statistics regularly.
     fn store_heartbeat(&self, _stats: pdpb::StoreStats) -> PdFuture<()> {
         unimplemented!();
@@ -212,6 +441,17 @@ pub trait PdClient: Send + Sync {
         unimplemented!();
     }
 
+    /// Scatters a set of regions across the cluster as a single coordinated group, so their
+    /// replicas spread evenly against each other instead of each region being scattered
+    /// independently (which can leave several of them clustering on the same stores by chance).
+    /// Useful for bulk-loaded tables, where many regions are created and need scattering at once.
+    ///
+    /// `group` is an opaque label PD uses to keep this batch's scatter operators from being
+    /// treated as conflicting with a concurrent, unrelated scatter of the same regions.
+    fn scatter_regions(&self, _region_ids: &[u64], _group: &str) -> Result<()> {
+        unimplemented!();
+    }
+
     /// Registers a handler to the client, which will be invoked after reconnecting to PD.
     ///
     /// Please note that this method should only be called once.
@@ -221,6 +461,11 @@ pub trait PdClient: Send + Sync {
     {
     }
 
+    /// Registers a listener that is invoked with the new leader's address every time the client
+    /// reconnects to a different PD leader. Unlike `handle_reconnect`, this may be called any
+    /// number of times; every registered listener is invoked on each reconnect.
+    fn add_leader_change_listener(&self, _f: Box<dyn Fn(&str) + Send>) {}
+
     fn get_gc_safe_point(&self) -> PdFuture<u64> {
         unimplemented!();
     }
@@ -235,10 +480,27 @@ pub trait PdClient: Send + Sync {
         unimplemented!();
     }
 
+    /// Gets PD's view of how slow each store in the cluster currently is, keyed by store id, for
+    /// leader-placement avoidance. There's no dedicated PD RPC for this, so implementations are
+    /// expected to derive it from the same per-store stats `store_heartbeat` reports, via
+    /// `get_store_slow_score` on the `StoreStats` returned by `get_store_stats` for every store
+    /// known to `get_all_stores`. A store that has never reported a score is reported as `0`,
+    /// the same default `get_store_slow_score` uses.
+    fn get_store_slow_scores(&self) -> PdFuture<HashMap<u64, u64>> {
+        unimplemented!();
+    }
+
     /// Gets a timestamp from PD.
     fn get_tso(&self) -> PdFuture<TimeStamp> {
         unimplemented!()
     }
+
+    /// Gets a timestamp from PD, returning its physical (wall-clock, in milliseconds) and logical
+    /// components separately instead of the combined `TimeStamp` form. Built on top of `get_tso`,
+    /// so it shares the same allocation path and retry behavior.
+    fn get_tso_parts(&self) -> PdFuture<(u64, u64)> {
+        Box::new(self.get_tso().map(|ts| (ts.physical(), ts.logical())))
+    }
 }
 
 const REQUEST_TIMEOUT: u64 = 2; // 2s
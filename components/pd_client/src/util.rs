@@ -10,15 +10,16 @@ use futures::future::{loop_fn, ok, Loop};
 use futures::sync::mpsc::UnboundedSender;
 use futures::task::Task;
 use futures::{task, Async, Future, Poll, Stream};
-use futures03::compat::Future01CompatExt;
+use futures03::compat::{Compat, Future01CompatExt};
 use futures03::executor::block_on;
+use futures03::future::FutureExt;
 use grpcio::{
     CallOption, ChannelBuilder, ClientDuplexReceiver, ClientDuplexSender, Environment,
     Result as GrpcResult,
 };
 use kvproto::pdpb::{
     ErrorType, GetMembersRequest, GetMembersResponse, Member, PdClient as PdClientStub,
-    RegionHeartbeatRequest, RegionHeartbeatResponse, ResponseHeader,
+    RecordPair, RegionHeartbeatRequest, RegionHeartbeatResponse, ResponseHeader, StoreStats,
 };
 use security::SecurityManager;
 use tikv_util::collections::HashSet;
@@ -26,7 +27,34 @@ use tikv_util::timer::GLOBAL_TIMER_HANDLE;
 use tikv_util::{Either, HandyRwLock};
 use tokio_timer::timer::Handle;
 
-use super::{Config, Error, PdFuture, Result, REQUEST_TIMEOUT};
+use super::{BackoffConfig, Config, Error, PdFuture, Result, REQUEST_TIMEOUT};
+
+/// Tracks the current reconnect interval for a `BackoffConfig`: starts at `base_interval`,
+/// doubles (or whatever `multiplier` says) on every `step()` up to `max_interval`, and drops
+/// back to `base_interval` on `reset()`.
+struct ReconnectBackoff {
+    cfg: BackoffConfig,
+    current: Duration,
+}
+
+impl ReconnectBackoff {
+    fn new(cfg: BackoffConfig) -> Self {
+        let current = cfg.base_interval.0;
+        ReconnectBackoff { cfg, current }
+    }
+
+    fn interval(&self) -> Duration {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.current = self.cfg.base_interval.0;
+    }
+
+    fn step(&mut self) {
+        self.current = self.current.mul_f64(self.cfg.multiplier).min(self.cfg.max_interval.0);
+    }
+}
 
 pub struct Inner {
     env: Arc<Environment>,
@@ -39,13 +67,15 @@ pub struct Inner {
     members: GetMembersResponse,
     security_mgr: Arc<SecurityManager>,
     on_reconnect: Option<Box<dyn Fn() + Sync + Send + 'static>>,
+    leader_change_listeners: Vec<Box<dyn Fn(&str) + Send + 'static>>,
+    backoff: ReconnectBackoff,
 
     last_update: Instant,
 }
 
 pub struct HeartbeatReceiver {
     receiver: Option<ClientDuplexReceiver<RegionHeartbeatResponse>>,
-    inner: Arc<RwLock<Inner>>,
+    leader_client: LeaderClient,
 }
 
 impl Stream for HeartbeatReceiver {
@@ -65,7 +95,7 @@ impl Stream for HeartbeatReceiver {
 
             self.receiver.take();
 
-            let mut inner = self.inner.wl();
+            let mut inner = self.leader_client.inner.wl();
             let mut receiver = None;
             if let Either::Left(ref mut recv) = inner.hb_receiver {
                 receiver = recv.take();
@@ -75,6 +105,26 @@ impl Stream for HeartbeatReceiver {
                 self.receiver = receiver;
             } else {
                 inner.hb_receiver = Either::Right(task::current());
+                // Rather than only waiting for unrelated request traffic (or the slow periodic
+                // update loop) to eventually notice the leader changed and refresh the receiver,
+                // proactively kick off a reconnect so the stream resubscribes on its own. This
+                // reuses the same `hb_receiver` slot and callback, so the caller of
+                // `handle_region_heartbeat_response` never needs to rebuild anything.
+                let client_stub = inner.client_stub.clone();
+                let leader_client = LeaderClient {
+                    timer: self.leader_client.timer.clone(),
+                    inner: Arc::clone(&self.leader_client.inner),
+                };
+                drop(inner);
+                client_stub.spawn(Compat::new(
+                    async move {
+                        if let Err(e) = leader_client.reconnect().await {
+                            warn!("failed to reconnect while refreshing heartbeat receiver"; "err" => ?e);
+                        }
+                    }
+                    .unit_error()
+                    .boxed(),
+                ));
                 return Ok(Async::NotReady);
             }
         }
@@ -93,6 +143,7 @@ impl LeaderClient {
         security_mgr: Arc<SecurityManager>,
         client_stub: PdClientStub,
         members: GetMembersResponse,
+        backoff_cfg: BackoffConfig,
     ) -> LeaderClient {
         let (tx, rx) = client_stub
             .region_heartbeat()
@@ -108,6 +159,8 @@ impl LeaderClient {
                 members,
                 security_mgr,
                 on_reconnect: None,
+                leader_change_listeners: Vec::new(),
+                backoff: ReconnectBackoff::new(backoff_cfg),
 
                 last_update: Instant::now(),
             })),
@@ -120,7 +173,10 @@ impl LeaderClient {
     {
         let recv = HeartbeatReceiver {
             receiver: None,
-            inner: Arc::clone(&self.inner),
+            leader_client: LeaderClient {
+                timer: self.timer.clone(),
+                inner: Arc::clone(&self.inner),
+            },
         };
         Box::new(
             recv.for_each(move |resp| {
@@ -131,11 +187,28 @@ impl LeaderClient {
         )
     }
 
+    /// Cancels the region heartbeat stream so it is torn down deliberately rather than dropped
+    /// mid-flight, avoiding the gRPC-layer warnings a client normally gets from an abruptly closed
+    /// duplex stream.
+    pub fn close(&self) {
+        let mut inner = self.inner.wl();
+        if let Either::Left(Some(ref mut sender)) = inner.hb_sender {
+            debug!("cancel region heartbeat sender on close");
+            sender.cancel();
+        }
+        inner.hb_sender = Either::Left(None);
+    }
+
     pub fn on_reconnect(&self, f: Box<dyn Fn() + Sync + Send + 'static>) {
         let mut inner = self.inner.wl();
         inner.on_reconnect = Some(f);
     }
 
+    pub fn add_leader_change_listener(&self, f: Box<dyn Fn(&str) + Send + 'static>) {
+        let mut inner = self.inner.wl();
+        inner.leader_change_listeners.push(f);
+    }
+
     pub fn request<Req, Resp, F>(&self, req: Req, func: F, retry: usize) -> Request<Req, Resp, F>
     where
         Req: Clone + 'static,
@@ -160,9 +233,20 @@ impl LeaderClient {
 
     /// Re-establishes connection with PD leader in asynchronized fashion.
     pub async fn reconnect(&self) -> Result<()> {
+        self.reconnect_inner(false).await
+    }
+
+    /// Re-establishes connection with PD leader immediately, bypassing the reconnect backoff
+    /// throttle used by the periodic update loop. Used by callers that proactively detected a
+    /// leader change and want to fail over without waiting for the next RPC to fail first.
+    pub async fn force_reconnect(&self) -> Result<()> {
+        self.reconnect_inner(true).await
+    }
+
+    async fn reconnect_inner(&self, force: bool) -> Result<()> {
         let (future, start) = {
             let inner = self.inner.rl();
-            if inner.last_update.elapsed() < Duration::from_secs(RECONNECT_INTERVAL_SEC) {
+            if !force && inner.last_update.elapsed() < inner.backoff.interval() {
                 // Avoid unnecessary updating.
                 return Ok(());
             }
@@ -179,7 +263,15 @@ impl LeaderClient {
             )
         };
 
-        let (client, members) = future.await?;
+        let (client, members) = match future.await {
+            Ok(result) => result,
+            Err(e) => {
+                let mut inner = self.inner.wl();
+                inner.last_update = Instant::now();
+                inner.backoff.step();
+                return Err(e);
+            }
+        };
         fail_point!("leader_client_reconnect");
 
         {
@@ -202,9 +294,22 @@ impl LeaderClient {
             inner.client_stub = client;
             inner.members = members;
             inner.last_update = Instant::now();
+            inner.backoff.reset();
             if let Some(ref on_reconnect) = inner.on_reconnect {
                 on_reconnect();
             }
+            if !inner.leader_change_listeners.is_empty() {
+                let leader_addr = inner
+                    .members
+                    .get_leader()
+                    .get_client_urls()
+                    .get(0)
+                    .cloned()
+                    .unwrap_or_default();
+                for listener in &inner.leader_change_listeners {
+                    listener(&leader_addr);
+                }
+            }
         }
         warn!("updating PD client done"; "spend" => ?start.elapsed());
         Ok(())
@@ -297,6 +402,9 @@ where
             Ok(_) => true,
             // Error::Incompatible is returned by response header from PD, no need to retry
             Err(Error::Incompatible) => true,
+            // The caller dropped the future before it resolved; the request was never actually
+            // rejected by PD, so retrying (and reconnecting to a new leader) would be pointless.
+            Err(Error::Canceled(_)) => true,
             Err(err) => {
                 error!(?err; "request failed, retry");
                 false
@@ -337,7 +445,7 @@ where
             // which may hold the read lock and wait for PD client thread completing the request
             // and the PD client thread which may block on acquiring the write lock.
             let client_stub = client.inner.rl().client_stub.clone();
-            func(&client_stub).map_err(Error::Grpc)
+            func(&client_stub).map_err(Error::from)
         };
         match ret {
             Ok(r) => {
@@ -384,11 +492,11 @@ pub fn validate_endpoints(
         let cid = resp.get_header().get_cluster_id();
         if let Some(sample) = cluster_id {
             if sample != cid {
-                return Err(box_err!(
-                    "PD response cluster_id mismatch, want {}, got {}",
-                    sample,
-                    cid
-                ));
+                return Err(Error::EndpointClusterMismatch {
+                    expected: sample,
+                    actual: cid,
+                    endpoint: ep.clone(),
+                });
             }
         } else {
             cluster_id = Some(cid);
@@ -501,8 +609,86 @@ pub fn check_resp_header(header: &ResponseHeader) -> Result<()> {
         ErrorType::NotBootstrapped => Err(Error::ClusterNotBootstrapped(header.get_cluster_id())),
         ErrorType::IncompatibleVersion => Err(Error::Incompatible),
         ErrorType::StoreTombstone => Err(Error::StoreTombstone(err.get_message().to_owned())),
-        ErrorType::RegionNotFound => Err(Error::RegionNotFound(vec![])),
-        ErrorType::Unknown => Err(box_err!(err.get_message())),
+        ErrorType::RegionNotFound => Err(Error::RegionNotFound {
+            key: vec![],
+            epoch: None,
+        }),
+        ErrorType::Unknown => {
+            let message = err.get_message();
+            if is_pd_in_maintenance(message) {
+                return Err(Error::PdInMaintenance(message.to_owned()));
+            }
+            match store_limit_exceeded_store_id(message) {
+                Some(store_id) => Err(Error::StoreLimitExceeded { store_id }),
+                None => Err(box_err!(message)),
+            }
+        }
         ErrorType::Ok => Ok(()),
     }
 }
+
+/// PD reports a store hitting its operator limit as a generic `Unknown` error rather than a
+/// dedicated `ErrorType`, so pick it out of the message text instead. Recognized so that
+/// `scatter_region`/`ask_batch_split` can hand callers a typed `Error::StoreLimitExceeded`
+/// (letting a scheduler pick a different store) instead of an opaque `Error::Other`.
+fn store_limit_exceeded_store_id(message: &str) -> Option<u64> {
+    if !message.contains("exceeds the limit") && !message.contains("exceed the limit") {
+        return None;
+    }
+    let after_store = message.split("store ").nth(1)?;
+    let digits: String = after_store.chars().take_while(char::is_ascii_digit).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// PD reports rejecting a write while it is in a read-only maintenance state (e.g. mid-upgrade)
+/// as a generic `Unknown` error too, rather than a dedicated `ErrorType`, so this is recognized
+/// from the message text the same way `store_limit_exceeded_store_id` is. Only write-path RPCs
+/// are ever rejected this way; PD keeps serving reads while in maintenance, so this never fires
+/// for a read call's response.
+fn is_pd_in_maintenance(message: &str) -> bool {
+    message.contains("read-only") || message.contains("maintenance")
+}
+
+/// The vendored `pdpb` in this tree has no dedicated field on `StoreStats` for a per-store
+/// "slow score", so it rides along in the `cpu_usages` `RecordPair` list under this reserved key,
+/// the same extensible named-metric bag `store_heartbeat` callers already use for real CPU usage
+/// entries. A real store's own usage entries are keyed by thread name, so this can't collide.
+const SLOW_SCORE_STAT_KEY: &str = "tikv_store_slow_score";
+
+/// Stashes `score` into `stats` for the next `store_heartbeat` call to report, see
+/// `SLOW_SCORE_STAT_KEY`.
+pub fn set_store_slow_score(stats: &mut StoreStats, score: u64) {
+    let mut pair = RecordPair::default();
+    pair.set_key(SLOW_SCORE_STAT_KEY.to_owned());
+    pair.set_value(score);
+    stats.mut_cpu_usages().push(pair);
+}
+
+/// Reads back the slow score stashed by `set_store_slow_score`, defaulting to `0` (not slow) for
+/// a store that has never reported one.
+pub fn get_store_slow_score(stats: &StoreStats) -> u64 {
+    stats
+        .get_cpu_usages()
+        .iter()
+        .find(|p| p.get_key() == SLOW_SCORE_STAT_KEY)
+        .map(RecordPair::get_value)
+        .unwrap_or(0)
+}
+
+/// Blocks on `fut`, but only for up to `timeout`, returning `Error::Timeout` instead of hanging
+/// forever the way a bare `.wait()` would if PD never answers. Intended for synchronous call
+/// sites (e.g. tests, or code that isn't itself running inside a future) that would otherwise be
+/// at PD's mercy for how long they block.
+pub fn wait_with_timeout<T: Send + 'static>(fut: PdFuture<T>, timeout: Duration) -> Result<T> {
+    let timeout_message = format!("request did not complete within {:?}", timeout);
+    let deadline = GLOBAL_TIMER_HANDLE
+        .delay(Instant::now() + timeout)
+        .then(move |_| Err(Error::Timeout(timeout_message)));
+    fut.select(deadline)
+        .map(|(item, _)| item)
+        .map_err(|(err, _)| err)
+        .wait()
+}
@@ -1212,6 +1212,15 @@ impl ApplyDelegate {
 
         let mut ranges = vec![];
         let mut ssts = vec![];
+
+        // A raft command can carry several `IngestSst` sub-requests (e.g. one per CF). Validate
+        // and ingest all of them together, in `default`/`lock`-before-`write` order, before
+        // processing the command's other sub-requests, so the physical rocksdb ingest order never
+        // depends on the order the sub-requests happen to arrive in.
+        let mut ingest_sst_responses = self
+            .handle_ingest_sst_batch(&ctx.importer, &ctx.engine, requests, &mut ssts)?
+            .into_iter();
+
         for req in requests {
             let cmd_type = req.get_cmd_type();
             let mut resp = match cmd_type {
@@ -1220,9 +1229,7 @@ impl ApplyDelegate {
                 CmdType::DeleteRange => {
                     self.handle_delete_range(&ctx.engine, req, &mut ranges, ctx.use_delete_range)
                 }
-                CmdType::IngestSst => {
-                    self.handle_ingest_sst(&ctx.importer, &ctx.engine, req, &mut ssts)
-                }
+                CmdType::IngestSst => Ok(ingest_sst_responses.next().unwrap()),
                 // Readonly commands are handled in raftstore directly.
                 // Don't panic here in case there are old entries need to be applied.
                 // It's also safe to skip them here, because a restart must have happened,
@@ -1422,36 +1429,52 @@ impl ApplyDelegate {
         Ok(resp)
     }
 
-    fn handle_ingest_sst(
+    /// Validates and ingests every `IngestSst` sub-request found in `requests`, ordering the
+    /// batch so `default` (and `lock`) are ingested before `write` (see
+    /// `SSTImporter::ingest_batch`), regardless of the order the sub-requests appear in
+    /// `requests`. Returns one `Response` per `IngestSst` sub-request, in the same relative
+    /// order those sub-requests appear in `requests`.
+    fn handle_ingest_sst_batch(
         &mut self,
         importer: &Arc<SSTImporter>,
         engine: &RocksEngine,
-        req: &Request,
+        requests: &[Request],
         ssts: &mut Vec<SstMeta>,
-    ) -> Result<Response> {
-        let sst = req.get_ingest_sst().get_sst();
-
-        if let Err(e) = check_sst_for_ingestion(sst, &self.region) {
-            error!(?e;
-                 "ingest fail";
-                 "region_id" => self.region_id(),
-                 "peer_id" => self.id(),
-                 "sst" => ?sst,
-                 "region" => ?&self.region,
-            );
-            // This file is not valid, we can delete it here.
-            let _ = importer.delete(sst);
-            return Err(e);
+    ) -> Result<Vec<Response>> {
+        let ingest_reqs: Vec<&Request> = requests
+            .iter()
+            .filter(|req| req.get_cmd_type() == CmdType::IngestSst)
+            .collect();
+        if ingest_reqs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut metas = Vec::with_capacity(ingest_reqs.len());
+        for req in &ingest_reqs {
+            let sst = req.get_ingest_sst().get_sst();
+            if let Err(e) = check_sst_for_ingestion(sst, &self.region) {
+                error!(?e;
+                     "ingest fail";
+                     "region_id" => self.region_id(),
+                     "peer_id" => self.id(),
+                     "sst" => ?sst,
+                     "region" => ?&self.region,
+                );
+                // This file is not valid, we can delete it here.
+                let _ = importer.delete(sst);
+                return Err(e);
+            }
+            metas.push(sst.clone());
         }
 
-        importer.ingest(sst, engine).unwrap_or_else(|e| {
-            // If this failed, it means that the file is corrupted or something
-            // is wrong with the engine, but we can do nothing about that.
-            panic!("{} ingest {:?}: {:?}", self.tag, sst, e);
+        importer.ingest_batch(&metas, engine).unwrap_or_else(|e| {
+            // If this failed, it means that some file is corrupted or something is wrong with
+            // the engine, but we can do nothing about that.
+            panic!("{} ingest {:?}: {:?}", self.tag, metas, e);
         });
 
-        ssts.push(sst.clone());
-        Ok(Response::default())
+        ssts.extend(metas);
+        Ok(ingest_reqs.iter().map(|_| Response::default()).collect())
     }
 }
 
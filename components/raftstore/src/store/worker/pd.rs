@@ -41,7 +41,7 @@ use tikv_util::worker::{FutureRunnable as Runnable, FutureScheduler as Scheduler
 
 type RecordPairVec = Vec<pdpb::RecordPair>;
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct FlowStatistics {
     pub read_keys: usize,
     pub read_bytes: usize,
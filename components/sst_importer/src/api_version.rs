@@ -0,0 +1,45 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+//! Guards against ingesting an SST built for one storage API version into a store running a
+//! different, incompatible one (e.g. a V1, non-keyspace-prefixed SST ingested into a V2 store).
+//!
+//! The vendored `import_sstpb::SstMeta` in this tree predates an API-version field, so callers
+//! must supply `sst_version` out of band rather than reading it off `meta`, the same seam
+//! `crate::checksum` uses for its algorithm selector.
+
+use crate::{Error, Result};
+
+/// Checks that an SST built for `sst_version` is safe to ingest into a store running
+/// `store_version`. Today this only rejects a strict mismatch; it does not attempt to reason
+/// about forward- or backward-compatible version ranges.
+pub fn check_api_version(sst_version: u32, store_version: u32) -> Result<()> {
+    if sst_version != store_version {
+        return Err(Error::ApiVersionMismatch {
+            sst_version,
+            store_version,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_api_version_match() {
+        check_api_version(1, 1).unwrap();
+        check_api_version(2, 2).unwrap();
+    }
+
+    #[test]
+    fn test_check_api_version_rejects_v1_sst_on_v2_store() {
+        match check_api_version(1, 2) {
+            Err(Error::ApiVersionMismatch {
+                sst_version: 1,
+                store_version: 2,
+            }) => {}
+            other => panic!("expect ApiVersionMismatch, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+//! Pluggable checksum algorithms used to verify SST content on download and ingest.
+//!
+//! The vendored `import_sstpb::SstMeta` in this tree predates a checksum-algorithm field, so
+//! callers select the algorithm out of band instead of reading it off `meta`; `SSTImporter`
+//! defaults every caller to [`ChecksumAlgorithm::Crc32`] to keep today's behavior unchanged.
+//! Once the field lands upstream, callers should decode it with [`ChecksumAlgorithm::from_u8`]
+//! instead of hardcoding the default.
+
+use crate::{Error, Result};
+
+/// Checksum algorithms supported when verifying SST content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Crc32,
+    Crc32C,
+    XxHash64,
+}
+
+impl ChecksumAlgorithm {
+    /// Decodes an algorithm selector as it would arrive over the wire. Unknown values are
+    /// reported as `Error::BadFormat` rather than silently falling back to a default.
+    pub fn from_u8(value: u8) -> Result<ChecksumAlgorithm> {
+        match value {
+            0 => Ok(ChecksumAlgorithm::Crc32),
+            1 => Ok(ChecksumAlgorithm::Crc32C),
+            2 => Ok(ChecksumAlgorithm::XxHash64),
+            _ => Err(Error::BadFormat(format!(
+                "unknown checksum algorithm {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// A streaming checksum digest over one of the [`ChecksumAlgorithm`] variants.
+pub enum ChecksumDigest {
+    Crc32(crc32fast::Hasher),
+    Crc32C(u32),
+    XxHash64(xxhash_rust::xxh3::Xxh3),
+}
+
+impl ChecksumDigest {
+    pub fn new(algorithm: ChecksumAlgorithm) -> ChecksumDigest {
+        match algorithm {
+            ChecksumAlgorithm::Crc32 => ChecksumDigest::Crc32(crc32fast::Hasher::new()),
+            ChecksumAlgorithm::Crc32C => ChecksumDigest::Crc32C(0),
+            ChecksumAlgorithm::XxHash64 => ChecksumDigest::XxHash64(xxhash_rust::xxh3::Xxh3::new()),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            ChecksumDigest::Crc32(hasher) => hasher.update(data),
+            ChecksumDigest::Crc32C(crc) => *crc = crc32c::crc32c_append(*crc, data),
+            ChecksumDigest::XxHash64(hasher) => hasher.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> u64 {
+        match self {
+            ChecksumDigest::Crc32(hasher) => u64::from(hasher.finalize()),
+            ChecksumDigest::Crc32C(crc) => u64::from(crc),
+            ChecksumDigest::XxHash64(hasher) => hasher.digest(),
+        }
+    }
+}
+
+/// Computes `data`'s checksum under `algorithm` and compares it against `expect`, returning
+/// `Error::ChecksumMismatch` on disagreement.
+pub fn verify_checksum(data: &[u8], algorithm: ChecksumAlgorithm, expect: u64) -> Result<()> {
+    let mut digest = ChecksumDigest::new(algorithm);
+    digest.update(data);
+    let got = digest.finalize();
+    if got != expect {
+        return Err(Error::ChecksumMismatch {
+            algorithm,
+            expect,
+            got,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checksum(data: &[u8], algorithm: ChecksumAlgorithm) -> u64 {
+        let mut digest = ChecksumDigest::new(algorithm);
+        digest.update(data);
+        digest.finalize()
+    }
+
+    #[test]
+    fn test_verify_checksum_all_algorithms() {
+        let data = b"hello checksum world";
+        for algorithm in &[
+            ChecksumAlgorithm::Crc32,
+            ChecksumAlgorithm::Crc32C,
+            ChecksumAlgorithm::XxHash64,
+        ] {
+            let expect = checksum(data, *algorithm);
+            verify_checksum(data, *algorithm, expect).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verify_checksum_mismatch() {
+        let data = b"hello checksum world";
+        let expect = checksum(data, ChecksumAlgorithm::Crc32) + 1;
+        match verify_checksum(data, ChecksumAlgorithm::Crc32, expect) {
+            Err(Error::ChecksumMismatch { .. }) => {}
+            other => panic!("expect ChecksumMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_u8_bad_format() {
+        assert_eq!(ChecksumAlgorithm::from_u8(0).unwrap(), ChecksumAlgorithm::Crc32);
+        assert_eq!(ChecksumAlgorithm::from_u8(1).unwrap(), ChecksumAlgorithm::Crc32C);
+        assert_eq!(ChecksumAlgorithm::from_u8(2).unwrap(), ChecksumAlgorithm::XxHash64);
+        match ChecksumAlgorithm::from_u8(3) {
+            Err(Error::BadFormat(_)) => {}
+            other => panic!("expect BadFormat, got {:?}", other),
+        }
+    }
+}
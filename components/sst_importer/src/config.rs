@@ -3,12 +3,23 @@
 use std::error::Error;
 use std::result::Result;
 
+use tikv_util::config::ReadableDuration;
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(default)]
 #[serde(rename_all = "kebab-case")]
 pub struct Config {
     pub num_threads: usize,
     pub stream_channel_window: usize,
+    /// Bounds the on-disk content cache of downloaded SSTs (see `ContentCache`) to this many
+    /// bytes. `0` means unlimited.
+    pub cache_capacity_bytes: u64,
+    /// Scrub interval for pending SSTs (see `Scrubber::spawn`). Nothing in TiKV reads this
+    /// setting or `scrub_auto_delete`: no `Scrubber` is started from the import config.
+    pub scrub_interval: ReadableDuration,
+    /// Whether a pending SST the scrubber finds corrupt is deleted outright instead of being
+    /// left in `.quarantine` for inspection.
+    pub scrub_auto_delete: bool,
 }
 
 impl Default for Config {
@@ -16,6 +27,9 @@ impl Default for Config {
         Config {
             num_threads: 8,
             stream_channel_window: 128,
+            cache_capacity_bytes: 0,
+            scrub_interval: ReadableDuration::secs(0),
+            scrub_auto_delete: false,
         }
     }
 }
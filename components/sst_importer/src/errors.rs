@@ -25,7 +25,7 @@ pub fn error_inc(err: &Error) {
         Error::EngineTraits(..) => "engine_traits",
         Error::ParseIntError(..) => "parse_int",
         Error::FileExists(..) => "file_exists",
-        Error::FileCorrupted(..) => "file_corrupt",
+        Error::FileCorrupted { .. } => "file_corrupt",
         Error::InvalidSSTPath(..) => "invalid_sst",
         Error::Engine(..) => "engine",
         Error::CannotReadExternalStorage(..) => "read_external_storage",
@@ -33,6 +33,12 @@ pub fn error_inc(err: &Error) {
         Error::BadFormat(..) => "bad_format",
         Error::Encryption(..) => "encryption",
         Error::CodecError(..) => "codec",
+        Error::RegionNotFound(..) => "region_not_found",
+        Error::ChecksumMismatch { .. } => "checksum_mismatch",
+        Error::ApiVersionMismatch { .. } => "api_version_mismatch",
+        Error::DiskSpaceNotEnough { .. } => "disk_space_not_enough",
+        Error::EmptySst(..) => "empty_sst",
+        Error::DiskQuotaExceeded { .. } => "disk_quota",
         _ => return,
     };
     IMPORTER_ERROR_VEC.with_label_values(&[label]).inc();
@@ -78,8 +84,13 @@ quick_error! {
         FileExists(path: PathBuf) {
             display("File {:?} exists", path)
         }
-        FileCorrupted(path: PathBuf, reason: String) {
-            display("File {:?} corrupted: {}", path, reason)
+        FileCorrupted { path: PathBuf, offset: Option<u64>, reason: String } {
+            display(
+                "File {:?}{} corrupted: {}",
+                path,
+                offset.map_or_else(String::new, |offset| format!(" at offset {}", offset)),
+                reason,
+            )
         }
         InvalidSSTPath(path: PathBuf) {
             display("Invalid SST path {:?}", path)
@@ -88,9 +99,12 @@ quick_error! {
         Engine(err: Box<dyn StdError + Send + Sync + 'static>) {
             display("{}", err)
         }
-        CannotReadExternalStorage(url: String, name: String, local_path: PathBuf, err: IoError) {
+        CannotReadExternalStorage(url: String, name: String, local_path: PathBuf, err: IoError, attempts: usize) {
             cause(err)
-            display("Cannot read {}/{} into {}: {}", url, name, local_path.display(), err)
+            display(
+                "Cannot read {}/{} into {} after {} attempts: {}",
+                url, name, local_path.display(), attempts, err,
+            )
         }
         WrongKeyPrefix(what: &'static str, key: Vec<u8>, prefix: Vec<u8>) {
             display("\
@@ -113,6 +127,37 @@ quick_error! {
             cause(err)
             description(err.description())
         }
+        RegionNotFound(region_id: u64) {
+            display("Region {} not found, cannot ingest", region_id)
+        }
+        ChecksumMismatch { algorithm: crate::checksum::ChecksumAlgorithm, expect: u64, got: u64 } {
+            display(
+                "checksum mismatch under {:?}: expect {}, got {}",
+                algorithm, expect, got,
+            )
+        }
+        // Not retryable: re-ingesting the same SST against the same store will fail identically.
+        ApiVersionMismatch { sst_version: u32, store_version: u32 } {
+            display(
+                "SST built for API version {} cannot be ingested into a store on API version {}",
+                sst_version, store_version,
+            )
+        }
+        DiskSpaceNotEnough { needed_bytes: u64, available_bytes: u64 } {
+            display(
+                "cannot make room for {} more bytes in the import cache, only {} bytes free after eviction",
+                needed_bytes, available_bytes,
+            )
+        }
+        EmptySst(path: PathBuf) {
+            display("SST {:?} contains no keys, ingesting it would be a silent no-op", path)
+        }
+        DiskQuotaExceeded { path: PathBuf, used: u64, limit: u64 } {
+            display(
+                "writing to {:?} would exceed the import directory's disk quota: {} bytes used, {} bytes allowed",
+                path, used, limit,
+            )
+        }
     }
 }
 
@@ -126,6 +171,28 @@ impl From<Error> for import_sstpb::Error {
     }
 }
 
+impl Error {
+    /// Returns whether retrying the operation that produced this error might succeed.
+    ///
+    /// `Io`, `Grpc`, `CannotReadExternalStorage`, and `Future` are treated as transient, since
+    /// they typically stem from a flaky network or a momentarily unavailable peer. Everything
+    /// else, including any variant added after this method was written, is treated as permanent
+    /// so callers fail fast by default instead of retrying an error that can never succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Io(_)
+            | Error::Grpc(_)
+            | Error::CannotReadExternalStorage(..)
+            | Error::Future(_) => true,
+            Error::FileCorrupted { .. }
+            | Error::InvalidSSTPath(_)
+            | Error::WrongKeyPrefix(..)
+            | Error::BadFormat(_) => false,
+            _ => false,
+        }
+    }
+}
+
 impl ErrorCodeExt for Error {
     fn error_code(&self) -> ErrorCode {
         match self {
@@ -137,17 +204,197 @@ impl ErrorCodeExt for Error {
             Error::EngineTraits(e) => e.error_code(),
             Error::ParseIntError(_) => error_code::sst_importer::PARSE_INT_ERROR,
             Error::FileExists(_) => error_code::sst_importer::FILE_EXISTS,
-            Error::FileCorrupted(_, _) => error_code::sst_importer::FILE_CORRUPTED,
+            Error::FileCorrupted { .. } => error_code::sst_importer::FILE_CORRUPTED,
             Error::InvalidSSTPath(_) => error_code::sst_importer::INVALID_SST_PATH,
             Error::InvalidChunk => error_code::sst_importer::INVALID_CHUNK,
             Error::Engine(_) => error_code::sst_importer::ENGINE,
-            Error::CannotReadExternalStorage(_, _, _, _) => {
+            Error::CannotReadExternalStorage(..) => {
                 error_code::sst_importer::CANNOT_READ_EXTERNAL_STORAGE
             }
             Error::WrongKeyPrefix(_, _, _) => error_code::sst_importer::WRONG_KEY_PREFIX,
             Error::BadFormat(_) => error_code::sst_importer::BAD_FORMAT,
             Error::Encryption(e) => e.error_code(),
             Error::CodecError(e) => e.error_code(),
+            Error::RegionNotFound(_) => error_code::sst_importer::REGION_NOT_FOUND,
+            Error::ChecksumMismatch { .. } => error_code::sst_importer::CHECKSUM_MISMATCH,
+            Error::ApiVersionMismatch { .. } => error_code::sst_importer::API_VERSION_MISMATCH,
+            Error::DiskSpaceNotEnough { .. } => error_code::sst_importer::DISK_SPACE_NOT_ENOUGH,
+            Error::EmptySst(_) => error_code::sst_importer::EMPTY_SST,
+            Error::DiskQuotaExceeded { .. } => error_code::sst_importer::DISK_QUOTA_EXCEEDED,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_not_found() {
+        let err = Error::RegionNotFound(7);
+        assert_eq!(err.to_string(), "Region 7 not found, cannot ingest");
+        assert_eq!(err.error_code(), error_code::sst_importer::REGION_NOT_FOUND);
+
+        let before = IMPORTER_ERROR_VEC
+            .with_label_values(&["region_not_found"])
+            .get();
+        error_inc(&err);
+        let after = IMPORTER_ERROR_VEC
+            .with_label_values(&["region_not_found"])
+            .get();
+        assert_eq!(after, before + 1);
+    }
+
+    #[test]
+    fn test_api_version_mismatch() {
+        let err = Error::ApiVersionMismatch {
+            sst_version: 1,
+            store_version: 2,
+        };
+        assert_eq!(
+            err.to_string(),
+            "SST built for API version 1 cannot be ingested into a store on API version 2"
+        );
+        assert_eq!(
+            err.error_code(),
+            error_code::sst_importer::API_VERSION_MISMATCH
+        );
+
+        let before = IMPORTER_ERROR_VEC
+            .with_label_values(&["api_version_mismatch"])
+            .get();
+        error_inc(&err);
+        let after = IMPORTER_ERROR_VEC
+            .with_label_values(&["api_version_mismatch"])
+            .get();
+        assert_eq!(after, before + 1);
+    }
+
+    #[test]
+    fn test_empty_sst() {
+        let path = PathBuf::from("/tmp/empty.sst");
+        let err = Error::EmptySst(path.clone());
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "SST {:?} contains no keys, ingesting it would be a silent no-op",
+                path
+            )
+        );
+        assert_eq!(err.error_code(), error_code::sst_importer::EMPTY_SST);
+
+        let before = IMPORTER_ERROR_VEC.with_label_values(&["empty_sst"]).get();
+        error_inc(&err);
+        let after = IMPORTER_ERROR_VEC.with_label_values(&["empty_sst"]).get();
+        assert_eq!(after, before + 1);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        use futures::Future;
+        use grpcio::{RpcStatus, RpcStatusCode};
+
+        let (tx, rx) = tokio_sync::oneshot::channel::<()>();
+        drop(tx);
+        let recv_err = rx.wait().unwrap_err();
+
+        let retryable = vec![
+            Error::Io(IoError::new(std::io::ErrorKind::Other, "boom")),
+            Error::Grpc(GrpcError::RpcFailure(RpcStatus::new(
+                RpcStatusCode::UNAVAILABLE,
+                None,
+            ))),
+            Error::CannotReadExternalStorage(
+                "s3://bucket".to_owned(),
+                "1.sst".to_owned(),
+                PathBuf::from("/tmp/1.sst"),
+                IoError::new(std::io::ErrorKind::Other, "boom"),
+                1,
+            ),
+            Error::Future(recv_err),
+        ];
+        for err in retryable {
+            assert!(err.is_retryable(), "{:?} should be retryable", err);
+        }
+
+        let permanent = vec![
+            Error::FileCorrupted {
+                path: PathBuf::from("/tmp/1.sst"),
+                offset: None,
+                reason: "bad crc32".to_owned(),
+            },
+            Error::InvalidSSTPath(PathBuf::from("/tmp/1.sst")),
+            Error::WrongKeyPrefix("data", b"k".to_vec(), b"p".to_vec()),
+            Error::BadFormat("bad magic".to_owned()),
+        ];
+        for err in permanent {
+            assert!(!err.is_retryable(), "{:?} should not be retryable", err);
+        }
+    }
+
+    #[test]
+    fn test_cannot_read_external_storage_display() {
+        let err = Error::CannotReadExternalStorage(
+            "s3://bucket".to_owned(),
+            "foo".to_owned(),
+            PathBuf::from("/tmp/x"),
+            IoError::new(std::io::ErrorKind::TimedOut, "connection reset"),
+            3,
+        );
+        assert_eq!(
+            err.to_string(),
+            "Cannot read s3://bucket/foo into /tmp/x after 3 attempts: connection reset"
+        );
+    }
+
+    #[test]
+    fn test_file_corrupted_display() {
+        let path = PathBuf::from("/tmp/import/1.sst");
+        let err = Error::FileCorrupted {
+            path: path.clone(),
+            offset: None,
+            reason: "crc32 1, expect 2".to_owned(),
+        };
+        assert_eq!(
+            err.to_string(),
+            format!("File {:?} corrupted: crc32 1, expect 2", path)
+        );
+
+        let err = Error::FileCorrupted {
+            path: path.clone(),
+            offset: Some(42),
+            reason: "crc32 1, expect 2".to_owned(),
+        };
+        assert_eq!(
+            err.to_string(),
+            format!("File {:?} at offset 42 corrupted: crc32 1, expect 2", path)
+        );
+    }
+
+    #[test]
+    fn test_disk_quota_exceeded() {
+        let path = PathBuf::from("/tmp/import");
+        let err = Error::DiskQuotaExceeded {
+            path: path.clone(),
+            used: 900,
+            limit: 1000,
+        };
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "writing to {:?} would exceed the import directory's disk quota: \
+                 900 bytes used, 1000 bytes allowed",
+                path
+            )
+        );
+        assert_eq!(
+            err.error_code(),
+            error_code::sst_importer::DISK_QUOTA_EXCEEDED
+        );
+
+        let before = IMPORTER_ERROR_VEC.with_label_values(&["disk_quota"]).get();
+        error_inc(&err);
+        let after = IMPORTER_ERROR_VEC.with_label_values(&["disk_quota"]).get();
+        assert_eq!(after, before + 1);
+    }
+}
@@ -14,6 +14,8 @@ extern crate tikv_util;
 #[allow(unused_extern_crates)]
 extern crate tikv_alloc;
 
+pub mod api_version;
+pub mod checksum;
 mod config;
 mod errors;
 pub mod metrics;
@@ -21,9 +23,13 @@ mod util;
 #[macro_use]
 pub mod service;
 pub mod import_mode;
+pub mod scrub;
 pub mod sst_importer;
 
+pub use self::api_version::check_api_version;
+pub use self::checksum::{ChecksumAlgorithm, ChecksumDigest};
 pub use self::config::Config;
 pub use self::errors::{error_inc, Error, Result};
-pub use self::sst_importer::{SSTImporter, SSTWriter};
+pub use self::scrub::Scrubber;
+pub use self::sst_importer::{ScrubFinding, SSTImporter, SSTWriter};
 pub use self::util::prepare_sst_for_ingestion;
@@ -60,4 +60,10 @@ lazy_static! {
         &["error"]
     )
     .unwrap();
+    pub static ref IMPORTER_INGEST_PREP_COUNTER: IntCounterVec = register_int_counter_vec!(
+        "tikv_import_ingest_prep_counter",
+        "Total number of times each strategy was used to stage an SST file for ingestion",
+        &["type"]
+    )
+    .unwrap();
 }
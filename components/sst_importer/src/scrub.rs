@@ -0,0 +1,72 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+//! A background job that periodically re-checksums pending SSTs, catching bit rot before a
+//! corrupt file is ingested. See `SSTImporter::scrub_pending_ssts` for the actual scrub pass;
+//! this only owns the timer loop around it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::sst_importer::ScrubFinding;
+use crate::SSTImporter;
+
+/// Runs `SSTImporter::scrub_pending_ssts` on `interval`, forwarding every finding to `report`
+/// until stopped. Dropping the `Scrubber` stops it, same as calling `stop()` explicitly.
+pub struct Scrubber {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Scrubber {
+    pub fn spawn(
+        importer: Arc<SSTImporter>,
+        interval: Duration,
+        auto_delete: bool,
+        report: Sender<ScrubFinding>,
+    ) -> Scrubber {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_loop = stop.clone();
+        let handle = thread::Builder::new()
+            .name(thd_name!("sst-scrubber"))
+            .spawn(move || {
+                while !stop_loop.load(Ordering::Relaxed) {
+                    thread::sleep(interval);
+                    if stop_loop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    match importer.scrub_pending_ssts(auto_delete) {
+                        Ok(findings) => {
+                            for finding in findings {
+                                warn!("quarantined corrupt pending sst"; "finding" => ?finding);
+                                // The receiver may already be gone (e.g. its owner shut down);
+                                // there's nothing else useful to do with a finding at that point.
+                                let _ = report.send(finding);
+                            }
+                        }
+                        Err(e) => error!(%e; "sst scrub pass failed"),
+                    }
+                }
+            })
+            .unwrap();
+        Scrubber {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Scrubber {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -1,13 +1,14 @@
 // Copyright 2018 TiKV Project Authors. Licensed under Apache-2.0.
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Write};
 use std::marker::Unpin;
 use std::ops::Bound;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use futures_util::io::{AsyncRead, AsyncReadExt};
@@ -20,13 +21,13 @@ use encryption::DataKeyManager;
 use engine_rocks::{encryption::get_env, RocksSstReader};
 use engine_traits::{
     EncryptionKeyManager, IngestExternalFileOptions, Iterator, KvEngine, SeekKey, SstExt,
-    SstReader, SstWriter, CF_DEFAULT, CF_WRITE,
+    SstReader, SstWriter, CF_DEFAULT, CF_LOCK, CF_WRITE,
 };
 use external_storage::{block_on_external_io, create_storage, url_of_backend, READ_BUF_SIZE};
 use tikv_util::time::Limiter;
 use txn_types::{is_short_value, Key, TimeStamp, Write as KvWrite, WriteRef, WriteType};
 
-use super::{Error, Result};
+use super::{ChecksumAlgorithm, ChecksumDigest, Error, Result};
 use crate::metrics::*;
 
 /// SSTImporter manages SST files that are waiting for ingesting.
@@ -39,9 +40,21 @@ impl SSTImporter {
     pub fn new<P: AsRef<Path>>(
         root: P,
         key_manager: Option<Arc<DataKeyManager>>,
+    ) -> Result<SSTImporter> {
+        Self::new_with_cache_capacity(root, key_manager, 0)
+    }
+
+    /// Like `new`, but bounds the shared content cache (see `ContentCache`) to at most
+    /// `cache_capacity_bytes` of downloaded SST data. A capacity of `0` means unlimited, matching
+    /// `new`'s behavior. Once the cache approaches the limit, already-ingested and
+    /// least-recently-used entries are evicted to make room before a new download is cached.
+    pub fn new_with_cache_capacity<P: AsRef<Path>>(
+        root: P,
+        key_manager: Option<Arc<DataKeyManager>>,
+        cache_capacity_bytes: u64,
     ) -> Result<SSTImporter> {
         Ok(SSTImporter {
-            dir: ImportDir::new(root)?,
+            dir: ImportDir::new(root, cache_capacity_bytes)?,
             key_manager,
         })
     }
@@ -78,7 +91,21 @@ impl SSTImporter {
     }
 
     pub fn ingest<E: KvEngine>(&self, meta: &SstMeta, engine: &E) -> Result<()> {
-        match self.dir.ingest(meta, engine, self.key_manager.as_ref()) {
+        self.ingest_with_allow_empty(meta, engine, false)
+    }
+
+    /// Like `ingest`, but if `allow_empty` is set, an SST containing no keys is ingested
+    /// (as a no-op) instead of being rejected with `Error::EmptySst`.
+    pub fn ingest_with_allow_empty<E: KvEngine>(
+        &self,
+        meta: &SstMeta,
+        engine: &E,
+        allow_empty: bool,
+    ) -> Result<()> {
+        match self
+            .dir
+            .ingest(meta, engine, self.key_manager.as_ref(), allow_empty)
+        {
             Ok(_) => {
                 info!("ingest"; "meta" => ?meta);
                 Ok(())
@@ -90,6 +117,20 @@ impl SSTImporter {
         }
     }
 
+    /// Ingests a batch of SSTs for the same region across multiple CFs. Unlike calling `ingest`
+    /// once per meta in caller-supplied order, this orders the batch so `default` (and `lock`) are
+    /// ingested before `write`, since a reader that observes a `write` record before its
+    /// corresponding `default` value would see a dangling pointer. SSTs within the same CF keep
+    /// their relative order.
+    pub fn ingest_batch<E: KvEngine>(&self, metas: &[SstMeta], engine: &E) -> Result<()> {
+        let mut ordered: Vec<&SstMeta> = metas.iter().collect();
+        ordered.sort_by_key(|meta| cf_ingest_order(meta.get_cf_name()));
+        for meta in ordered {
+            self.ingest(meta, engine)?;
+        }
+        Ok(())
+    }
+
     // Downloads an SST file from an external storage.
     //
     // This method is blocking. It performs the following transformations before
@@ -189,7 +230,7 @@ impl SSTImporter {
         let path = self.dir.join(meta)?;
         let url = url_of_backend(backend);
 
-        {
+        self.dir.download_with_cache(meta, &path.temp, || {
             // prepare to download the file from the external_storage
             let ext_storage = create_storage(backend)?;
             let mut ext_reader = ext_storage.read(name);
@@ -223,6 +264,8 @@ impl SSTImporter {
                     name.to_owned(),
                     path.temp.to_owned(),
                     e,
+                    // This read isn't retried internally, so it's always a single attempt.
+                    1,
                 )
             })?;
 
@@ -230,7 +273,8 @@ impl SSTImporter {
                 .append(true)
                 .open(&path.temp)?
                 .sync_data()?;
-        }
+            Ok(())
+        })?;
 
         // now validate the SST file.
         let path_str = path.temp.to_str().unwrap();
@@ -408,6 +452,14 @@ impl SSTImporter {
         self.dir.list_ssts()
     }
 
+    /// Re-checksums every pending (not yet ingested) SST against the checksum it was created
+    /// with, quarantining any file whose content no longer matches (or, if `auto_delete` is set,
+    /// deleting it outright) so it can't be ingested. See `Scrubber` for a background job that
+    /// calls this on an interval.
+    pub fn scrub_pending_ssts(&self, auto_delete: bool) -> Result<Vec<ScrubFinding>> {
+        self.dir.scrub_pending_ssts(auto_delete)
+    }
+
     pub fn new_writer<E: KvEngine>(
         &self,
         default: E::SstWriter,
@@ -533,23 +585,31 @@ impl<E: KvEngine> SSTWriter<E> {
 ///
 /// The file being written is stored in `$root/.temp/$file_name`. After writing
 /// is completed, the file is moved to `$root/$file_name`. The file generated
-/// from the ingestion process will be placed in `$root/.clone/$file_name`.
+/// from the ingestion process will be placed in `$root/.clone/$file_name`. A
+/// pending file that fails a `scrub_pending_ssts` checksum recheck is moved to
+/// `$root/.quarantine/$file_name` instead of being ingested.
 ///
 /// TODO: Add size and rate limit.
 pub struct ImportDir {
     root_dir: PathBuf,
     temp_dir: PathBuf,
     clone_dir: PathBuf,
+    quarantine_dir: PathBuf,
+    content_cache: ContentCache,
 }
 
 impl ImportDir {
     const TEMP_DIR: &'static str = ".temp";
     const CLONE_DIR: &'static str = ".clone";
+    const QUARANTINE_DIR: &'static str = ".quarantine";
 
-    fn new<P: AsRef<Path>>(root: P) -> Result<ImportDir> {
+    fn new<P: AsRef<Path>>(root: P, cache_capacity_bytes: u64) -> Result<ImportDir> {
         let root_dir = root.as_ref().to_owned();
         let temp_dir = root_dir.join(Self::TEMP_DIR);
         let clone_dir = root_dir.join(Self::CLONE_DIR);
+        // Unlike `temp_dir`/`clone_dir`, `quarantine_dir` holds evidence of past corruption, not
+        // in-flight scratch work, so it's never wiped on restart.
+        let quarantine_dir = root_dir.join(Self::QUARANTINE_DIR);
         if temp_dir.exists() {
             fs::remove_dir_all(&temp_dir)?;
         }
@@ -558,13 +618,71 @@ impl ImportDir {
         }
         fs::create_dir_all(&temp_dir)?;
         fs::create_dir_all(&clone_dir)?;
+        fs::create_dir_all(&quarantine_dir)?;
+        let content_cache = ContentCache::new(&root_dir, cache_capacity_bytes)?;
         Ok(ImportDir {
             root_dir,
             temp_dir,
             clone_dir,
+            quarantine_dir,
+            content_cache,
         })
     }
 
+    /// Ensures the content described by `meta` ends up at `dest`, either by hard-linking a
+    /// previous download of identical content (same crc32 and length) out of the content-
+    /// addressed cache, or by running `fetch` to produce it and then publishing the result to the
+    /// cache for next time.
+    ///
+    /// `meta.get_crc32()` is only 32 bits, so the cache key built from it is collision-prone: two
+    /// different uploads could hash to the same key. Before serving a hit, this re-verifies the
+    /// cached file's actual bytes against `meta`'s declared checksum (the same check `ingest`
+    /// does downstream) rather than trusting the key string alone; a mismatch is treated as a
+    /// miss, and the stale entry is dropped. This still can't catch a deliberately constructed
+    /// crc32 collision, since crc32 is the only checksum this tree's vendored `SstMeta` carries.
+    ///
+    /// `fetch` is expected to write its output to `dest`. Concurrent calls for identical content
+    /// share a single `fetch` via a per-key lock, so the same content is downloaded from external
+    /// storage at most once even when requested concurrently.
+    fn download_with_cache<F>(&self, meta: &SstMeta, dest: &Path, fetch: F) -> Result<()>
+    where
+        F: FnOnce() -> Result<()>,
+    {
+        let key = match ContentCache::key(meta) {
+            Some(key) => key,
+            // Without a trustworthy checksum we cannot safely dedupe, so always fetch fresh.
+            None => return fetch(),
+        };
+        let lock = self.content_cache.lock_for(&key);
+        let _guard = lock.lock().unwrap();
+
+        let cached_path = self.content_cache.path_for(&key);
+        if cached_path.exists() && self.content_cache.verify(&cached_path, meta) {
+            if fs::hard_link(&cached_path, dest).is_err() {
+                fs::copy(&cached_path, dest)?;
+            }
+            self.content_cache.touch(&key);
+            return Ok(());
+        } else if cached_path.exists() {
+            warn!(
+                "content cache entry failed checksum verification, treating as a miss";
+                "key" => %key,
+            );
+            self.content_cache.evict(&key);
+        }
+
+        fetch()?;
+
+        let size_bytes = fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+        self.content_cache.ensure_capacity(&key, size_bytes)?;
+        if let Err(e) = fs::hard_link(dest, &cached_path) {
+            warn!("failed to populate SST content cache"; "key" => %key, "err" => %e);
+        } else {
+            self.content_cache.record(&key, size_bytes);
+        }
+        Ok(())
+    }
+
     fn join(&self, meta: &SstMeta) -> Result<ImportPath> {
         let file_name = sst_meta_to_path(meta)?;
         let save_path = self.root_dir.join(&file_name);
@@ -604,12 +722,23 @@ impl ImportDir {
         meta: &SstMeta,
         engine: &E,
         key_manager: Option<&Arc<DataKeyManager>>,
+        allow_empty: bool,
     ) -> Result<()> {
         let start = Instant::now();
         let path = self.join(meta)?;
         let cf = meta.get_cf_name();
         let cf = engine.cf_handle(cf).expect("bad cf name");
         super::prepare_sst_for_ingestion(&path.save, &path.clone, key_manager)?;
+
+        if !allow_empty {
+            let clone_str = path.clone.to_str().unwrap();
+            let reader = E::SstReader::open(clone_str)?;
+            let mut iter = reader.iter();
+            if !iter.seek(SeekKey::Start)? {
+                return Err(Error::EmptySst(path.clone));
+            }
+        }
+
         let length = meta.get_length();
         let crc32 = meta.get_crc32();
         // FIXME perform validate_sst_for_ingestion after we can handle sst file size correctly.
@@ -629,6 +758,11 @@ impl ImportDir {
         IMPORTER_INGEST_DURATION
             .with_label_values(&["ingest"])
             .observe(start.elapsed().as_secs_f64());
+        // The content is now durably in the engine, so its content-cache copy (if any) is a safe,
+        // low-value eviction candidate: it only exists to dedupe a future identical download.
+        if let Some(key) = ContentCache::key(meta) {
+            self.content_cache.mark_ingested(&key);
+        }
         Ok(())
     }
 
@@ -647,6 +781,219 @@ impl ImportDir {
         }
         Ok(ssts)
     }
+
+    /// See `SSTImporter::scrub_pending_ssts`.
+    fn scrub_pending_ssts(&self, auto_delete: bool) -> Result<Vec<ScrubFinding>> {
+        let mut findings = Vec::new();
+        for meta in self.list_ssts()? {
+            let crc32 = meta.get_crc32();
+            let length = meta.get_length();
+            if crc32 == 0 || length == 0 {
+                // Same convention `ingest` uses: without an explicitly provided checksum there
+                // is nothing trustworthy to recheck it against.
+                continue;
+            }
+            let path = self.join(&meta)?;
+            let data = match fs::read(&path.save) {
+                Ok(data) => data,
+                // Ingested (and so removed) between `list_ssts` and now; not corruption, just a
+                // race with a concurrent ingest.
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let err = match crate::checksum::verify_checksum(
+                &data,
+                ChecksumAlgorithm::Crc32,
+                u64::from(crc32),
+            ) {
+                Ok(()) => continue,
+                Err(err) => err,
+            };
+            crate::error_inc(&err);
+            let quarantined_to = if auto_delete {
+                fs::remove_file(&path.save)?;
+                None
+            } else {
+                let quarantine_path = self.quarantine_dir.join(sst_meta_to_path(&meta)?);
+                fs::rename(&path.save, &quarantine_path)?;
+                Some(quarantine_path)
+            };
+            findings.push(ScrubFinding {
+                meta,
+                error: err,
+                quarantined_to,
+            });
+        }
+        Ok(findings)
+    }
+}
+
+/// One corrupt pending SST found by `SSTImporter::scrub_pending_ssts`.
+#[derive(Debug)]
+pub struct ScrubFinding {
+    pub meta: SstMeta,
+    pub error: Error,
+    /// Where the corrupt file was moved, or `None` if it was deleted outright instead.
+    pub quarantined_to: Option<PathBuf>,
+}
+
+/// Bookkeeping for a single entry in `ContentCache`, used to pick eviction candidates.
+struct CacheUsage {
+    size_bytes: u64,
+    last_used: Instant,
+    // Set once the SST this entry caches has been ingested elsewhere, meaning the cache copy is
+    // now purely a dedup convenience rather than the only copy of the data.
+    ingested: bool,
+}
+
+/// A content-addressed store of previously-downloaded SST files, keyed by their (crc32, length)
+/// checksum, so that a second download of identical content can reuse the first one's bytes
+/// instead of fetching it again from external storage.
+///
+/// The cache is bounded to `capacity_bytes` total (`0` means unlimited). When a new entry would
+/// push the cache over the limit, already-ingested entries are evicted first, then the
+/// least-recently-used remaining entries, until there's room. An entry whose per-key lock is
+/// currently held (i.e. a download or publish is in flight for it) is never evicted.
+struct ContentCache {
+    dir: PathBuf,
+    // Per-key locks so concurrent downloads of the same content coalesce onto a single fetch
+    // instead of racing to populate the cache.
+    locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    capacity_bytes: u64,
+    usage: Mutex<HashMap<String, CacheUsage>>,
+}
+
+impl ContentCache {
+    const CACHE_DIR: &'static str = ".content-cache";
+
+    fn new(root_dir: &Path, capacity_bytes: u64) -> Result<ContentCache> {
+        let dir = root_dir.join(Self::CACHE_DIR);
+        fs::create_dir_all(&dir)?;
+        Ok(ContentCache {
+            dir,
+            locks: Mutex::new(HashMap::new()),
+            capacity_bytes,
+            usage: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn key(meta: &SstMeta) -> Option<String> {
+        let crc32 = meta.get_crc32();
+        let length = meta.get_length();
+        if crc32 == 0 || length == 0 {
+            return None;
+        }
+        Some(format!("{:08x}-{}", crc32, length))
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+
+    fn lock_for(&self, key: &str) -> Arc<Mutex<()>> {
+        let mut locks = self.locks.lock().unwrap();
+        Arc::clone(
+            locks
+                .entry(key.to_owned())
+                .or_insert_with(|| Arc::new(Mutex::new(()))),
+        )
+    }
+
+    /// Refreshes `key`'s recency, for cache hits.
+    fn touch(&self, key: &str) {
+        if let Some(entry) = self.usage.lock().unwrap().get_mut(key) {
+            entry.last_used = Instant::now();
+        }
+    }
+
+    /// Records a newly-published cache entry of `size_bytes`.
+    fn record(&self, key: &str, size_bytes: u64) {
+        self.usage.lock().unwrap().insert(
+            key.to_owned(),
+            CacheUsage {
+                size_bytes,
+                last_used: Instant::now(),
+                ingested: false,
+            },
+        );
+    }
+
+    fn mark_ingested(&self, key: &str) {
+        if let Some(entry) = self.usage.lock().unwrap().get_mut(key) {
+            entry.ingested = true;
+        }
+    }
+
+    /// Checks that the file at `cached_path` actually has the content `meta` declares, rather
+    /// than trusting the (collision-prone) key it's stored under. Returns `false` on any read or
+    /// checksum error, treating both the same as "not a match".
+    fn verify(&self, cached_path: &Path, meta: &SstMeta) -> bool {
+        let data = match fs::read(cached_path) {
+            Ok(data) => data,
+            Err(_) => return false,
+        };
+        if data.len() as u64 != meta.get_length() {
+            return false;
+        }
+        crate::checksum::verify_checksum(
+            &data,
+            ChecksumAlgorithm::Crc32,
+            u64::from(meta.get_crc32()),
+        )
+        .is_ok()
+    }
+
+    /// Drops a stale or corrupt entry from both the usage table and disk.
+    fn evict(&self, key: &str) {
+        self.usage.lock().unwrap().remove(key);
+        if let Err(e) = fs::remove_file(self.path_for(key)) {
+            warn!("failed to remove stale SST content cache entry"; "key" => %key, "err" => %e);
+        }
+    }
+
+    /// Evicts already-ingested and then least-recently-used entries, skipping any entry whose
+    /// per-key lock is currently held, until there's room for `incoming_bytes` more. Returns
+    /// `Error::DiskSpaceNotEnough` if eviction alone can't make enough room.
+    fn ensure_capacity(&self, incoming_key: &str, incoming_bytes: u64) -> Result<()> {
+        if self.capacity_bytes == 0 {
+            return Ok(());
+        }
+        loop {
+            let mut usage = self.usage.lock().unwrap();
+            let total: u64 = usage.values().map(|e| e.size_bytes).sum();
+            if total + incoming_bytes <= self.capacity_bytes {
+                return Ok(());
+            }
+
+            let locks = self.locks.lock().unwrap();
+            let victim = usage
+                .iter()
+                .filter(|(k, _)| {
+                    k.as_str() != incoming_key
+                        && locks.get(*k).map_or(true, |lock| lock.try_lock().is_ok())
+                })
+                .min_by_key(|(_, e)| (!e.ingested, e.last_used))
+                .map(|(k, _)| k.clone());
+            drop(locks);
+
+            let victim = match victim {
+                Some(key) => key,
+                None => {
+                    return Err(Error::DiskSpaceNotEnough {
+                        needed_bytes: incoming_bytes,
+                        available_bytes: self.capacity_bytes.saturating_sub(total),
+                    });
+                }
+            };
+            let entry = usage.remove(&victim).unwrap();
+            drop(usage);
+            if let Err(e) = fs::remove_file(self.path_for(&victim)) {
+                warn!("failed to evict SST content cache entry"; "key" => %victim, "err" => %e);
+            } else {
+                debug!("evicted SST content cache entry"; "key" => %victim, "size" => entry.size_bytes, "ingested" => entry.ingested);
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -674,7 +1021,7 @@ pub struct ImportFile {
     meta: SstMeta,
     path: ImportPath,
     file: Option<File>,
-    digest: crc32fast::Hasher,
+    digest: Option<ChecksumDigest>,
 }
 
 impl ImportFile {
@@ -683,17 +1030,20 @@ impl ImportFile {
             .write(true)
             .create_new(true)
             .open(&path.temp)?;
+        // The vendored `SstMeta` carries no checksum-algorithm selector yet, so every caller
+        // defaults to `Crc32`. See `crate::checksum` for the seam to update once it does.
+        let algorithm = ChecksumAlgorithm::Crc32;
         Ok(ImportFile {
             meta,
             path,
             file: Some(file),
-            digest: crc32fast::Hasher::new(),
+            digest: Some(ChecksumDigest::new(algorithm)),
         })
     }
 
     pub fn append(&mut self, data: &[u8]) -> Result<()> {
         self.file.as_mut().unwrap().write_all(data)?;
-        self.digest.update(data);
+        self.digest.as_mut().unwrap().update(data);
         Ok(())
     }
 
@@ -715,12 +1065,16 @@ impl ImportFile {
         Ok(())
     }
 
-    fn validate(&self) -> Result<()> {
-        let crc32 = self.digest.clone().finalize();
-        let expect = self.meta.get_crc32();
+    fn validate(&mut self) -> Result<()> {
+        let crc32 = self.digest.take().unwrap().finalize();
+        let expect = u64::from(self.meta.get_crc32());
         if crc32 != expect {
             let reason = format!("crc32 {}, expect {}", crc32, expect);
-            return Err(Error::FileCorrupted(self.path.temp.clone(), reason));
+            return Err(Error::FileCorrupted {
+                path: self.path.temp.clone(),
+                offset: None,
+                reason,
+            });
         }
 
         let f = self.file.as_ref().unwrap();
@@ -728,7 +1082,11 @@ impl ImportFile {
         let expect = self.meta.get_length();
         if length != expect {
             let reason = format!("length {}, expect {}", length, expect);
-            return Err(Error::FileCorrupted(self.path.temp.clone(), reason));
+            return Err(Error::FileCorrupted {
+                path: self.path.temp.clone(),
+                offset: None,
+                reason,
+            });
         }
         Ok(())
     }
@@ -751,6 +1109,19 @@ impl fmt::Debug for ImportFile {
     }
 }
 
+/// Orders CFs so `default` and `lock` are ingested before `write`. `write` records point at
+/// `default` (for long values) and are only meaningful once the pointee is visible, so ingesting
+/// `default` first avoids a window where a reader can see a dangling `write` record. CFs other
+/// than these three are ordered after `write`, arbitrarily among themselves, since ingest ordering
+/// only matters for the default/write dependency.
+fn cf_ingest_order(cf: &str) -> u8 {
+    match cf {
+        CF_DEFAULT | CF_LOCK => 0,
+        CF_WRITE => 1,
+        _ => 2,
+    }
+}
+
 const SST_SUFFIX: &str = ".sst";
 
 fn sst_meta_to_path(meta: &SstMeta) -> Result<PathBuf> {
@@ -838,7 +1209,7 @@ mod tests {
     #[test]
     fn test_import_dir() {
         let temp_dir = Builder::new().prefix("test_import_dir").tempdir().unwrap();
-        let dir = ImportDir::new(temp_dir.path()).unwrap();
+        let dir = ImportDir::new(temp_dir.path(), 0).unwrap();
 
         let mut meta = SstMeta::default();
         meta.set_uuid(Uuid::new_v4().as_bytes().to_vec());
@@ -883,7 +1254,7 @@ mod tests {
             f.append(&data).unwrap();
             f.finish().unwrap();
 
-            dir.ingest(&meta, &db, None).unwrap();
+            dir.ingest(&meta, &db, None, false).unwrap();
             check_db_range(&db, range);
 
             ingested.push(meta);
@@ -901,6 +1272,35 @@ mod tests {
         assert!(dir.list_ssts().unwrap().is_empty());
     }
 
+    #[test]
+    fn test_ingest_rejects_empty_sst_unless_allowed() {
+        let temp_dir = Builder::new()
+            .prefix("test_ingest_rejects_empty_sst_unless_allowed")
+            .tempdir()
+            .unwrap();
+        let dir = ImportDir::new(temp_dir.path(), 0).unwrap();
+
+        let db_path = temp_dir.path().join("db");
+        let db = new_test_engine(db_path.to_str().unwrap(), &[CF_DEFAULT]);
+
+        let path = temp_dir.path().join("empty.sst");
+        let (meta, data) = gen_sst_file(&path, (5, 5));
+
+        let mut f = dir.create(&meta).unwrap();
+        f.append(&data).unwrap();
+        f.finish().unwrap();
+
+        match dir.ingest(&meta, &db, None, false) {
+            Err(Error::EmptySst(_)) => {}
+            other => panic!("expected Error::EmptySst, got {:?}", other),
+        }
+
+        // the rejected SST must still be sitting where it was, ready to retry.
+        assert!(dir.join(&meta).unwrap().save.exists());
+
+        dir.ingest(&meta, &db, None, true).unwrap();
+    }
+
     #[test]
     fn test_import_file() {
         let temp_dir = Builder::new().prefix("test_import_file").tempdir().unwrap();
@@ -965,6 +1365,24 @@ mod tests {
         assert_eq!(meta, new_meta);
     }
 
+    #[test]
+    fn test_cf_ingest_order() {
+        let mut default_meta = SstMeta::default();
+        default_meta.set_cf_name(CF_DEFAULT.to_owned());
+        let mut lock_meta = SstMeta::default();
+        lock_meta.set_cf_name(CF_LOCK.to_owned());
+        let mut write_meta = SstMeta::default();
+        write_meta.set_cf_name(CF_WRITE.to_owned());
+
+        // deliberately supplied out of order.
+        let metas = vec![write_meta.clone(), lock_meta.clone(), default_meta.clone()];
+        let mut ordered: Vec<&SstMeta> = metas.iter().collect();
+        ordered.sort_by_key(|meta| cf_ingest_order(meta.get_cf_name()));
+
+        let cfs: Vec<&str> = ordered.iter().map(|meta| meta.get_cf_name()).collect();
+        assert_eq!(cfs, vec![CF_LOCK, CF_DEFAULT, CF_WRITE]);
+    }
+
     fn create_sample_external_sst_file() -> Result<(tempfile::TempDir, StorageBackend, SstMeta)> {
         let ext_sst_dir = tempfile::tempdir()?;
         let mut sst_writer =
@@ -1192,6 +1610,176 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_download_sst_content_cache() {
+        // creates a sample SST file, this time with a crc32 filled in so the content-addressed
+        // cache has a checksum to key on.
+        let (ext_sst_dir, backend, mut meta) = create_sample_external_sst_file().unwrap();
+        let data = fs::read(ext_sst_dir.path().join("sample.sst")).unwrap();
+        meta.set_crc32(calc_data_crc32(&data));
+
+        let importer_dir = tempfile::tempdir().unwrap();
+        let importer = SSTImporter::new(&importer_dir, None).unwrap();
+
+        let sst_writer = create_sst_writer_with_db(&importer, &meta).unwrap();
+        importer
+            .download::<TestEngine>(
+                &meta,
+                &backend,
+                "sample.sst",
+                &RewriteRule::default(),
+                Limiter::new(INFINITY),
+                sst_writer,
+            )
+            .unwrap()
+            .unwrap();
+        let first_save = importer.dir.join(&meta).unwrap().save;
+
+        // A second request for identical content (same crc32 and length, different uuid) must
+        // succeed by reusing the cache, since the backend it is given no longer has the file.
+        let mut meta2 = meta.clone();
+        meta2.set_uuid(Uuid::new_v4().as_bytes().to_vec());
+        let empty_dir = tempfile::tempdir().unwrap();
+        let missing_backend = external_storage::make_local_backend(empty_dir.path());
+
+        let sst_writer2 = create_sst_writer_with_db(&importer, &meta2).unwrap();
+        importer
+            .download::<TestEngine>(
+                &meta2,
+                &missing_backend,
+                "sample.sst",
+                &RewriteRule::default(),
+                Limiter::new(INFINITY),
+                sst_writer2,
+            )
+            .unwrap()
+            .unwrap();
+        let second_save = importer.dir.join(&meta2).unwrap().save;
+
+        // Both downloads share the same underlying file via the content cache.
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(
+            first_save.metadata().unwrap().ino(),
+            second_save.metadata().unwrap().ino()
+        );
+    }
+
+    #[test]
+    fn test_content_cache_rejects_corrupt_entry() {
+        // creates a sample SST file, this time with a crc32 filled in so the content-addressed
+        // cache has a checksum to key on.
+        let (ext_sst_dir, backend, mut meta) = create_sample_external_sst_file().unwrap();
+        let data = fs::read(ext_sst_dir.path().join("sample.sst")).unwrap();
+        meta.set_crc32(calc_data_crc32(&data));
+
+        let importer_dir = tempfile::tempdir().unwrap();
+        let importer = SSTImporter::new(&importer_dir, None).unwrap();
+
+        let sst_writer = create_sst_writer_with_db(&importer, &meta).unwrap();
+        importer
+            .download::<TestEngine>(
+                &meta,
+                &backend,
+                "sample.sst",
+                &RewriteRule::default(),
+                Limiter::new(INFINITY),
+                sst_writer,
+            )
+            .unwrap()
+            .unwrap();
+
+        // Corrupt the cached copy in place, simulating disk corruption or a crc32 collision
+        // between two different uploads that happened to key to the same cache entry.
+        let key = ContentCache::key(&meta).unwrap();
+        let cached_path = importer.dir.content_cache.path_for(&key);
+        let mut corrupt = fs::read(&cached_path).unwrap();
+        corrupt[0] ^= 0xff;
+        fs::write(&cached_path, &corrupt).unwrap();
+
+        // A second request for the same key must not be served the corrupt cached bytes: it
+        // should fall back to fetching fresh content from the backend.
+        let mut meta2 = meta.clone();
+        meta2.set_uuid(Uuid::new_v4().as_bytes().to_vec());
+        let sst_writer2 = create_sst_writer_with_db(&importer, &meta2).unwrap();
+        importer
+            .download::<TestEngine>(
+                &meta2,
+                &backend,
+                "sample.sst",
+                &RewriteRule::default(),
+                Limiter::new(INFINITY),
+                sst_writer2,
+            )
+            .unwrap()
+            .unwrap();
+
+        let second_save = importer.dir.join(&meta2).unwrap().save;
+        assert_eq!(fs::read(&second_save).unwrap(), data);
+    }
+
+    #[test]
+    fn test_content_cache_eviction_skips_in_use_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ContentCache::new(dir.path(), 20).unwrap();
+
+        // Populate two 10-byte entries, filling the 20-byte cap exactly.
+        for key in &["a", "b"] {
+            fs::write(cache.path_for(key), vec![0u8; 10]).unwrap();
+            cache.record(key, 10);
+        }
+
+        // "a" is the least-recently-used entry; hold its lock to simulate an in-flight
+        // download/publish, so eviction must skip it even though it would otherwise go first.
+        let lock = cache.lock_for("a");
+        let _guard = lock.lock().unwrap();
+
+        fs::write(cache.path_for("c"), vec![0u8; 10]).unwrap();
+        cache.ensure_capacity("c", 10).unwrap();
+        cache.record("c", 10);
+
+        assert!(cache.path_for("a").exists());
+        assert!(!cache.path_for("b").exists());
+        assert!(cache.path_for("c").exists());
+    }
+
+    #[test]
+    fn test_content_cache_prefers_evicting_ingested_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ContentCache::new(dir.path(), 20).unwrap();
+
+        fs::write(cache.path_for("older"), vec![0u8; 10]).unwrap();
+        cache.record("older", 10);
+        fs::write(cache.path_for("newer"), vec![0u8; 10]).unwrap();
+        cache.record("newer", 10);
+        // "newer" was written last, so by recency alone it would survive; marking it ingested
+        // must still make it evict before the untouched, older entry.
+        cache.mark_ingested("newer");
+
+        cache.ensure_capacity("incoming", 10).unwrap();
+
+        assert!(cache.path_for("older").exists());
+        assert!(!cache.path_for("newer").exists());
+    }
+
+    #[test]
+    fn test_content_cache_disk_space_not_enough() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ContentCache::new(dir.path(), 10).unwrap();
+
+        fs::write(cache.path_for("a"), vec![0u8; 10]).unwrap();
+        cache.record("a", 10);
+        // Hold "a"'s lock so it's the only entry and it's ineligible for eviction.
+        let lock = cache.lock_for("a");
+        let _guard = lock.lock().unwrap();
+
+        match cache.ensure_capacity("b", 10) {
+            Err(Error::DiskSpaceNotEnough {
+                needed_bytes: 10, ..
+            }) => {}
+            other => panic!("expected DiskSpaceNotEnough, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_download_sst_with_key_rewrite() {
         // creates a sample SST file.
@@ -1404,6 +1992,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ingest_batch_default_before_write() {
+        let importer_dir = tempfile::tempdir().unwrap();
+        let importer = SSTImporter::new(&importer_dir, None).unwrap();
+
+        // downloads a default-CF SST and a write-CF SST that reference the same keys.
+        let (_ext_default_dir, default_backend, mut default_meta) =
+            create_sample_external_sst_file_txn_default().unwrap();
+        let default_sst_writer = create_sst_writer_with_db(&importer, &default_meta).unwrap();
+        importer
+            .download::<TestEngine>(
+                &default_meta,
+                &default_backend,
+                "sample_default.sst",
+                &RewriteRule::default(),
+                Limiter::new(INFINITY),
+                default_sst_writer,
+            )
+            .unwrap()
+            .unwrap();
+        default_meta.set_length(0);
+        default_meta.set_crc32(0);
+
+        let (_ext_write_dir, write_backend, mut write_meta) =
+            create_sample_external_sst_file_txn_write().unwrap();
+        let write_sst_writer = create_sst_writer_with_db(&importer, &write_meta).unwrap();
+        importer
+            .download::<TestEngine>(
+                &write_meta,
+                &write_backend,
+                "sample_write.sst",
+                &RewriteRule::default(),
+                Limiter::new(INFINITY),
+                write_sst_writer,
+            )
+            .unwrap()
+            .unwrap();
+        write_meta.set_length(0);
+        write_meta.set_crc32(0);
+
+        // Passes the write CF first, so a correct implementation must reorder it after default
+        // for the batch to represent a consistent point-in-time state at every step.
+        let ingest_dir = tempfile::tempdir().unwrap();
+        let db = new_test_engine(ingest_dir.path().to_str().unwrap(), DATA_CFS);
+        importer
+            .ingest_batch(&[write_meta, default_meta], &db)
+            .unwrap();
+
+        let mut default_iter = db.iterator_cf(CF_DEFAULT).unwrap();
+        default_iter.seek(SeekKey::Start).unwrap();
+        assert_eq!(
+            collect(default_iter),
+            vec![
+                (get_encoded_key(b"t123_r01", 1), b"abc".to_vec()),
+                (get_encoded_key(b"t123_r04", 3), b"xyz".to_vec()),
+                (get_encoded_key(b"t123_r07", 7), b"pqrst".to_vec()),
+            ]
+        );
+
+        let mut write_iter = db.iterator_cf(CF_WRITE).unwrap();
+        write_iter.seek(SeekKey::Start).unwrap();
+        assert_eq!(write_iter.valid().unwrap(), true);
+    }
+
     #[test]
     fn test_download_sst_partial_range() {
         let (_ext_sst_dir, backend, mut meta) = create_sample_external_sst_file().unwrap();
@@ -1613,4 +2265,64 @@ mod tests {
         let metas = w.finish().unwrap();
         assert_eq!(metas.len(), 2);
     }
+
+    fn create_pending_sst(importer: &SSTImporter, data: &[u8]) -> SstMeta {
+        let mut meta = SstMeta::default();
+        meta.set_uuid(Uuid::new_v4().as_bytes().to_vec());
+        meta.set_crc32(calc_data_crc32(data));
+        meta.set_length(data.len() as u64);
+
+        let mut f = importer.create(&meta).unwrap();
+        f.append(data).unwrap();
+        f.finish().unwrap();
+        meta
+    }
+
+    #[test]
+    fn test_scrub_pending_ssts_quarantines_corrupt_file() {
+        let importer_dir = tempfile::tempdir().unwrap();
+        let importer = SSTImporter::new(&importer_dir, None).unwrap();
+
+        let healthy_data = b"perfectly fine sst contents";
+        let healthy_meta = create_pending_sst(&importer, healthy_data);
+
+        let corrupt_data = b"sst contents before bit rot";
+        let corrupt_meta = create_pending_sst(&importer, corrupt_data);
+        let corrupt_path = importer.get_path(&corrupt_meta);
+        fs::write(&corrupt_path, b"sst CONTENTS before bit rot").unwrap();
+
+        let findings = importer.scrub_pending_ssts(false).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].meta, corrupt_meta);
+        match &findings[0].error {
+            Error::ChecksumMismatch { .. } => {}
+            other => panic!("expect ChecksumMismatch, got {:?}", other),
+        }
+
+        // The corrupt file is gone from the pending directory (and so can't be ingested) and was
+        // instead moved into quarantine, while the healthy one is untouched.
+        assert!(!corrupt_path.exists());
+        let quarantined_to = findings[0].quarantined_to.as_ref().unwrap();
+        assert!(quarantined_to.exists());
+        assert!(importer.get_path(&healthy_meta).exists());
+
+        // Scrubbing again finds nothing left to complain about.
+        assert!(importer.scrub_pending_ssts(false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scrub_pending_ssts_auto_delete() {
+        let importer_dir = tempfile::tempdir().unwrap();
+        let importer = SSTImporter::new(&importer_dir, None).unwrap();
+
+        let corrupt_data = b"sst contents before bit rot";
+        let corrupt_meta = create_pending_sst(&importer, corrupt_data);
+        let corrupt_path = importer.get_path(&corrupt_meta);
+        fs::write(&corrupt_path, b"sst CONTENTS before bit rot").unwrap();
+
+        let findings = importer.scrub_pending_ssts(true).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].quarantined_to.is_none());
+        assert!(!corrupt_path.exists());
+    }
 }
@@ -11,6 +11,7 @@ use encryption::DataKeyManager;
 use engine_traits::EncryptionKeyManager;
 
 use super::Result;
+use crate::metrics::IMPORTER_INGEST_PREP_COUNTER;
 
 /// Prepares the SST file for ingestion.
 /// The purpose is to make the ingestion retryable when using the `move_files` option.
@@ -22,6 +23,15 @@ use super::Result;
 ///    validation.
 /// 3. If the file has been ingested to `RocksDB`, we should not modified the
 ///    global seqno directly, because that may corrupt RocksDB's data.
+///
+/// If the source file already has more than one hard link (typically because it was already
+/// staged and ingested once, e.g. a retry or a re-import of an SST covering the same range),
+/// hard-linking again would hand RocksDB a second name for data it may already own, so a fresh
+/// file is needed instead. Rather than rewriting the whole file, we try a copy-on-write reflink
+/// first: same effect as a copy (a distinct file RocksDB can safely move), but without actually
+/// duplicating the file's data on disk. Filesystems that don't support it (anything other than
+/// btrfs/xfs on Linux, or the source and destination living on different filesystems) fall back
+/// to a plain byte copy, same as before.
 pub fn prepare_sst_for_ingestion<P: AsRef<Path>, Q: AsRef<Path>>(
     path: P,
     clone: Q,
@@ -51,10 +61,21 @@ pub fn prepare_sst_for_ingestion<P: AsRef<Path>, Q: AsRef<Path>>(
         // RocksDB must not have this file, we can make a hard link.
         fs::hard_link(path, clone)
             .map_err(|e| format!("link from {} to {}: {:?}", path, clone, e))?;
+        IMPORTER_INGEST_PREP_COUNTER
+            .with_label_values(&["link"])
+            .inc();
+    } else if try_reflink(path, clone).is_ok() {
+        IMPORTER_INGEST_PREP_COUNTER
+            .with_label_values(&["reflink"])
+            .inc();
     } else {
-        // RocksDB may have this file, we should make a copy.
+        // RocksDB may have this file, and reflink isn't available, so we have to make a real
+        // copy.
         copy_and_sync(path, clone)
             .map_err(|e| format!("copy from {} to {}: {:?}", path, clone, e))?;
+        IMPORTER_INGEST_PREP_COUNTER
+            .with_label_values(&["copy"])
+            .inc();
     }
     if let Some(key_manager) = encryption_key_manager {
         key_manager.link_file(path, clone)?;
@@ -76,9 +97,41 @@ fn copy_and_sync<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
     Ok(())
 }
 
+/// Tries to make `to` a copy-on-write clone of `from`, sharing the same data extents on disk
+/// until either is later modified, instead of duplicating the bytes right away. Returns an error
+/// if the underlying filesystem doesn't support it, leaving `to` untouched for the caller to fall
+/// back to `copy_and_sync`.
+#[cfg(target_os = "linux")]
+fn try_reflink<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // `FICLONE`, from `linux/fs.h`. Not exposed by the `libc` crate, so it's spelled out here.
+    const FICLONE: libc::c_ulong = 0x4004_9409;
+
+    let src = File::open(from)?;
+    let dst = File::create(to.as_ref())?;
+    let ret = unsafe { libc::ioctl(dst.as_raw_fd(), FICLONE, src.as_raw_fd()) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        let err = io::Error::last_os_error();
+        let _ = fs::remove_file(to);
+        Err(err)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn try_reflink<P: AsRef<Path>, Q: AsRef<Path>>(_from: P, _to: Q) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "reflink is only supported on linux",
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::prepare_sst_for_ingestion;
+    use crate::metrics::IMPORTER_INGEST_PREP_COUNTER;
 
     use encryption::DataKeyManager;
     use engine_rocks::{
@@ -188,13 +241,33 @@ mod tests {
             manager.delete_file(sst_clone.to_str().unwrap()).unwrap();
         }
 
-        // The second ingestion will copy sst_path to sst_clone.
+        // The second ingestion re-stages the same range: since `sst_path` already has another
+        // link, we can't hard link it again, so it reflinks (or, where unsupported, copies)
+        // sst_path to sst_clone instead.
+        let reflink_before = IMPORTER_INGEST_PREP_COUNTER
+            .with_label_values(&["reflink"])
+            .get();
+        let copy_before = IMPORTER_INGEST_PREP_COUNTER
+            .with_label_values(&["copy"])
+            .get();
         check_hard_link(&sst_path, 2);
         prepare_sst_for_ingestion(&sst_path, &sst_clone, key_manager).unwrap();
         db.validate_sst_for_ingestion(cf, &sst_clone, size, checksum)
             .unwrap();
         check_hard_link(&sst_path, 2);
         check_hard_link(&sst_clone, 1);
+        // Whichever strategy the filesystem allowed, exactly one of them fired, and it did not
+        // fall back to the plain hard-link path used for a never-before-seen file.
+        let reflink_after = IMPORTER_INGEST_PREP_COUNTER
+            .with_label_values(&["reflink"])
+            .get();
+        let copy_after = IMPORTER_INGEST_PREP_COUNTER
+            .with_label_values(&["copy"])
+            .get();
+        assert_eq!(
+            (reflink_after - reflink_before) + (copy_after - copy_before),
+            1
+        );
         db.ingest_external_file_cf(cf, &ingest_opts, &[sst_clone.to_str().unwrap()])
             .unwrap();
         check_db_with_kvs(&db, cf_name, &kvs);
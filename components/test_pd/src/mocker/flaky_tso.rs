@@ -0,0 +1,33 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use kvproto::pdpb::*;
+
+use super::*;
+
+/// Fails the first `fail_count` `tso()` attempts, then succeeds every attempt after that. Used to
+/// exercise a client's bounded retry policy for the TSO RPC.
+#[derive(Debug)]
+pub struct FlakyTso {
+    fail_count: usize,
+    seen: AtomicUsize,
+}
+
+impl FlakyTso {
+    pub fn new(fail_count: usize) -> FlakyTso {
+        FlakyTso {
+            fail_count,
+            seen: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl PdMocker for FlakyTso {
+    fn tso(&self, _: &TsoRequest) -> Option<Result<TsoResponse>> {
+        if self.seen.fetch_add(1, Ordering::SeqCst) < self.fail_count {
+            return Some(Err("tso temporarily unavailable".to_owned()));
+        }
+        None
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+use kvproto::pdpb::*;
+
+use super::*;
+
+/// Rejects write-path RPCs the way a real PD does while it is in a read-only maintenance state
+/// (e.g. mid-upgrade): an `Unknown`-typed error whose message says so, since there is no
+/// dedicated `ErrorType` for it. Read-path RPCs fall through (`None`) so `Service` still answers
+/// them, matching a real PD that keeps serving reads during maintenance.
+#[derive(Debug)]
+pub struct Maintenance;
+
+impl Maintenance {
+    fn header(&self) -> ResponseHeader {
+        let mut err = Error::default();
+        err.set_type(ErrorType::Unknown);
+        err.set_message("pd is in read-only maintenance mode".to_owned());
+
+        let mut header = ResponseHeader::default();
+        header.set_error(err);
+        header
+    }
+}
+
+impl PdMocker for Maintenance {
+    fn bootstrap(&self, _: &BootstrapRequest) -> Option<Result<BootstrapResponse>> {
+        let mut resp = BootstrapResponse::default();
+        resp.set_header(self.header());
+        Some(Ok(resp))
+    }
+
+    fn ask_batch_split(&self, _: &AskBatchSplitRequest) -> Option<Result<AskBatchSplitResponse>> {
+        let mut resp = AskBatchSplitResponse::default();
+        resp.set_header(self.header());
+        Some(Ok(resp))
+    }
+}
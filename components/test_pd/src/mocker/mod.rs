@@ -5,18 +5,26 @@ use std::result;
 use kvproto::pdpb::*;
 
 mod bootstrap;
+mod flaky_tso;
 mod incompatible;
 mod leader_change;
+mod maintenance;
+mod pause;
 mod retry;
 mod service;
 mod split;
+mod store_limit;
 
 pub use self::bootstrap::AlreadyBootstrapped;
+pub use self::flaky_tso::FlakyTso;
 pub use self::incompatible::Incompatible;
 pub use self::leader_change::LeaderChange;
+pub use self::maintenance::Maintenance;
+pub use self::pause::Pause;
 pub use self::retry::{NotRetry, Retry};
 pub use self::service::Service;
 pub use self::split::Split;
+pub use self::store_limit::StoreLimit;
 
 pub const DEFAULT_CLUSTER_ID: u64 = 42;
 
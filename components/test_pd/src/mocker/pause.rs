@@ -0,0 +1,21 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+use std::thread;
+use std::time::Duration;
+
+use kvproto::pdpb::*;
+
+use super::*;
+
+/// Simulates a PD that has stopped answering `get_region_by_id` requests, e.g. wedged
+/// mid-election, by sleeping much longer than any reasonable client timeout before ever
+/// responding. Used to exercise a client's own timeout handling rather than PD's.
+#[derive(Debug)]
+pub struct Pause;
+
+impl PdMocker for Pause {
+    fn get_region_by_id(&self, _: &GetRegionByIdRequest) -> Option<Result<GetRegionResponse>> {
+        thread::sleep(Duration::from_secs(3600));
+        Some(Ok(GetRegionResponse::default()))
+    }
+}
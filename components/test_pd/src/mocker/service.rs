@@ -17,6 +17,17 @@ pub struct Service {
     stores: Mutex<HashMap<u64, Store>>,
     regions: Mutex<HashMap<u64, Region>>,
     leaders: Mutex<HashMap<u64, Peer>>,
+    // Store ids seen by `store_heartbeat`, in arrival order, so tests can verify a batch of
+    // heartbeats was serviced (and its responses returned) in the order it was sent.
+    store_heartbeats: Mutex<Vec<u64>>,
+    // Most recently reported `StoreStats` per store id, so `get_store` can hand it back.
+    store_stats: Mutex<HashMap<u64, StoreStats>>,
+    // Most recently reported `RegionHeartbeatRequest` per region id, so tests can inspect the
+    // stats (e.g. read/written bytes and keys) a heartbeat actually carried.
+    region_heartbeats: Mutex<HashMap<u64, RegionHeartbeatRequest>>,
+    // The most recently received `scatter_region`/`scatter_regions` request, so tests can assert
+    // on what region ids and group label a batch scatter actually carried.
+    last_scatter_region: Mutex<Option<ScatterRegionRequest>>,
 }
 
 impl Service {
@@ -28,6 +39,10 @@ impl Service {
             stores: Mutex::new(HashMap::default()),
             regions: Mutex::new(HashMap::default()),
             leaders: Mutex::new(HashMap::default()),
+            store_heartbeats: Mutex::new(Vec::new()),
+            store_stats: Mutex::new(HashMap::default()),
+            region_heartbeats: Mutex::new(HashMap::default()),
+            last_scatter_region: Mutex::new(None),
         }
     }
 
@@ -42,6 +57,33 @@ impl Service {
         let store_id = store.get_id();
         self.stores.lock().unwrap().insert(store_id, store);
     }
+
+    /// Add an arbitrary region.
+    pub fn add_region(&self, region: &Region) {
+        self.regions
+            .lock()
+            .unwrap()
+            .insert(region.get_id(), region.clone());
+    }
+
+    /// Store ids seen by `store_heartbeat` so far, in arrival order.
+    pub fn store_heartbeats(&self) -> Vec<u64> {
+        self.store_heartbeats.lock().unwrap().clone()
+    }
+
+    /// The most recently received `region_heartbeat` request for the given region, if any.
+    pub fn region_heartbeat_stats(&self, region_id: u64) -> Option<RegionHeartbeatRequest> {
+        self.region_heartbeats
+            .lock()
+            .unwrap()
+            .get(&region_id)
+            .cloned()
+    }
+
+    /// The most recently received `scatter_region`/`scatter_regions` request, if any.
+    pub fn last_scatter_region(&self) -> Option<ScatterRegionRequest> {
+        self.last_scatter_region.lock().unwrap().clone()
+    }
 }
 
 fn make_members_response(eps: Vec<String>) -> GetMembersResponse {
@@ -123,6 +165,9 @@ impl PdMocker for Service {
             Some(store) => {
                 resp.set_header(Service::header());
                 resp.set_store(store.clone());
+                if let Some(stats) = self.store_stats.lock().unwrap().get(&req.get_store_id()) {
+                    resp.set_stats(stats.clone());
+                }
                 Some(Ok(resp))
             }
             None => {
@@ -218,6 +263,10 @@ impl PdMocker for Service {
             .lock()
             .unwrap()
             .insert(region_id, req.get_leader().clone());
+        self.region_heartbeats
+            .lock()
+            .unwrap()
+            .insert(region_id, req.clone());
 
         let mut resp = RegionHeartbeatResponse::default();
         let header = Service::header();
@@ -225,7 +274,16 @@ impl PdMocker for Service {
         Some(Ok(resp))
     }
 
-    fn store_heartbeat(&self, _: &StoreHeartbeatRequest) -> Option<Result<StoreHeartbeatResponse>> {
+    fn store_heartbeat(
+        &self,
+        req: &StoreHeartbeatRequest,
+    ) -> Option<Result<StoreHeartbeatResponse>> {
+        let store_id = req.get_stats().get_store_id();
+        self.store_heartbeats.lock().unwrap().push(store_id);
+        self.store_stats
+            .lock()
+            .unwrap()
+            .insert(store_id, req.get_stats().clone());
         let mut resp = StoreHeartbeatResponse::default();
         let header = Service::header();
         resp.set_header(header);
@@ -256,7 +314,11 @@ impl PdMocker for Service {
         Some(Ok(resp))
     }
 
-    fn scatter_region(&self, _: &ScatterRegionRequest) -> Option<Result<ScatterRegionResponse>> {
+    fn scatter_region(
+        &self,
+        req: &ScatterRegionRequest,
+    ) -> Option<Result<ScatterRegionResponse>> {
+        *self.last_scatter_region.lock().unwrap() = Some(req.clone());
         let mut resp = ScatterRegionResponse::default();
         let header = Service::header();
         resp.set_header(header);
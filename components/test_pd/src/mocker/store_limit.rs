@@ -0,0 +1,46 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+use kvproto::pdpb::*;
+
+use super::*;
+
+/// Rejects `scatter_region`/`ask_batch_split` the way a real PD does when the target store has
+/// hit its operator limit: an `Unknown`-typed error whose message carries the store id, since
+/// there is no dedicated `ErrorType` for it.
+#[derive(Debug)]
+pub struct StoreLimit {
+    store_id: u64,
+}
+
+impl StoreLimit {
+    pub fn new(store_id: u64) -> StoreLimit {
+        StoreLimit { store_id }
+    }
+
+    fn header(&self) -> ResponseHeader {
+        let mut err = Error::default();
+        err.set_type(ErrorType::Unknown);
+        err.set_message(format!(
+            "the store {} exceeds the limit",
+            self.store_id
+        ));
+
+        let mut header = ResponseHeader::default();
+        header.set_error(err);
+        header
+    }
+}
+
+impl PdMocker for StoreLimit {
+    fn scatter_region(&self, _: &ScatterRegionRequest) -> Option<Result<ScatterRegionResponse>> {
+        let mut resp = ScatterRegionResponse::default();
+        resp.set_header(self.header());
+        Some(Ok(resp))
+    }
+
+    fn ask_batch_split(&self, _: &AskBatchSplitRequest) -> Option<Result<AskBatchSplitResponse>> {
+        let mut resp = AskBatchSplitResponse::default();
+        resp.set_header(self.header());
+        Some(Ok(resp))
+    }
+}
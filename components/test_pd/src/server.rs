@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use futures::{Future, Sink, Stream};
 use grpcio::{
@@ -176,12 +176,31 @@ impl<C: PdMocker + Send + Sync + 'static> Pd for PdMock<C> {
         req: RequestStream<TsoRequest>,
         resp: DuplexSink<TsoResponse>,
     ) {
+        // A whole `tso()` stream is opened anew for every attempt made by the client, so a mocker
+        // can simulate a flaky attempt by failing it here, before any request on the stream is
+        // even read.
+        let refused = self
+            .case
+            .as_ref()
+            .and_then(|case| case.tso(&TsoRequest::default()));
+        if let Some(Err(err)) = refused {
+            let status = RpcStatus::new(RpcStatusCode::UNKNOWN, Some(err));
+            ctx.spawn(
+                resp.fail(status)
+                    .map_err(move |err| error!("failed to reply: {:?}", err)),
+            );
+            return;
+        }
+
         let header = Service::header();
         let fut = resp
             .send_all(req.map(move |_| {
                 let mut r = TsoResponse::default();
                 r.set_header(header.clone());
-                r.mut_timestamp().physical = 42;
+                r.mut_timestamp().physical = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as i64;
                 (r, WriteFlags::default())
             }))
             .map_err(|_| ())
@@ -1,6 +1,6 @@
 // Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
 
-use pd_client::{Config, RpcClient};
+use pd_client::{BackoffConfig, Config, RpcClient};
 use security::{SecurityConfig, SecurityManager};
 use tikv_util::config::ReadableDuration;
 
@@ -33,3 +33,15 @@ pub fn new_client_with_update_interval(
         mgr.unwrap_or_else(|| Arc::new(SecurityManager::new(&SecurityConfig::default()).unwrap()));
     RpcClient::new(&cfg, mgr).unwrap()
 }
+
+pub fn new_client_with_reconnect_backoff(
+    eps: Vec<(String, u16)>,
+    mgr: Option<Arc<SecurityManager>>,
+    backoff: BackoffConfig,
+) -> RpcClient {
+    let mut cfg = new_config(eps);
+    cfg.reconnect_backoff = backoff;
+    let mgr =
+        mgr.unwrap_or_else(|| Arc::new(SecurityManager::new(&SecurityConfig::default()).unwrap()));
+    RpcClient::new(&cfg, mgr).unwrap()
+}
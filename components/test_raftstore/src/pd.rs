@@ -17,7 +17,7 @@ use kvproto::pdpb;
 use raft::eraftpb;
 
 use keys::{self, data_key, enc_end_key, enc_start_key};
-use pd_client::{Error, Key, PdClient, PdFuture, RegionInfo, RegionStat, Result};
+use pd_client::{Error, HotRegionInfo, Key, PdClient, PdFuture, RegionInfo, RegionStat, Result};
 use raftstore::store::util::check_key_in_region;
 use raftstore::store::{INIT_EPOCH_CONF_VER, INIT_EPOCH_VER};
 use tikv_util::collections::{HashMap, HashMapEntry, HashSet};
@@ -225,6 +225,7 @@ struct Cluster {
     region_id_keys: HashMap<u64, Key>,
     region_approximate_size: HashMap<u64, u64>,
     region_approximate_keys: HashMap<u64, u64>,
+    region_hot_stats: HashMap<u64, HotRegionInfo>,
     region_last_report_ts: HashMap<u64, UnixSecs>,
     region_last_report_term: HashMap<u64, u64>,
     base_id: AtomicUsize,
@@ -261,6 +262,7 @@ impl Cluster {
             region_id_keys: HashMap::default(),
             region_approximate_size: HashMap::default(),
             region_approximate_keys: HashMap::default(),
+            region_hot_stats: HashMap::default(),
             region_last_report_ts: HashMap::default(),
             region_last_report_term: HashMap::default(),
             base_id: AtomicUsize::new(1000),
@@ -356,6 +358,20 @@ impl Cluster {
         self.region_last_report_ts.get(&region_id).cloned()
     }
 
+    fn get_hot_regions(&self, store_id: u64) -> Vec<HotRegionInfo> {
+        self.region_hot_stats
+            .values()
+            .filter(|info| {
+                info.is_hot()
+                    && self
+                        .leaders
+                        .get(&info.region_id)
+                        .map_or(false, |leader| leader.get_store_id() == store_id)
+            })
+            .cloned()
+            .collect()
+    }
+
     fn get_region_last_report_term(&self, region_id: u64) -> Option<u64> {
         self.region_last_report_term.get(&region_id).cloned()
     }
@@ -626,6 +642,16 @@ impl Cluster {
             .insert(region.get_id(), region_stat.approximate_size);
         self.region_approximate_keys
             .insert(region.get_id(), region_stat.approximate_keys);
+        self.region_hot_stats.insert(
+            region.get_id(),
+            HotRegionInfo {
+                region_id: region.get_id(),
+                read_bytes: region_stat.read_bytes,
+                read_keys: region_stat.read_keys,
+                written_bytes: region_stat.written_bytes,
+                written_keys: region_stat.written_keys,
+            },
+        );
         self.region_last_report_ts
             .insert(region.get_id(), region_stat.last_report_ts);
         self.region_last_report_term.insert(region.get_id(), term);
@@ -1124,6 +1150,13 @@ impl PdClient for TestPdClient {
         }
     }
 
+    fn get_hot_regions(&self, store_id: u64) -> PdFuture<Vec<HotRegionInfo>> {
+        if let Err(e) = self.check_bootstrap() {
+            return Box::new(err(e));
+        }
+        Box::new(ok(self.cluster.rl().get_hot_regions(store_id)))
+    }
+
     fn handle_region_heartbeat_response<F>(&self, store_id: u64, f: F) -> PdFuture<()>
     where
         Self: Sized,
@@ -212,6 +212,7 @@ impl Simulator for ServerCluster {
             Arc::clone(&engines.kv),
             Arc::clone(&importer),
             security_mgr.clone(),
+            region_info_accessor.clone(),
         );
         // Create Debug service.
         let pool = futures_cpupool::Builder::new()
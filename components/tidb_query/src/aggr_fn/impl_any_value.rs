@@ -0,0 +1,297 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+use std::marker::PhantomData;
+
+use tidb_query_codegen::AggrFunction;
+use tidb_query_datatype::EvalType;
+use tipb::{Expr, ExprType, FieldType};
+
+use crate::codec::data_type::*;
+use crate::expr::EvalContext;
+use crate::rpn_expr::{RpnExpression, RpnExpressionBuilder};
+use crate::Result;
+
+/// The parser for ANY_VALUE aggregate function.
+pub struct AggrFnDefinitionParserAnyValue;
+
+impl super::AggrDefinitionParser for AggrFnDefinitionParserAnyValue {
+    fn check_supported(&self, aggr_def: &Expr) -> Result<()> {
+        assert_eq!(aggr_def.get_tp(), ExprType::AnyValue);
+        super::util::check_aggr_exp_supported_one_child(aggr_def)
+    }
+
+    fn parse(
+        &self,
+        mut aggr_def: Expr,
+        ctx: &mut EvalContext,
+        src_schema: &[FieldType],
+        out_schema: &mut Vec<FieldType>,
+        out_exp: &mut Vec<RpnExpression>,
+    ) -> Result<Box<dyn super::AggrFunction>> {
+        use std::convert::TryFrom;
+        use tidb_query_datatype::FieldTypeAccessor;
+
+        assert_eq!(aggr_def.get_tp(), ExprType::AnyValue);
+        let child = aggr_def.take_children().into_iter().next().unwrap();
+        let eval_type = EvalType::try_from(child.get_field_type().as_accessor().tp()).unwrap();
+
+        let out_ft = aggr_def.take_field_type();
+        let out_et = box_try!(EvalType::try_from(out_ft.as_accessor().tp()));
+
+        if out_et != eval_type {
+            return Err(other_err!(
+                "Unexpected return field type {}",
+                out_ft.as_accessor().tp()
+            ));
+        }
+
+        // ANY_VALUE outputs one column with the same type as its child
+        out_schema.push(out_ft);
+        out_exp.push(RpnExpressionBuilder::build_from_expr_tree(
+            child,
+            ctx,
+            src_schema.len(),
+        )?);
+
+        match_template_evaluable! {
+            TT, match eval_type {
+                EvalType::TT => Ok(Box::new(AggrFnAnyValue::<TT>::new()))
+            }
+        }
+    }
+}
+
+/// The ANY_VALUE aggregate function.
+#[derive(Debug, AggrFunction)]
+#[aggr_function(state = AggrFnStateAnyValue::<T>::new())]
+pub struct AggrFnAnyValue<T>(PhantomData<T>)
+where
+    T: Evaluable,
+    VectorValue: VectorValueExt<T>;
+
+impl<T> AggrFnAnyValue<T>
+where
+    T: Evaluable,
+    VectorValue: VectorValueExt<T>,
+{
+    fn new() -> Self {
+        AggrFnAnyValue(PhantomData)
+    }
+}
+
+/// The state of the ANY_VALUE aggregate function. Unlike FIRST, which latches whatever the
+/// first row happens to be (`NULL` included), ANY_VALUE keeps scanning until it finds the
+/// first non-`NULL` value and latches that, so a `NULL`-prefixed group still returns a value.
+#[derive(Debug)]
+pub enum AggrFnStateAnyValue<T>
+where
+    T: Evaluable,
+    VectorValue: VectorValueExt<T>,
+{
+    Empty,
+    Valued(T),
+}
+
+impl<T> AggrFnStateAnyValue<T>
+where
+    T: Evaluable,
+    VectorValue: VectorValueExt<T>,
+{
+    pub fn new() -> Self {
+        AggrFnStateAnyValue::Empty
+    }
+}
+
+// Here we manually implement `AggrFunctionStateUpdatePartial` instead of implementing
+// `ConcreteAggrFunctionState` so that `update_repeat` and `update_vector` can be faster.
+impl<T> super::AggrFunctionStateUpdatePartial<T> for AggrFnStateAnyValue<T>
+where
+    T: Evaluable,
+    VectorValue: VectorValueExt<T>,
+{
+    #[inline]
+    fn update(&mut self, _ctx: &mut EvalContext, value: &Option<T>) -> Result<()> {
+        if let AggrFnStateAnyValue::Empty = self {
+            if let Some(value) = value {
+                // TODO: avoid this clone
+                *self = AggrFnStateAnyValue::Valued(value.clone());
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn update_repeat(
+        &mut self,
+        ctx: &mut EvalContext,
+        value: &Option<T>,
+        repeat_times: usize,
+    ) -> Result<()> {
+        assert!(repeat_times > 0);
+        self.update(ctx, value)
+    }
+
+    #[inline]
+    fn update_vector(
+        &mut self,
+        ctx: &mut EvalContext,
+        physical_values: &[Option<T>],
+        logical_rows: &[usize],
+    ) -> Result<()> {
+        for physical_index in logical_rows {
+            if let AggrFnStateAnyValue::Valued(_) = self {
+                break;
+            }
+            self.update(ctx, &physical_values[*physical_index])?;
+        }
+        Ok(())
+    }
+}
+
+// In order to make `AggrFnStateAnyValue` satisfy the `AggrFunctionState` trait, we default impl
+// all `AggrFunctionStateUpdatePartial` of `Evaluable` for all `AggrFnStateAnyValue`.
+impl<T1, T2> super::AggrFunctionStateUpdatePartial<T1> for AggrFnStateAnyValue<T2>
+where
+    T1: Evaluable,
+    T2: Evaluable,
+    VectorValue: VectorValueExt<T2>,
+{
+    #[inline]
+    default fn update(&mut self, _ctx: &mut EvalContext, _value: &Option<T1>) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+
+    #[inline]
+    default fn update_repeat(
+        &mut self,
+        _ctx: &mut EvalContext,
+        _value: &Option<T1>,
+        _repeat_times: usize,
+    ) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+
+    #[inline]
+    default fn update_vector(
+        &mut self,
+        _ctx: &mut EvalContext,
+        _physical_values: &[Option<T1>],
+        _logical_rows: &[usize],
+    ) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+}
+
+impl<T> super::AggrFunctionState for AggrFnStateAnyValue<T>
+where
+    T: Evaluable,
+    VectorValue: VectorValueExt<T>,
+{
+    fn push_result(&self, _ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
+        assert_eq!(target.len(), 1);
+        let res = if let AggrFnStateAnyValue::Valued(v) = self {
+            Some(v.clone())
+        } else {
+            None
+        };
+        target[0].push(res);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AggrFunction;
+    use super::*;
+
+    use tidb_query_datatype::FieldTypeTp;
+    use tipb_helper::ExprDefBuilder;
+
+    use crate::aggr_fn::AggrDefinitionParser;
+
+    #[test]
+    fn test_update() {
+        let mut ctx = EvalContext::default();
+        let function = AggrFnAnyValue::<Int>::new();
+        let mut state = function.create_state();
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Int)];
+        state.push_result(&mut ctx, &mut result[..]).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[None]);
+
+        // A leading `NULL` should not be latched.
+        state.update(&mut ctx, &Option::<Int>::None).unwrap();
+        state.push_result(&mut ctx, &mut result[..]).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[None, None]);
+
+        state.update(&mut ctx, &Some(1)).unwrap();
+        state.push_result(&mut ctx, &mut result[..]).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[None, None, Some(1)]);
+
+        state.update(&mut ctx, &Some(2)).unwrap();
+        state.push_result(&mut ctx, &mut result[..]).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[None, None, Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn test_update_vector() {
+        let mut ctx = EvalContext::default();
+        let function = AggrFnAnyValue::<Int>::new();
+        let mut state = function.create_state();
+        let mut result = [VectorValue::with_capacity(0, EvalType::Int)];
+
+        state
+            .update_vector(&mut ctx, &[None, None, Some(2)], &[0, 1, 2])
+            .unwrap();
+        state.push_result(&mut ctx, &mut result[..]).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[Some(2)]);
+    }
+
+    #[test]
+    fn test_integrate() {
+        let expr = ExprDefBuilder::aggr_func(ExprType::AnyValue, FieldTypeTp::LongLong)
+            .push_child(ExprDefBuilder::column_ref(0, FieldTypeTp::LongLong))
+            .build();
+        AggrFnDefinitionParserAnyValue
+            .check_supported(&expr)
+            .unwrap();
+
+        let src_schema = [FieldTypeTp::LongLong.into()];
+        let mut schema = vec![];
+        let mut exp = vec![];
+        let mut ctx = EvalContext::default();
+        let aggr_fn = AggrFnDefinitionParserAnyValue
+            .parse(expr, &mut ctx, &src_schema, &mut schema, &mut exp)
+            .unwrap();
+
+        assert_eq!(schema.len(), 1);
+        assert_eq!(schema[0].as_accessor().tp(), FieldTypeTp::LongLong);
+        assert_eq!(exp.len(), 1);
+
+        let mut state = aggr_fn.create_state();
+        let mut result = [VectorValue::with_capacity(0, EvalType::Int)];
+        // The first logical row's value should be returned, ignoring any that follow.
+        state.update(&mut ctx, &Some(1i64)).unwrap();
+        state.update(&mut ctx, &Some(2i64)).unwrap();
+        state.push_result(&mut ctx, &mut result[..]).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[Some(1)]);
+    }
+
+    #[test]
+    fn test_illegal_request() {
+        let expr = ExprDefBuilder::aggr_func(ExprType::AnyValue, FieldTypeTp::Double) // Expect LongLong but give Double
+            .push_child(ExprDefBuilder::column_ref(0, FieldTypeTp::LongLong))
+            .build();
+        AggrFnDefinitionParserAnyValue
+            .check_supported(&expr)
+            .unwrap();
+
+        let src_schema = [FieldTypeTp::LongLong.into()];
+        let mut schema = vec![];
+        let mut exp = vec![];
+        let mut ctx = EvalContext::default();
+        AggrFnDefinitionParserAnyValue
+            .parse(expr, &mut ctx, &src_schema, &mut schema, &mut exp)
+            .unwrap_err();
+    }
+}
@@ -268,6 +268,41 @@ mod tests {
         );
     }
 
+    /// A parent operator finalizing AVG's `(count, sum)` partial state should be able to divide
+    /// the two columns to recover the expected average.
+    #[test]
+    fn test_partial_state_divides_to_expected_average() {
+        let mut ctx = EvalContext::default();
+        let function = AggrFnAvg::<Decimal>::new();
+        let mut state = function.create_state();
+
+        state
+            .update(&mut ctx, &Some(Decimal::from(10u64)))
+            .unwrap();
+        state.update(&mut ctx, &Option::<Decimal>::None).unwrap();
+        state
+            .update(&mut ctx, &Some(Decimal::from(15u64)))
+            .unwrap();
+        state
+            .update(&mut ctx, &Some(Decimal::from(20u64)))
+            .unwrap();
+
+        let mut result = [
+            VectorValue::with_capacity(0, EvalType::Int),
+            VectorValue::with_capacity(0, EvalType::Decimal),
+        ];
+        state.push_result(&mut ctx, &mut result[..]).unwrap();
+
+        let count = result[0].as_int_slice()[0].unwrap();
+        let sum = result[1].as_decimal_slice()[0].clone().unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(sum, Decimal::from(45u64));
+
+        let average: crate::codec::Result<Decimal> =
+            (&sum / &Decimal::from(count as u64)).unwrap().into();
+        assert_eq!(average.unwrap(), Decimal::from(15u64));
+    }
+
     #[test]
     fn test_illegal_request() {
         let expr = ExprDefBuilder::aggr_func(ExprType::Avg, FieldTypeTp::Double) // Expect NewDecimal but give Real
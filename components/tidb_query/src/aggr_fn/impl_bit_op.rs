@@ -13,10 +13,12 @@ pub trait BitOp: Clone + std::fmt::Debug + Send + Sync + 'static {
     /// Returns the bit operation type
     fn tp() -> ExprType;
 
-    /// Returns the bit operation initial state
+    /// Returns the identity element of the fold, i.e. the result of the aggregate over an empty
+    /// (or all-NULL) group: all-ones for AND, zero for OR/XOR.
     fn init_state() -> u64;
 
-    /// Executes the special bit operation
+    /// Folds `rhs` into the running `lhs`. NULL children are skipped by the caller before this is
+    /// ever invoked, so `op` only ever sees non-NULL values.
     fn op(lhs: &mut u64, rhs: u64);
 }
 
@@ -0,0 +1,219 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+use std::cell::Cell;
+
+use tidb_query_codegen::AggrFunction;
+use tidb_query_datatype::{Collation, FieldTypeAccessor};
+use tipb::{Expr, ExprType, FieldType};
+
+use crate::codec::collation::*;
+use crate::codec::data_type::*;
+use crate::codec::Error as CodecError;
+use crate::expr::EvalContext;
+use crate::rpn_expr::{RpnExpression, RpnExpressionBuilder};
+use crate::Result;
+
+/// The parser for the plain (non-`DISTINCT`) `GROUP_CONCAT` aggregate function.
+///
+/// Expects 2 children: the value expression, and a `String`/`Bytes` literal carrying the
+/// separator. TiDB is expected to fold `SEPARATOR '...'` into this second child when pushing the
+/// aggregate down.
+pub struct AggrFnDefinitionParserGroupConcat;
+
+impl super::AggrDefinitionParser for AggrFnDefinitionParserGroupConcat {
+    fn check_supported(&self, aggr_def: &Expr) -> Result<()> {
+        assert_eq!(aggr_def.get_tp(), ExprType::GroupConcat);
+        if aggr_def.get_children().len() != 2 {
+            return Err(other_err!(
+                "Expect 2 parameters, but got {}",
+                aggr_def.get_children().len()
+            ));
+        }
+        RpnExpressionBuilder::check_expr_tree_supported(&aggr_def.get_children()[0])
+    }
+
+    fn parse(
+        &self,
+        mut aggr_def: Expr,
+        ctx: &mut EvalContext,
+        src_schema: &[FieldType],
+        out_schema: &mut Vec<FieldType>,
+        out_exp: &mut Vec<RpnExpression>,
+    ) -> Result<Box<dyn super::AggrFunction>> {
+        assert_eq!(aggr_def.get_tp(), ExprType::GroupConcat);
+        let out_coll = box_try!(aggr_def.get_field_type().as_accessor().collation());
+
+        let mut children = aggr_def.take_children().into_iter();
+        let child = children.next().unwrap();
+        let separator_expr = children.next().unwrap();
+        let separator = super::util::extract_bytes_constant(&separator_expr)?;
+
+        // `GROUP_CONCAT` always outputs a `Bytes` column, regardless of the input column's type:
+        // non-string children are expected to already be cast to `Bytes` by TiDB before pushdown.
+        out_schema.push(aggr_def.take_field_type());
+        out_exp.push(RpnExpressionBuilder::build_from_expr_tree(
+            child,
+            ctx,
+            src_schema.len(),
+        )?);
+
+        match_template_collator! {
+            C, match out_coll {
+                Collation::C => Ok(Box::new(AggFnGroupConcat::<C>::new(separator)))
+            }
+        }
+    }
+}
+
+/// `GROUP_CONCAT(expr SEPARATOR sep)`, without `DISTINCT`.
+///
+/// Accumulates every non-`NULL` value in arrival order and joins them with `separator`. The
+/// result is truncated to `EvalContext::cfg.group_concat_max_len` bytes, appending a
+/// `data_too_long` warning the first time truncation happens, mirroring MySQL's
+/// `group_concat_max_len` behavior. Returns `NULL` for an empty group.
+#[derive(Debug, AggrFunction)]
+#[aggr_function(state = AggFnStateGroupConcat::<C>::new(self.separator.clone()))]
+pub struct AggFnGroupConcat<C: Collator> {
+    separator: Bytes,
+    _phantom: std::marker::PhantomData<C>,
+}
+
+impl<C: Collator> AggFnGroupConcat<C> {
+    pub fn new(separator: Bytes) -> Self {
+        Self {
+            separator,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AggFnStateGroupConcat<C: Collator> {
+    separator: Bytes,
+    // Values in arrival order, kept in their original (unencoded) form.
+    values: Vec<Bytes>,
+    warned_truncated: Cell<bool>,
+    _phantom: std::marker::PhantomData<C>,
+}
+
+impl<C: Collator> AggFnStateGroupConcat<C> {
+    pub fn new(separator: Bytes) -> Self {
+        Self {
+            separator,
+            values: Vec::new(),
+            warned_truncated: Cell::new(false),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<C: Collator> super::ConcreteAggrFunctionState for AggFnStateGroupConcat<C> {
+    type ParameterType = Bytes;
+
+    #[inline]
+    fn update_concrete(
+        &mut self,
+        _ctx: &mut EvalContext,
+        value: &Option<Self::ParameterType>,
+    ) -> Result<()> {
+        if let Some(value) = value {
+            self.values.push(value.clone());
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn push_result(&self, ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
+        if self.values.is_empty() {
+            target[0].push(None);
+            return Ok(());
+        }
+
+        let mut result = Vec::new();
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                result.extend_from_slice(&self.separator);
+            }
+            result.extend_from_slice(value);
+        }
+
+        let max_len = ctx.cfg.group_concat_max_len;
+        if result.len() > max_len {
+            result.truncate(max_len);
+            if !self.warned_truncated.replace(true) {
+                ctx.warnings.append_warning(CodecError::data_too_long(
+                    "Some rows were cut by GROUP_CONCAT()".to_string(),
+                ));
+            }
+        }
+
+        target[0].push(Some(result));
+        Ok(())
+    }
+
+    fn approximate_mem_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.separator.capacity()
+            + self
+                .values
+                .iter()
+                .map(|v| v.capacity() + std::mem::size_of::<Bytes>())
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggr_fn::{AggrFunction, ConcreteAggrFunctionState};
+
+    #[test]
+    fn test_group_concat_empty_group_returns_null() {
+        let mut ctx = EvalContext::default();
+        let state = AggFnStateGroupConcat::<CollatorBinary>::new(b",".to_vec());
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Bytes)];
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(result[0].as_bytes_slice(), &[None]);
+    }
+
+    #[test]
+    fn test_group_concat_single_value() {
+        let mut ctx = EvalContext::default();
+        let mut state = AggFnStateGroupConcat::<CollatorBinary>::new(b",".to_vec());
+
+        state
+            .update_concrete(&mut ctx, &Some(b"foo".to_vec()))
+            .unwrap();
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Bytes)];
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(
+            result[0].as_bytes_slice()[0].clone().unwrap(),
+            b"foo".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_group_concat_truncates_using_group_concat_max_len_from_ctx() {
+        let mut ctx = EvalContext::default();
+        ctx.cfg = std::sync::Arc::new({
+            let mut cfg = crate::expr::EvalConfig::default();
+            cfg.set_group_concat_max_len(5);
+            cfg
+        });
+        let mut state = AggFnStateGroupConcat::<CollatorBinary>::new(b",".to_vec());
+
+        for v in &["aaa", "bbb", "ccc"] {
+            state
+                .update_concrete(&mut ctx, &Some(v.as_bytes().to_vec()))
+                .unwrap();
+        }
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Bytes)];
+        state.push_result(&mut ctx, &mut result).unwrap();
+        let concatenated = result[0].as_bytes_slice()[0].clone().unwrap();
+        assert_eq!(concatenated.len(), 5);
+        assert_eq!(ctx.warnings.warnings.len(), 1);
+    }
+}
@@ -9,6 +9,8 @@ use tipb::{Expr, ExprType, FieldType};
 
 use crate::codec::collation::*;
 use crate::codec::data_type::*;
+use crate::codec::datum::{self, Datum};
+use crate::codec::mysql::RoundMode;
 use crate::expr::EvalContext;
 use crate::rpn_expr::{RpnExpression, RpnExpressionBuilder};
 use crate::Result;
@@ -47,6 +49,30 @@ impl<T: Extremum> AggrFnDefinitionParserExtremum<T> {
 impl<T: Extremum> super::AggrDefinitionParser for AggrFnDefinitionParserExtremum<T> {
     fn check_supported(&self, aggr_def: &Expr) -> Result<()> {
         assert_eq!(aggr_def.get_tp(), T::TP);
+        super::util::check_aggr_exp_supported_one_child(aggr_def)?;
+
+        // `MAX(enum_col)`/`MIN(enum_col)` would ideally compare on the column's underlying
+        // numeric ordinal (how MySQL orders `ENUM`/`SET` values) and emit the corresponding
+        // member back, similar to how `AggFnExtremumForBytes` special-cases `Bytes`. But
+        // `codec::table`'s row decoder rejects `FieldTypeTp::Enum`/`Set`/`Bit` outright (see
+        // `codec::table::unflatten`), so there is no way to get an enum column's value
+        // out of a coprocessor request in the first place; layering ordinal comparison on top of
+        // an aggregate function state can't paper over a value that never decodes. Reject these
+        // explicitly here with a clearer message instead of letting them fail deeper inside
+        // `EvalType::try_from` with a generic "unsupported type" error.
+        if let Some(child) = aggr_def.get_children().first() {
+            let child_tp = child.get_field_type().as_accessor().tp();
+            if child_tp == tidb_query_datatype::FieldTypeTp::Enum
+                || child_tp == tidb_query_datatype::FieldTypeTp::Set
+            {
+                return Err(other_err!(
+                    "MAX/MIN over an Enum/Set column is not supported: column value decoding for \
+                     {:?} is not implemented",
+                    child_tp
+                ));
+            }
+        }
+
         super::util::check_aggr_exp_supported_one_child(aggr_def)
     }
 
@@ -74,6 +100,21 @@ impl<T: Extremum> super::AggrDefinitionParser for AggrFnDefinitionParserExtremum
             ));
         }
 
+        // For `Bytes`, `out_coll` picks which `Collator` the aggregate state below compares
+        // with; it never re-derives that from the child. If the child were pushed down with a
+        // different collation than the declared output, we'd silently compare its values with
+        // the wrong collator instead of catching the mismatch here.
+        if out_et == EvalType::Bytes {
+            let child_coll = box_try!(child.get_field_type().as_accessor().collation());
+            if child_coll != out_coll {
+                return Err(other_err!(
+                    "Child collation {:?} does not match output collation {:?}",
+                    child_coll,
+                    out_coll
+                ));
+            }
+        }
+
         // `MAX/MIN` outputs one column which has the same type with its child
         out_schema.push(out_ft);
         out_exp.push(RpnExpressionBuilder::build_from_expr_tree(
@@ -90,6 +131,14 @@ impl<T: Extremum> super::AggrDefinitionParser for AggrFnDefinitionParserExtremum
             };
         }
 
+        if out_et == EvalType::Real {
+            return Ok(Box::new(AggFnExtremumForReal::<T>::new()));
+        }
+
+        if out_et == EvalType::Decimal {
+            return Ok(Box::new(AggFnExtremumForDecimal::<T>::new()));
+        }
+
         match_template_evaluable! {
             TT, match eval_type {
                 EvalType::TT => Ok(Box::new(AggFnExtremum::<TT, T>::new()))
@@ -147,13 +196,245 @@ where
     }
 }
 
-impl<C, E> super::ConcreteAggrFunctionState for AggFnStateExtremum4Bytes<C, E>
+impl<C, E> AggFnStateExtremum4Bytes<C, E>
+where
+    VectorValue: VectorValueExt<Bytes>,
+    C: Collator,
+    E: Extremum,
+{
+    #[inline]
+    fn consider(&mut self, value: &Option<Bytes>) -> Result<()> {
+        let value = match value {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let replace = match &self.extremum {
+            None => true,
+            Some(extremum) => collation_extremum::<C>(extremum, value, E::ORD)?,
+        };
+        if replace {
+            self.extremum = Some(value.clone());
+        }
+        Ok(())
+    }
+}
+
+// Manually implemented, like `AggFnStateExtremum`, instead of via `ConcreteAggrFunctionState`, so
+// that `update_vector` can find the single winning value within the batch (comparing candidates
+// against each other, which never clones) and only clone it into `self.extremum` once, instead of
+// re-cloning on every row of the batch that improves on the running extremum.
+impl<C, E> super::AggrFunctionStateUpdatePartial<Bytes> for AggFnStateExtremum4Bytes<C, E>
+where
+    VectorValue: VectorValueExt<Bytes>,
+    C: Collator,
+    E: Extremum,
+{
+    #[inline]
+    fn update(&mut self, _ctx: &mut EvalContext, value: &Option<Bytes>) -> Result<()> {
+        self.consider(value)
+    }
+
+    #[inline]
+    fn update_repeat(
+        &mut self,
+        _ctx: &mut EvalContext,
+        value: &Option<Bytes>,
+        repeat_times: usize,
+    ) -> Result<()> {
+        assert!(repeat_times > 0);
+        // Every row in the run carries the same value, so considering it once has the same
+        // effect as considering it `repeat_times` times.
+        self.consider(value)
+    }
+
+    #[inline]
+    fn update_vector(
+        &mut self,
+        _ctx: &mut EvalContext,
+        physical_values: &[Option<Bytes>],
+        logical_rows: &[usize],
+    ) -> Result<()> {
+        let mut winner: Option<&Bytes> = None;
+        for physical_index in logical_rows {
+            if let Some(value) = &physical_values[*physical_index] {
+                let replace = match winner {
+                    None => true,
+                    Some(current) => collation_extremum::<C>(current, value, E::ORD)?,
+                };
+                if replace {
+                    winner = Some(value);
+                }
+            }
+        }
+        if let Some(winner) = winner {
+            let replace = match &self.extremum {
+                None => true,
+                Some(extremum) => collation_extremum::<C>(extremum, winner, E::ORD)?,
+            };
+            if replace {
+                self.extremum = Some(winner.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+// In order to make `AggFnStateExtremum4Bytes` satisfy the `AggrFunctionState` trait, we default
+// impl all `AggrFunctionStateUpdatePartial` of `Evaluable` for all `AggFnStateExtremum4Bytes`,
+// mirroring `AggFnStateExtremum`'s own such impl.
+impl<T, C, E> super::AggrFunctionStateUpdatePartial<T> for AggFnStateExtremum4Bytes<C, E>
+where
+    T: Evaluable,
+    VectorValue: VectorValueExt<Bytes>,
+    C: Collator,
+    E: Extremum,
+{
+    #[inline]
+    default fn update(&mut self, _ctx: &mut EvalContext, _value: &Option<T>) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+
+    #[inline]
+    default fn update_repeat(
+        &mut self,
+        _ctx: &mut EvalContext,
+        _value: &Option<T>,
+        _repeat_times: usize,
+    ) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+
+    #[inline]
+    default fn update_vector(
+        &mut self,
+        _ctx: &mut EvalContext,
+        _physical_values: &[Option<T>],
+        _logical_rows: &[usize],
+    ) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+}
+
+impl<C, E> super::AggrFunctionState for AggFnStateExtremum4Bytes<C, E>
 where
     VectorValue: VectorValueExt<Bytes>,
     C: Collator,
     E: Extremum,
 {
-    type ParameterType = Bytes;
+    fn push_result(&self, _ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
+        target[0].push(self.extremum.clone());
+        Ok(())
+    }
+
+    fn approximate_mem_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.extremum.as_ref().map_or(0, Vec::capacity)
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(snapshot_bytes(&self.extremum))
+    }
+
+    fn restore(&mut self, snapshot: &[u8]) -> Result<()> {
+        self.extremum = restore_bytes(snapshot)?;
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.extremum = None;
+    }
+}
+
+/// Encodes an optional raw byte buffer (as held by the Bytes-flavored extremum states) into a
+/// compact snapshot: a presence byte followed by the buffer's own bytes verbatim.
+fn snapshot_bytes(value: &Option<Bytes>) -> Vec<u8> {
+    match value {
+        None => vec![0],
+        Some(v) => {
+            let mut buf = Vec::with_capacity(1 + v.len());
+            buf.push(1);
+            buf.extend_from_slice(v);
+            buf
+        }
+    }
+}
+
+/// The inverse of `snapshot_bytes`.
+fn restore_bytes(snapshot: &[u8]) -> Result<Option<Bytes>> {
+    match snapshot.split_first() {
+        Some((0, rest)) if rest.is_empty() => Ok(None),
+        Some((1, rest)) => Ok(Some(rest.to_vec())),
+        _ => Err(other_err!("corrupted extremum state snapshot")),
+    }
+}
+
+/// Compares two floats the way `AggFnExtremumForReal` needs to, defining a total, deterministic
+/// order where `f64`'s own `PartialOrd` has none: `NaN` sorts as the greatest possible value
+/// (regardless of whether the caller is computing MAX or MIN), matching how MySQL treats `NaN` as
+/// larger than any other value it can produce. `+0.0` and `-0.0` compare equal, same as
+/// `PartialOrd` already treats them, so whichever is seen first is kept as the extremum on a tie.
+fn real_total_cmp(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+/// Like `AggFnExtremum<Real, E>`, but compares via `real_total_cmp` instead of `Real`'s own `Ord`
+/// (which, being backed by `NotNan`, simply cannot represent `NaN` in the first place). Handling
+/// `Real` as its own case here, the same way `AggFnExtremumForBytes` special-cases `Bytes`, keeps
+/// the comparison policy explicit and in one place rather than relying on `Ord`'s behavior for a
+/// type where "the natural order" is genuinely ambiguous for some inputs.
+///
+/// There is no separate pushdown signature for this; the parser routes any MAX/MIN over a `Real`
+/// column here instead of the generic `AggFnExtremum<Real, E>`.
+#[derive(Debug, AggrFunction)]
+#[aggr_function(state = AggFnStateExtremumForReal::<E>::new())]
+pub struct AggFnExtremumForReal<E>
+where
+    E: Extremum,
+{
+    _phantom: std::marker::PhantomData<E>,
+}
+
+impl<E> AggFnExtremumForReal<E>
+where
+    E: Extremum,
+{
+    fn new() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AggFnStateExtremumForReal<E>
+where
+    E: Extremum,
+{
+    extremum: Option<Real>,
+    _phantom: std::marker::PhantomData<E>,
+}
+
+impl<E> AggFnStateExtremumForReal<E>
+where
+    E: Extremum,
+{
+    pub fn new() -> Self {
+        Self {
+            extremum: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E> super::ConcreteAggrFunctionState for AggFnStateExtremumForReal<E>
+where
+    E: Extremum,
+{
+    type ParameterType = Real;
 
     #[inline]
     fn update_concrete(
@@ -161,24 +442,165 @@ where
         _ctx: &mut EvalContext,
         value: &Option<Self::ParameterType>,
     ) -> Result<()> {
-        if value.is_none() {
-            return Ok(());
+        let value = match value {
+            Some(v) => *v,
+            None => return Ok(()),
+        };
+        let replace = match self.extremum {
+            None => true,
+            Some(cur) => real_total_cmp(cur.into_inner(), value.into_inner()) == E::ORD,
+        };
+        if replace {
+            self.extremum = Some(value);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn push_result(&self, _ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
+        target[0].push(self.extremum);
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>> {
+        let mut ctx = EvalContext::default();
+        let datum = match self.extremum {
+            Some(v) => v.to_datum(),
+            None => Datum::Null,
+        };
+        datum::encode_value(&mut ctx, &[datum])
+    }
+
+    fn restore(&mut self, snapshot: &[u8]) -> Result<()> {
+        let mut data = snapshot;
+        let mut datums = datum::decode(&mut data)?;
+        if datums.len() != 1 {
+            return Err(other_err!("corrupted extremum state snapshot"));
+        }
+        self.extremum = match datums.pop().unwrap() {
+            Datum::Null => None,
+            other => Some(Real::from_datum(other)?),
+        };
+        Ok(())
+    }
+}
+
+/// Like `AggFnExtremum<Decimal, E>`, but additionally remembers the largest fractional scale
+/// seen among all non-NULL inputs and rescales the winning value up to it before emitting, so
+/// e.g. `MAX(dec)` over `1.50` and `2.3` returns `2.30` rather than silently dropping `1.50`'s
+/// trailing zero along with the value it lost to. Handling `Decimal` as its own case here, the
+/// same way `AggFnExtremumForReal` special-cases `Real`, keeps this scale bookkeeping out of the
+/// generic `AggFnExtremum<T, E>` path, which has no notion of scale for its other
+/// instantiations.
+///
+/// There is no separate pushdown signature for this; the parser routes any MAX/MIN over a
+/// `Decimal` column here instead of the generic `AggFnExtremum<Decimal, E>`.
+#[derive(Debug, AggrFunction)]
+#[aggr_function(state = AggFnStateExtremumForDecimal::<E>::new())]
+pub struct AggFnExtremumForDecimal<E>
+where
+    E: Extremum,
+{
+    _phantom: std::marker::PhantomData<E>,
+}
+
+impl<E> AggFnExtremumForDecimal<E>
+where
+    E: Extremum,
+{
+    fn new() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
         }
+    }
+}
+
+#[derive(Debug)]
+pub struct AggFnStateExtremumForDecimal<E>
+where
+    E: Extremum,
+{
+    extremum: Option<Decimal>,
+    max_scale: u8,
+    _phantom: std::marker::PhantomData<E>,
+}
 
-        if self.extremum.is_none() {
-            self.extremum = value.clone();
-            return Ok(());
+impl<E> AggFnStateExtremumForDecimal<E>
+where
+    E: Extremum,
+{
+    pub fn new() -> Self {
+        Self {
+            extremum: None,
+            max_scale: 0,
+            _phantom: std::marker::PhantomData,
         }
+    }
+}
 
-        if C::sort_compare(&self.extremum.as_ref().unwrap(), &value.as_ref().unwrap())? == E::ORD {
-            self.extremum = value.clone();
+impl<E> super::ConcreteAggrFunctionState for AggFnStateExtremumForDecimal<E>
+where
+    E: Extremum,
+{
+    type ParameterType = Decimal;
+
+    #[inline]
+    fn update_concrete(
+        &mut self,
+        _ctx: &mut EvalContext,
+        value: &Option<Self::ParameterType>,
+    ) -> Result<()> {
+        let value = match value {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let (_, frac) = value.prec_and_frac();
+        self.max_scale = self.max_scale.max(frac);
+        if self.extremum.is_none() || self.extremum.as_ref().unwrap().cmp(value) == E::ORD {
+            self.extremum = Some(value.clone());
         }
         Ok(())
     }
 
     #[inline]
     fn push_result(&self, _ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
-        target[0].push(self.extremum.clone());
+        let result = match &self.extremum {
+            None => None,
+            Some(v) => {
+                let rescaled: crate::codec::Result<Decimal> = v
+                    .clone()
+                    .round(self.max_scale as i8, RoundMode::HalfEven)
+                    .into();
+                Some(rescaled?)
+            }
+        };
+        target[0].push(result);
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>> {
+        let mut ctx = EvalContext::default();
+        let value_datum = match &self.extremum {
+            Some(v) => v.to_datum(),
+            None => Datum::Null,
+        };
+        datum::encode_value(&mut ctx, &[value_datum, Datum::U64(u64::from(self.max_scale))])
+    }
+
+    fn restore(&mut self, snapshot: &[u8]) -> Result<()> {
+        let mut data = snapshot;
+        let mut datums = datum::decode(&mut data)?;
+        if datums.len() != 2 {
+            return Err(other_err!("corrupted extremum state snapshot"));
+        }
+        self.max_scale = match datums.pop().unwrap() {
+            Datum::U64(v) => v as u8,
+            _ => return Err(other_err!("corrupted extremum state snapshot")),
+        };
+        self.extremum = match datums.pop().unwrap() {
+            Datum::Null => None,
+            other => Some(Decimal::from_datum(other)?),
+        };
         Ok(())
     }
 }
@@ -188,7 +610,7 @@ where
 #[aggr_function(state = AggFnStateExtremum::<T, E>::new())]
 pub struct AggFnExtremum<T, E>
 where
-    T: Evaluable + Ord,
+    T: Evaluable + Ord + ExtremumDatum,
     E: Extremum,
     VectorValue: VectorValueExt<T>,
 {
@@ -197,7 +619,7 @@ where
 
 impl<T, E> AggFnExtremum<T, E>
 where
-    T: Evaluable + Ord,
+    T: Evaluable + Ord + ExtremumDatum,
     E: Extremum,
     VectorValue: VectorValueExt<T>,
 {
@@ -232,39 +654,255 @@ where
             _phantom: std::marker::PhantomData,
         }
     }
+
+    #[inline]
+    fn consider(&mut self, value: &Option<T>) {
+        if value.is_some()
+            && (self.extremum_value.is_none() || self.extremum_value.cmp(value) == E::ORD)
+        {
+            self.extremum_value = value.clone();
+        }
+    }
 }
 
-impl<T, E> super::ConcreteAggrFunctionState for AggFnStateExtremum<T, E>
+// Manually implemented, like `AggrFnStateFirst`, instead of via `ConcreteAggrFunctionState`, so
+// that `update_vector` can scan the whole batch and clone the winning value into place only once,
+// instead of cloning it anew every time a later row in the batch improves on the running extremum.
+impl<T, E> super::AggrFunctionStateUpdatePartial<T> for AggFnStateExtremum<T, E>
 where
     T: Evaluable + Ord,
     E: Extremum,
     VectorValue: VectorValueExt<T>,
 {
-    type ParameterType = T;
+    #[inline]
+    fn update(&mut self, _ctx: &mut EvalContext, value: &Option<T>) -> Result<()> {
+        self.consider(value);
+        Ok(())
+    }
 
     #[inline]
-    fn update_concrete(
+    fn update_repeat(
         &mut self,
         _ctx: &mut EvalContext,
-        value: &Option<Self::ParameterType>,
+        value: &Option<T>,
+        repeat_times: usize,
     ) -> Result<()> {
-        if value.is_some()
-            && (self.extremum_value.is_none() || self.extremum_value.cmp(value) == E::ORD)
-        {
-            self.extremum_value = value.clone();
-        }
+        assert!(repeat_times > 0);
+        // Every row in the run carries the same value, so considering it once has the same effect
+        // as considering it `repeat_times` times.
+        self.consider(value);
         Ok(())
     }
 
     #[inline]
+    fn update_vector(
+        &mut self,
+        _ctx: &mut EvalContext,
+        physical_values: &[Option<T>],
+        logical_rows: &[usize],
+    ) -> Result<()> {
+        let winner = {
+            let mut best: Option<&T> = self.extremum_value.as_ref();
+            for physical_index in logical_rows {
+                if let Some(v) = &physical_values[*physical_index] {
+                    if best.is_none() || best.unwrap().cmp(v) == E::ORD {
+                        best = Some(v);
+                    }
+                }
+            }
+            best.cloned()
+        };
+        self.extremum_value = winner;
+        Ok(())
+    }
+}
+
+// In order to make `AggFnStateExtremum` satisfy the `AggrFunctionState` trait, we default impl all
+// `AggrFunctionStateUpdatePartial` of `Evaluable` for all `AggFnStateExtremum`, mirroring
+// `AggrFnStateFirst`'s own such impl.
+impl<T1, T2, E> super::AggrFunctionStateUpdatePartial<T1> for AggFnStateExtremum<T2, E>
+where
+    T1: Evaluable,
+    T2: Evaluable + Ord,
+    E: Extremum,
+    VectorValue: VectorValueExt<T2>,
+{
+    #[inline]
+    default fn update(&mut self, _ctx: &mut EvalContext, _value: &Option<T1>) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+
+    #[inline]
+    default fn update_repeat(
+        &mut self,
+        _ctx: &mut EvalContext,
+        _value: &Option<T1>,
+        _repeat_times: usize,
+    ) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+
+    #[inline]
+    default fn update_vector(
+        &mut self,
+        _ctx: &mut EvalContext,
+        _physical_values: &[Option<T1>],
+        _logical_rows: &[usize],
+    ) -> Result<()> {
+        panic!("Unmatched parameter type")
+    }
+}
+
+impl<T, E> super::AggrFunctionState for AggFnStateExtremum<T, E>
+where
+    T: Evaluable + Ord + ExtremumDatum,
+    E: Extremum,
+    VectorValue: VectorValueExt<T>,
+{
     fn push_result(&self, _ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
         target[0].push(self.extremum_value.clone());
         Ok(())
     }
+
+    fn approximate_mem_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>> {
+        let mut ctx = EvalContext::default();
+        let datum = match &self.extremum_value {
+            Some(v) => v.to_datum(),
+            None => Datum::Null,
+        };
+        datum::encode_value(&mut ctx, &[datum])
+    }
+
+    fn restore(&mut self, snapshot: &[u8]) -> Result<()> {
+        let mut data = snapshot;
+        let mut datums = datum::decode(&mut data)?;
+        if datums.len() != 1 {
+            return Err(other_err!("corrupted extremum state snapshot"));
+        }
+        self.extremum_value = match datums.pop().unwrap() {
+            Datum::Null => None,
+            other => Some(T::from_datum(other)?),
+        };
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.extremum_value = None;
+    }
+}
+
+/// Bridges an `AggFnStateExtremum` payload type to `Datum`, the codec's self-describing
+/// on-the-wire value representation, so state snapshots can reuse it instead of a bespoke binary
+/// format per data type.
+trait ExtremumDatum: Sized {
+    fn to_datum(&self) -> Datum;
+    fn from_datum(datum: Datum) -> Result<Self>;
+}
+
+impl ExtremumDatum for Int {
+    fn to_datum(&self) -> Datum {
+        Datum::I64(*self)
+    }
+
+    fn from_datum(datum: Datum) -> Result<Self> {
+        match datum {
+            Datum::I64(v) => Ok(v),
+            _ => Err(other_err!("corrupted extremum state snapshot")),
+        }
+    }
+}
+
+// `AggFnExtremum<Bytes, E>` is never actually constructed (the parser always routes `Bytes` to
+// `AggFnExtremumForBytes` instead), but `match_template_evaluable!` still type-checks a `Bytes`
+// arm for every instantiation site, so `Bytes` needs an impl here too.
+impl ExtremumDatum for Bytes {
+    fn to_datum(&self) -> Datum {
+        Datum::Bytes(self.clone())
+    }
+
+    fn from_datum(datum: Datum) -> Result<Self> {
+        match datum {
+            Datum::Bytes(v) => Ok(v),
+            _ => Err(other_err!("corrupted extremum state snapshot")),
+        }
+    }
+}
+
+impl ExtremumDatum for Real {
+    fn to_datum(&self) -> Datum {
+        Datum::F64(self.into_inner())
+    }
+
+    fn from_datum(datum: Datum) -> Result<Self> {
+        match datum {
+            Datum::F64(v) => {
+                Real::new(v).map_err(|_| other_err!("corrupted extremum state snapshot"))
+            }
+            _ => Err(other_err!("corrupted extremum state snapshot")),
+        }
+    }
+}
+
+impl ExtremumDatum for Decimal {
+    fn to_datum(&self) -> Datum {
+        Datum::Dec(self.clone())
+    }
+
+    fn from_datum(datum: Datum) -> Result<Self> {
+        match datum {
+            Datum::Dec(v) => Ok(v),
+            _ => Err(other_err!("corrupted extremum state snapshot")),
+        }
+    }
+}
+
+impl ExtremumDatum for DateTime {
+    fn to_datum(&self) -> Datum {
+        Datum::Time(self.clone())
+    }
+
+    fn from_datum(datum: Datum) -> Result<Self> {
+        match datum {
+            Datum::Time(v) => Ok(v),
+            _ => Err(other_err!("corrupted extremum state snapshot")),
+        }
+    }
+}
+
+impl ExtremumDatum for Duration {
+    fn to_datum(&self) -> Datum {
+        Datum::Dur(*self)
+    }
+
+    fn from_datum(datum: Datum) -> Result<Self> {
+        match datum {
+            Datum::Dur(v) => Ok(v),
+            _ => Err(other_err!("corrupted extremum state snapshot")),
+        }
+    }
+}
+
+impl ExtremumDatum for Json {
+    fn to_datum(&self) -> Datum {
+        Datum::Json(self.clone())
+    }
+
+    fn from_datum(datum: Datum) -> Result<Self> {
+        match datum {
+            Datum::Json(v) => Ok(v),
+            _ => Err(other_err!("corrupted extremum state snapshot")),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use tidb_query_datatype::EvalType;
     use tipb_helper::ExprDefBuilder;
 
@@ -324,6 +962,64 @@ mod tests {
         assert_eq!(result[0].as_int_slice(), &[Some(40)]);
     }
 
+    #[test]
+    fn test_max_reset_is_independent_of_prior_partition() {
+        let mut ctx = EvalContext::default();
+        let function = AggFnExtremum::<Int, Max>::new();
+        let mut state = function.create_state();
+
+        state.update(&mut ctx, &Some(100i64)).unwrap();
+        let mut result = [VectorValue::with_capacity(0, EvalType::Int)];
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[Some(100)]);
+
+        state.reset();
+
+        state.update(&mut ctx, &Some(3i64)).unwrap();
+        result[0].clear();
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(result[0].as_int_slice(), &[Some(3)]);
+    }
+
+    #[test]
+    fn test_max_update_vector_matches_row_by_row_update_on_large_batch() {
+        // `update_vector`'s single-scan short-circuit should agree with feeding the same values
+        // through `update` one at a time, including on a batch large enough (10k rows) that a
+        // regression re-cloning the running extremum on every improving row would actually show up
+        // in a profile, and on a sorted-descending column, where only the very first row ever
+        // improves on the running extremum.
+        let mut ctx = EvalContext::default();
+        let values: Vec<Option<Int>> = (0..10_000i64).rev().map(Some).collect();
+        let logical_rows: Vec<usize> = (0..values.len()).collect();
+
+        let vectorized = AggFnExtremum::<Int, Max>::new();
+        let mut vectorized_state = vectorized.create_state();
+        vectorized_state
+            .update_vector(&mut ctx, &values, &logical_rows)
+            .unwrap();
+
+        let row_by_row = AggFnExtremum::<Int, Max>::new();
+        let mut row_by_row_state = row_by_row.create_state();
+        for v in &values {
+            row_by_row_state.update(&mut ctx, v).unwrap();
+        }
+
+        let mut vectorized_result = [VectorValue::with_capacity(0, EvalType::Int)];
+        vectorized_state
+            .push_result(&mut ctx, &mut vectorized_result)
+            .unwrap();
+        let mut row_by_row_result = [VectorValue::with_capacity(0, EvalType::Int)];
+        row_by_row_state
+            .push_result(&mut ctx, &mut row_by_row_result)
+            .unwrap();
+
+        assert_eq!(
+            vectorized_result[0].as_int_slice(),
+            row_by_row_result[0].as_int_slice()
+        );
+        assert_eq!(vectorized_result[0].as_int_slice(), &[Some(9999)]);
+    }
+
     #[test]
     fn test_min() {
         let mut ctx = EvalContext::default();
@@ -379,6 +1075,84 @@ mod tests {
         assert_eq!(result[0].as_int_slice(), &[Some(-1i64)]);
     }
 
+    #[test]
+    fn test_max_decimal_preserves_widest_scale() {
+        let mut ctx = EvalContext::default();
+        let function = AggFnExtremumForDecimal::<Max>::new();
+        let mut state = function.create_state();
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Decimal)];
+
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(result[0].as_decimal_slice(), &[None]);
+
+        // `2.3` is the numerically larger value, but `1.50`'s scale should still be reflected in
+        // the emitted result.
+        state
+            .update(&mut ctx, &Some(Decimal::from_str("1.50").unwrap()))
+            .unwrap();
+        state
+            .update(&mut ctx, &Some(Decimal::from_str("2.3").unwrap()))
+            .unwrap();
+
+        result[0].clear();
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(
+            result[0].as_decimal_slice(),
+            &[Some(Decimal::from_str("2.30").unwrap())]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut ctx = EvalContext::default();
+        let all_updates = [Some(7i64), None, Some(30i64), Some(5i64), None, Some(42i64)];
+
+        // An uninterrupted run over the whole input.
+        let uninterrupted = AggFnExtremum::<Int, Max>::new();
+        let mut uninterrupted_state = uninterrupted.create_state();
+        for v in &all_updates {
+            uninterrupted_state.update(&mut ctx, v).unwrap();
+        }
+        let mut want = [VectorValue::with_capacity(0, EvalType::Int)];
+        uninterrupted_state.push_result(&mut ctx, &mut want).unwrap();
+
+        // A run that gets snapshotted partway through, reconstructed from that snapshot, and then
+        // fed the rest of the same input.
+        let (before, after) = all_updates.split_at(3);
+        let resumed = AggFnExtremum::<Int, Max>::new();
+        let mut resumed_state = resumed.create_state();
+        for v in before {
+            resumed_state.update(&mut ctx, v).unwrap();
+        }
+        let snapshot = resumed_state.snapshot().unwrap();
+
+        let fresh = AggFnExtremum::<Int, Max>::new();
+        let mut restored_state = fresh.create_state();
+        restored_state.restore(&snapshot).unwrap();
+        for v in after {
+            restored_state.update(&mut ctx, v).unwrap();
+        }
+
+        let mut got = [VectorValue::with_capacity(0, EvalType::Int)];
+        restored_state.push_result(&mut ctx, &mut got).unwrap();
+        assert_eq!(got[0].as_int_slice(), want[0].as_int_slice());
+        assert_eq!(got[0].as_int_slice(), &[Some(42)]);
+
+        // Snapshotting an empty state and restoring it should round-trip to the same empty state.
+        let empty = AggFnExtremum::<Int, Max>::new();
+        let empty_state = empty.create_state();
+        let empty_snapshot = empty_state.snapshot().unwrap();
+
+        let mut restored_empty = AggFnExtremum::<Int, Max>::new().create_state();
+        restored_empty.restore(&empty_snapshot).unwrap();
+        let mut empty_result = [VectorValue::with_capacity(0, EvalType::Int)];
+        restored_empty
+            .push_result(&mut ctx, &mut empty_result)
+            .unwrap();
+        assert_eq!(empty_result[0].as_int_slice(), &[None]);
+    }
+
     #[test]
     fn test_collation() {
         let mut ctx = EvalContext::default();
@@ -423,6 +1197,61 @@ mod tests {
         }
     }
 
+    /// `update_vector` finds the batch's own winner before ever comparing against
+    /// `self.extremum`, so a batch full of large non-winning values should still produce the
+    /// correct extremum after the one true winner arrives, no matter where in the batch it is.
+    #[test]
+    fn test_update_vector_skips_cloning_non_winning_large_values() {
+        let mut ctx = EvalContext::default();
+        let function = Box::new(AggFnExtremumForBytes::<CollatorBinary, Max>::new())
+            as Box<dyn AggrFunction>;
+        let mut state = function.create_state();
+
+        // A batch of many large, strictly smaller values, with the single winner buried in the
+        // middle. Correctness must not depend on the winner's position in the batch.
+        let mut physical_values: Vec<Option<Bytes>> = (0..500)
+            .map(|i| Some(vec![b'a'; 4096].into_iter().chain(vec![i as u8]).collect()))
+            .collect();
+        let winner = vec![b'z'; 4096];
+        physical_values[250] = Some(winner.clone());
+        let logical_rows: Vec<usize> = (0..physical_values.len()).collect();
+
+        state
+            .update_vector(&mut ctx, &physical_values, &logical_rows)
+            .unwrap();
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Bytes)];
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(result[0].as_bytes_slice(), &[Some(winner)]);
+    }
+
+    #[test]
+    fn test_mem_tracker_trips_on_many_large_bytes_states() {
+        use crate::aggr_fn::{AggrFunctionState, AggrFunctionStateUpdatePartial};
+        use crate::batch::executors::util::aggr_mem_tracker::AggrMemTracker;
+
+        let mut ctx = EvalContext::default();
+        let large_value = vec![0u8; 1024];
+        // Each state's approximate_mem_bytes is roughly size_of::<State>() + the buffered value's
+        // capacity, so this limit is comfortably crossed well before the 1000th state.
+        let mut tracker = AggrMemTracker::new(64 * 1024);
+
+        let mut created = 0;
+        for _ in 0..1000 {
+            let mut state = AggFnStateExtremum4Bytes::<CollatorBinary, Max>::new();
+            state
+                .update(&mut ctx, &Some(large_value.clone()))
+                .unwrap();
+            created += 1;
+            if tracker.alloc(state.approximate_mem_bytes()).is_err() {
+                break;
+            }
+        }
+
+        assert!(created < 1000);
+        assert!(tracker.used_bytes() > 64 * 1024);
+    }
+
     #[test]
     fn test_integration() {
         let max_parser = AggrFnDefinitionParserExtremum::<Max>::new();
@@ -506,6 +1335,99 @@ mod tests {
         assert_eq!(aggr_result[0].as_int_slice(), &[Some(99), Some(-1i64),]);
     }
 
+    #[test]
+    fn test_real_total_cmp() {
+        assert_eq!(real_total_cmp(f64::NAN, 1.0), Ordering::Greater);
+        assert_eq!(real_total_cmp(1.0, f64::NAN), Ordering::Less);
+        assert_eq!(real_total_cmp(f64::NAN, f64::NAN), Ordering::Equal);
+        assert_eq!(real_total_cmp(f64::NAN, f64::INFINITY), Ordering::Greater);
+
+        assert_eq!(real_total_cmp(f64::INFINITY, f64::MAX), Ordering::Greater);
+        assert_eq!(
+            real_total_cmp(f64::NEG_INFINITY, f64::MIN),
+            Ordering::Less
+        );
+
+        assert_eq!(real_total_cmp(0.0, -0.0), Ordering::Equal);
+        assert_eq!(real_total_cmp(-0.0, 0.0), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_extremum_real_nan_and_infinities() {
+        let mut ctx = EvalContext::default();
+
+        // `Real` (`NotNan<f64>`) can never itself hold `NaN`, so its extremum can only ever be
+        // exercised with the finite/infinite values below; `real_total_cmp`'s own NaN handling is
+        // covered directly by `test_real_total_cmp`.
+        let values = vec![
+            Real::new(1.0).ok(),
+            Real::new(f64::NEG_INFINITY).ok(),
+            Real::new(-3.5).ok(),
+            Real::new(f64::INFINITY).ok(),
+            Real::new(2.0).ok(),
+        ];
+
+        let max_function = AggFnExtremumForReal::<Max>::new();
+        let mut max_state = max_function.create_state();
+        for v in &values {
+            max_state.update(&mut ctx, v).unwrap();
+        }
+        let mut max_result = [VectorValue::with_capacity(0, EvalType::Real)];
+        max_state.push_result(&mut ctx, &mut max_result).unwrap();
+        assert_eq!(
+            max_result[0].as_real_slice(),
+            &[Real::new(f64::INFINITY).ok()]
+        );
+
+        let min_function = AggFnExtremumForReal::<Min>::new();
+        let mut min_state = min_function.create_state();
+        for v in &values {
+            min_state.update(&mut ctx, v).unwrap();
+        }
+        let mut min_result = [VectorValue::with_capacity(0, EvalType::Real)];
+        min_state.push_result(&mut ctx, &mut min_result).unwrap();
+        assert_eq!(
+            min_result[0].as_real_slice(),
+            &[Real::new(f64::NEG_INFINITY).ok()]
+        );
+    }
+
+    #[test]
+    fn test_extremum_real_signed_zero() {
+        use crate::aggr_fn::ConcreteAggrFunctionState;
+
+        let mut ctx = EvalContext::default();
+
+        // `+0.0` and `-0.0` compare equal, so whichever is seen first is kept.
+        let mut state = AggFnStateExtremumForReal::<Max>::new();
+        state.update_concrete(&mut ctx, &Real::new(0.0).ok()).unwrap();
+        state
+            .update_concrete(&mut ctx, &Real::new(-0.0).ok())
+            .unwrap();
+        assert_eq!(state.extremum.unwrap().into_inner().is_sign_positive(), true);
+
+        let mut state = AggFnStateExtremumForReal::<Min>::new();
+        state.update_concrete(&mut ctx, &Real::new(-0.0).ok()).unwrap();
+        state.update_concrete(&mut ctx, &Real::new(0.0).ok()).unwrap();
+        assert_eq!(state.extremum.unwrap().into_inner().is_sign_negative(), true);
+    }
+
+    #[test]
+    fn test_enum_column_not_supported() {
+        // `MAX(enum_col)` cannot be supported end-to-end in this codebase yet: `codec::table`
+        // has no decoder for `FieldTypeTp::Enum`, so there is no way to obtain the column's
+        // value inside the coprocessor at all, regardless of how the aggregate function itself
+        // compares or emits it. `check_supported` reports this plainly rather than the request
+        // failing later with a generic "unsupported type" error out of `EvalType::try_from`.
+        let expr = ExprDefBuilder::aggr_func(ExprType::Max, FieldTypeTp::Enum)
+            .push_child(ExprDefBuilder::column_ref(0, FieldTypeTp::Enum))
+            .build();
+        let err = AggrFnDefinitionParserExtremum::<Max>::new()
+            .check_supported(&expr)
+            .unwrap_err();
+        assert!(format!("{}", err).contains("Enum"));
+    }
+
     #[test]
     fn test_illegal_request() {
         let expr = ExprDefBuilder::aggr_func(ExprType::Max, FieldTypeTp::Double) // Expect LongLong but give Real
@@ -523,4 +1445,32 @@ mod tests {
             .parse(expr, &mut ctx, &src_schema, &mut schema, &mut exp)
             .unwrap_err();
     }
+
+    #[test]
+    fn test_illegal_request_mismatched_collation() {
+        let child_ft = tidb_query_datatype::builder::FieldTypeBuilder::new()
+            .tp(FieldTypeTp::VarString)
+            .collation(Collation::Utf8Mb4Bin)
+            .build();
+        let out_ft = tidb_query_datatype::builder::FieldTypeBuilder::new()
+            .tp(FieldTypeTp::VarString)
+            .collation(Collation::Utf8Mb4GeneralCi)
+            .build();
+
+        let expr = ExprDefBuilder::aggr_func(ExprType::Max, out_ft)
+            .push_child(ExprDefBuilder::column_ref(0, child_ft.clone()))
+            .build();
+        AggrFnDefinitionParserExtremum::<Max>::new()
+            .check_supported(&expr)
+            .unwrap();
+
+        let src_schema = [child_ft];
+        let mut schema = vec![];
+        let mut exp = vec![];
+        let mut ctx = EvalContext::default();
+        AggrFnDefinitionParserExtremum::<Max>::new()
+            .parse(expr, &mut ctx, &src_schema, &mut schema, &mut exp)
+            .unwrap_err();
+    }
+
 }
@@ -143,6 +143,101 @@ where
     }
 }
 
+/// A SUM(Int) implementation that accumulates as `i64` and only promotes to `Decimal` if the
+/// running total would overflow, mirroring how MySQL/TiDB's `SUM(int)` never actually loses
+/// precision to overflow.
+///
+/// Note: `AggrFnDefinitionParserSum` above never constructs this type. The parser rewrites an
+/// `Int` operand into `CAST(<operand> AS DECIMAL)` via `rewrite_exp_for_sum_avg` before choosing
+/// a state, so the operand it dispatches on is always `Decimal` or `Real` — the `ExprType::Sum`
+/// dispatch path never reaches a native, overflow-checked `i64` accumulator.
+/// `AggrFnSumInt` is provided for direct construction only. Its result is always `Decimal`,
+/// matching the column type TiDB actually assigns `SUM(int)`.
+#[derive(Debug)]
+pub struct AggrFnSumInt;
+
+impl AggrFnSumInt {
+    pub fn new() -> Self {
+        AggrFnSumInt
+    }
+}
+
+impl super::AggrFunction for AggrFnSumInt {
+    fn name(&self) -> &'static str {
+        "AggrFnSumInt"
+    }
+
+    fn create_state(&self) -> Box<dyn super::AggrFunctionState> {
+        Box::new(AggrFnStateSumInt::new())
+    }
+}
+
+/// The running total kept by `AggrFnStateSumInt`: an `i64` until adding the next value would
+/// overflow it, then a `Decimal` for the remainder of the group.
+#[derive(Debug)]
+enum SumIntAcc {
+    Int(i64),
+    Decimal(Decimal),
+}
+
+/// The state of `AggrFnSumInt`.
+#[derive(Debug)]
+pub struct AggrFnStateSumInt {
+    acc: SumIntAcc,
+    has_value: bool,
+}
+
+impl AggrFnStateSumInt {
+    pub fn new() -> Self {
+        Self {
+            acc: SumIntAcc::Int(0),
+            has_value: false,
+        }
+    }
+}
+
+impl super::ConcreteAggrFunctionState for AggrFnStateSumInt {
+    type ParameterType = Int;
+
+    #[inline]
+    fn update_concrete(&mut self, _ctx: &mut EvalContext, value: &Option<Int>) -> Result<()> {
+        let value = match value {
+            None => return Ok(()),
+            Some(value) => *value,
+        };
+        self.has_value = true;
+        self.acc = match &self.acc {
+            SumIntAcc::Int(sum) => match sum.checked_add(value) {
+                Some(sum) => SumIntAcc::Int(sum),
+                None => {
+                    let r: crate::codec::Result<Decimal> =
+                        (&Decimal::from(*sum) + &Decimal::from(value)).into();
+                    SumIntAcc::Decimal(r?)
+                }
+            },
+            SumIntAcc::Decimal(sum) => {
+                let r: crate::codec::Result<Decimal> = (sum + &Decimal::from(value)).into();
+                SumIntAcc::Decimal(r?)
+            }
+        };
+        Ok(())
+    }
+
+    #[inline]
+    fn push_result(&self, _ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
+        if !self.has_value {
+            target[0].push(None);
+        } else {
+            let sum = match self.acc {
+                SumIntAcc::Int(sum) => Decimal::from(sum),
+                SumIntAcc::Decimal(ref sum) => sum.clone(),
+            };
+            target[0].push(Some(sum));
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,6 +297,59 @@ mod tests {
         assert_eq!(aggr_result[0].as_real_slice(), &[Real::new(54.5).ok()]);
     }
 
+    #[test]
+    fn test_sum_int_promotes_to_decimal_on_overflow() {
+        let mut ctx = EvalContext::default();
+        let function = AggrFnSumInt::new();
+        let mut state = function.create_state();
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Decimal)];
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(result[0].as_decimal_slice(), &[None]);
+
+        // Stays within i64 range: no promotion needed yet.
+        state.update(&mut ctx, &Some(i64::MAX - 10)).unwrap();
+        state.update(&mut ctx, &Option::<Int>::None).unwrap();
+
+        result[0].clear();
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(
+            result[0].as_decimal_slice(),
+            &[Some(Decimal::from(i64::MAX - 10))]
+        );
+
+        // This addition overflows i64, so the state must promote to Decimal.
+        state.update(&mut ctx, &Some(20i64)).unwrap();
+
+        result[0].clear();
+        state.push_result(&mut ctx, &mut result).unwrap();
+        let expected: crate::codec::Result<Decimal> =
+            (&Decimal::from(i64::MAX - 10) + &Decimal::from(20i64)).into();
+        assert_eq!(result[0].as_decimal_slice(), &[Some(expected.unwrap())]);
+
+        // Further additions keep accumulating in Decimal.
+        state.update(&mut ctx, &Some(5i64)).unwrap();
+        result[0].clear();
+        state.push_result(&mut ctx, &mut result).unwrap();
+        let expected: crate::codec::Result<Decimal> = (&expected.unwrap() + &Decimal::from(5i64))
+            .into();
+        assert_eq!(result[0].as_decimal_slice(), &[Some(expected.unwrap())]);
+    }
+
+    #[test]
+    fn test_sum_int_all_null_returns_null() {
+        let mut ctx = EvalContext::default();
+        let function = AggrFnSumInt::new();
+        let mut state = function.create_state();
+
+        state.update(&mut ctx, &Option::<Int>::None).unwrap();
+        state.update(&mut ctx, &Option::<Int>::None).unwrap();
+
+        let mut result = [VectorValue::with_capacity(0, EvalType::Decimal)];
+        state.push_result(&mut ctx, &mut result).unwrap();
+        assert_eq!(result[0].as_decimal_slice(), &[None]);
+    }
+
     #[test]
     fn test_illegal_request() {
         let expr = ExprDefBuilder::aggr_func(ExprType::Sum, FieldTypeTp::Double) // Expect NewDecimal but give Double
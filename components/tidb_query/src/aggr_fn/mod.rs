@@ -2,10 +2,12 @@
 
 //! This module provides aggregate functions for batch executors.
 
+mod impl_any_value;
 mod impl_avg;
 mod impl_bit_op;
 mod impl_count;
 mod impl_first;
+mod impl_group_concat;
 mod impl_max_min;
 mod impl_sum;
 mod parser;
@@ -64,6 +66,26 @@ pub trait AggrFunctionState:
     // TODO: A better implementation is to specialize different push result targets. However
     // current aggregation executor cannot utilize it.
     fn push_result(&self, ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()>;
+
+    /// Returns an approximate size, in bytes, of the memory this state currently occupies.
+    ///
+    /// This only needs to be roughly accurate: it is meant to feed a memory limiter (see
+    /// `AggrMemTracker` in the batch executor utilities) that decides when a hash aggregation
+    /// has accumulated too many groups, not to account for every byte precisely.
+    fn approximate_mem_bytes(&self) -> usize;
+
+    /// See `ConcreteAggrFunctionState::snapshot`.
+    fn snapshot(&self) -> Result<Vec<u8>>;
+
+    /// See `ConcreteAggrFunctionState::restore`.
+    fn restore(&mut self, snapshot: &[u8]) -> Result<()>;
+
+    /// See `ConcreteAggrFunctionState::reset`.
+    ///
+    /// The default assumes the state does not support resetting.
+    fn reset(&mut self) {
+        panic!("state reset is not supported by this aggregate function")
+    }
 }
 
 /// A helper trait for single parameter aggregate function states that only work over concrete eval
@@ -83,6 +105,43 @@ pub trait ConcreteAggrFunctionState: std::fmt::Debug + Send + 'static {
     ) -> Result<()>;
 
     fn push_result(&self, ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()>;
+
+    /// The default assumes the state has no heap-allocated buffers beyond its own struct size.
+    /// States that buffer variable-length data (e.g. `Bytes`) should override this to also
+    /// account for that allocation.
+    fn approximate_mem_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    /// Produces a compact, self-contained snapshot of this state's data, suitable for a paused
+    /// coprocessor request to persist and later reconstruct an equivalent state from. This
+    /// checkpoints a single state; it is unrelated to merging two partial states together.
+    ///
+    /// The default assumes the state does not support snapshotting.
+    fn snapshot(&self) -> Result<Vec<u8>> {
+        Err(other_err!(
+            "state snapshot is not supported by this aggregate function"
+        ))
+    }
+
+    /// Restores state previously produced by `snapshot`. Should only be called on a state that
+    /// was just created via `AggrFunction::create_state`, before any `update_concrete` call.
+    ///
+    /// The default assumes the state does not support snapshotting.
+    fn restore(&mut self, _snapshot: &[u8]) -> Result<()> {
+        Err(other_err!(
+            "state snapshot is not supported by this aggregate function"
+        ))
+    }
+
+    /// Puts this state back into the same condition as a freshly created one, so a windowed or
+    /// streaming executor can reuse it across window partitions instead of allocating a new
+    /// state for every partition.
+    ///
+    /// The default assumes the state does not support resetting.
+    fn reset(&mut self) {
+        panic!("state reset is not supported by this aggregate function")
+    }
 }
 
 /// A helper trait that provides `update()` and `update_vector()` over a concrete type, which will
@@ -199,6 +258,22 @@ where
     fn push_result(&self, ctx: &mut EvalContext, target: &mut [VectorValue]) -> Result<()> {
         <Self as ConcreteAggrFunctionState>::push_result(self, ctx, target)
     }
+
+    fn approximate_mem_bytes(&self) -> usize {
+        <Self as ConcreteAggrFunctionState>::approximate_mem_bytes(self)
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>> {
+        <Self as ConcreteAggrFunctionState>::snapshot(self)
+    }
+
+    fn restore(&mut self, snapshot: &[u8]) -> Result<()> {
+        <Self as ConcreteAggrFunctionState>::restore(self, snapshot)
+    }
+
+    fn reset(&mut self) {
+        <Self as ConcreteAggrFunctionState>::reset(self)
+    }
 }
 
 #[cfg(test)]
@@ -2,9 +2,10 @@
 
 use std::convert::TryFrom;
 
+use codec::prelude::NumberDecoder;
 use tidb_query_datatype::builder::FieldTypeBuilder;
 use tidb_query_datatype::{EvalType, FieldTypeAccessor, FieldTypeTp};
-use tipb::{Expr, FieldType};
+use tipb::{Expr, ExprType, FieldType};
 
 use crate::rpn_expr::impl_cast::get_cast_fn_rpn_node;
 use crate::rpn_expr::{RpnExpression, RpnExpressionBuilder};
@@ -26,6 +27,39 @@ pub fn check_aggr_exp_supported_one_child(aggr_def: &Expr) -> Result<()> {
     Ok(())
 }
 
+/// Extracts an `i64` literal parameter carried by an aggregate function definition, e.g. a
+/// numeric cap or bucket count passed as a non-column argument.
+///
+/// The literal is expected to be encoded as an `Int64` constant expression, which is how TiDB
+/// pushes down non-column arguments to a coprocessor request.
+pub fn extract_int_constant(expr: &Expr) -> Result<i64> {
+    if expr.get_tp() != ExprType::Int64 {
+        return Err(other_err!(
+            "Expect an int64 literal parameter, but got {:?}",
+            expr.get_tp()
+        ));
+    }
+    expr.get_val()
+        .read_i64()
+        .map_err(|_| other_err!("Unable to decode int64 literal parameter"))
+}
+
+/// Extracts a `Bytes` literal parameter carried by an aggregate function definition, e.g. the
+/// separator of `AggFnGroupConcat`.
+///
+/// The literal is expected to be encoded as a `String` or `Bytes` constant expression, which is
+/// how TiDB pushes down non-column string arguments to a coprocessor request. Unlike numeric
+/// constants, the raw bytes of the literal are stored directly in `expr.get_val()`.
+pub fn extract_bytes_constant(expr: &Expr) -> Result<Vec<u8>> {
+    match expr.get_tp() {
+        ExprType::String | ExprType::Bytes => Ok(expr.get_val().to_vec()),
+        tp => Err(other_err!(
+            "Expect a string literal parameter, but got {:?}",
+            tp
+        )),
+    }
+}
+
 /// Rewrites the expression to insert necessary cast functions for SUM and AVG aggregate functions.
 ///
 /// See `typeInfer4Sum` and `typeInfer4Avg` in TiDB.
@@ -9,7 +9,7 @@ mod slow_hash_aggr_executor;
 mod stream_aggr_executor;
 mod table_scan_executor;
 mod top_n_executor;
-mod util;
+pub(crate) mod util;
 
 pub use self::fast_hash_aggr_executor::BatchFastHashAggregationExecutor;
 pub use self::index_scan_executor::BatchIndexScanExecutor;
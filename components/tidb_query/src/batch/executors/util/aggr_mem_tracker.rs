@@ -0,0 +1,83 @@
+// Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
+
+use crate::Result;
+
+/// Tracks the approximate total memory occupied by all group states of a hash aggregation,
+/// returning an error once a configured limit is crossed.
+///
+/// A hash-agg executor calls `alloc()` when it creates a new group's states, and `realloc()`
+/// when an existing group's states may have grown (e.g. a `Bytes` extremum state buffering a
+/// longer value), summing each state's `AggrFunctionState::approximate_mem_bytes()`.
+///
+/// This is not yet wired into `slow_hash_aggr_executor`/`fast_hash_aggr_executor`; spilling on top
+/// of it is future work.
+#[derive(Debug)]
+pub struct AggrMemTracker {
+    limit: usize,
+    used: usize,
+}
+
+impl AggrMemTracker {
+    /// A `limit` of `0` disables tracking: `alloc()`/`realloc()` will never return an error.
+    pub fn new(limit: usize) -> Self {
+        Self { limit, used: 0 }
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.used
+    }
+
+    /// Accounts for `bytes` of newly created state memory.
+    pub fn alloc(&mut self, bytes: usize) -> Result<()> {
+        self.used += bytes;
+        self.check_limit()
+    }
+
+    /// Updates the tracked total for a group whose states used to occupy `old_bytes` and now
+    /// occupy `new_bytes`.
+    pub fn realloc(&mut self, old_bytes: usize, new_bytes: usize) -> Result<()> {
+        self.used = self.used.saturating_sub(old_bytes) + new_bytes;
+        self.check_limit()
+    }
+
+    fn check_limit(&self) -> Result<()> {
+        if self.limit > 0 && self.used > self.limit {
+            return Err(other_err!(
+                "memory limit exceeded when creating aggregate group states: used {} bytes, limit {} bytes",
+                self.used,
+                self.limit
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trips_limit() {
+        let mut tracker = AggrMemTracker::new(100);
+        tracker.alloc(40).unwrap();
+        tracker.alloc(40).unwrap();
+        assert!(tracker.alloc(40).is_err());
+    }
+
+    #[test]
+    fn test_disabled_when_limit_is_zero() {
+        let mut tracker = AggrMemTracker::new(0);
+        tracker.alloc(std::usize::MAX / 2).unwrap();
+        tracker.alloc(std::usize::MAX / 2).unwrap();
+        assert!(tracker.used_bytes() > 0);
+    }
+
+    #[test]
+    fn test_realloc_accounts_for_growth() {
+        let mut tracker = AggrMemTracker::new(100);
+        tracker.alloc(50).unwrap();
+        assert!(tracker.realloc(50, 40).is_ok());
+        assert_eq!(tracker.used_bytes(), 40);
+        assert!(tracker.realloc(40, 200).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright 2019 TiKV Project Authors. Licensed under Apache-2.0.
 
 pub mod aggr_executor;
+pub mod aggr_mem_tracker;
 pub mod hash_aggr_helper;
 #[cfg(test)]
 pub mod mock_executor;
@@ -70,6 +70,19 @@ pub trait Collator: 'static + std::marker::Send + std::marker::Sync + std::fmt::
     fn sort_hash<H: Hasher>(state: &mut H, bstr: &[u8]) -> Result<()>;
 }
 
+/// Returns whether `candidate` should replace `current` as the running extremum under collation
+/// `C`, i.e. whether `current`'s SortKey compares to `candidate`'s as `ord` (`Ordering::Less` for
+/// MAX, `Ordering::Greater` for MIN). Extracted out of `AggFnStateExtremum4Bytes::consider`
+/// so other collation-aware aggregates (e.g. an ordered `GROUP_CONCAT`) can share the same
+/// `C::sort_compare` boilerplate without redoing the `match_template_collator!` dispatch.
+pub fn collation_extremum<C: Collator>(
+    current: &[u8],
+    candidate: &[u8],
+    ord: Ordering,
+) -> Result<bool> {
+    Ok(C::sort_compare(current, candidate)? == ord)
+}
+
 /// Collator for binary collation without padding.
 #[derive(Debug)]
 pub struct CollatorBinary;
@@ -226,3 +239,33 @@ where
         &self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collation_extremum() {
+        // Under `Utf8Mb4GeneralCi`, `"a"` and `"A"` are equal, so `"A"` doesn't beat `"a"` for
+        // either MAX or MIN.
+        assert_eq!(
+            collation_extremum::<CollatorUtf8Mb4GeneralCi>(b"a", b"A", Ordering::Less).unwrap(),
+            false
+        );
+        assert_eq!(
+            collation_extremum::<CollatorUtf8Mb4GeneralCi>(b"a", b"A", Ordering::Greater).unwrap(),
+            false
+        );
+
+        // Under `Binary`, `"a"` (0x61) sorts after `"A"` (0x41), so `"A"` beats `"a"` for MIN
+        // (`Ordering::Greater`) but not for MAX (`Ordering::Less`).
+        assert_eq!(
+            collation_extremum::<CollatorBinary>(b"a", b"A", Ordering::Greater).unwrap(),
+            true
+        );
+        assert_eq!(
+            collation_extremum::<CollatorBinary>(b"a", b"A", Ordering::Less).unwrap(),
+            false
+        );
+    }
+}
@@ -157,6 +157,21 @@ impl VectorValue {
         }
     }
 
+    /// Pushes a value cloned out of `value` into current column, using the concrete type
+    /// matching this column's `EvalType`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value`'s variant does not match the current column's `EvalType`.
+    #[inline]
+    pub fn push_scalar(&mut self, value: &ScalarValue) {
+        match_template_evaluable! {
+            TT, match self {
+                VectorValue::TT(v) => v.push(TT::borrow_scalar_value(value).clone()),
+            }
+        }
+    }
+
     /// Returns maximum encoded size in binary format.
     pub fn maximum_encoded_size(&self, logical_rows: &[usize]) -> usize {
         match self {
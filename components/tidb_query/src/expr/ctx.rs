@@ -61,6 +61,8 @@ impl SqlMode {
 }
 
 const DEFAULT_MAX_WARNING_CNT: usize = 64;
+// MySQL's default for the `group_concat_max_len` session variable.
+const DEFAULT_GROUP_CONCAT_MAX_LEN: usize = 1024;
 
 #[derive(Clone, Debug)]
 pub struct EvalConfig {
@@ -71,6 +73,9 @@ pub struct EvalConfig {
     // warning is a executor stuff instead of a evaluation stuff.
     pub max_warning_cnt: usize,
     pub sql_mode: SqlMode,
+    /// Mirrors the `group_concat_max_len` session variable: the maximum number of bytes
+    /// `GROUP_CONCAT` will return before truncating.
+    pub group_concat_max_len: usize,
 }
 
 impl Default for EvalConfig {
@@ -106,6 +111,7 @@ impl EvalConfig {
             flag: Flag::empty(),
             max_warning_cnt: DEFAULT_MAX_WARNING_CNT,
             sql_mode: SqlMode::empty(),
+            group_concat_max_len: DEFAULT_GROUP_CONCAT_MAX_LEN,
         }
     }
 
@@ -125,6 +131,11 @@ impl EvalConfig {
         self
     }
 
+    pub fn set_group_concat_max_len(&mut self, new_value: usize) -> &mut Self {
+        self.group_concat_max_len = new_value;
+        self
+    }
+
     pub fn set_time_zone_by_name(&mut self, tz_name: &str) -> Result<&mut Self> {
         match Tz::from_tz_name(tz_name) {
             Some(tz) => {
@@ -8,6 +8,7 @@ use tipb::{Expr, ExprType, FieldType};
 
 use super::super::function::RpnFnMeta;
 use super::expr::{RpnExpression, RpnExpressionNode};
+use crate::codec::batch::LazyBatchColumnVec;
 use crate::codec::data_type::*;
 use crate::codec::mysql::{JsonDecoder, MAX_FSP};
 use crate::expr::EvalContext;
@@ -86,6 +87,23 @@ impl RpnExpressionBuilder {
         Ok(RpnExpression::from(expr_nodes))
     }
 
+    /// Like `build_from_expr_tree`, but additionally folds every function call whose arguments
+    /// are all constants into a single constant node, evaluating it once up front instead of
+    /// once per row.
+    ///
+    /// This is conservative: a function call is only folded when `RpnFnMeta::name` is not on
+    /// `NON_FOLDABLE_FN_NAMES` below. Anything context-dependent (e.g. `NOW()`, `RAND()`) must be
+    /// listed there so it keeps being evaluated per row instead of being baked into the plan
+    /// once at build time.
+    pub fn build_from_expr_tree_with_const_fold(
+        tree_node: Expr,
+        ctx: &mut EvalContext,
+        max_columns: usize,
+    ) -> Result<RpnExpression> {
+        let expr = Self::build_from_expr_tree(tree_node, ctx, max_columns)?;
+        Ok(fold_constants(expr, ctx))
+    }
+
     /// Only used in tests, with a customized function mapper.
     #[cfg(test)]
     pub fn build_from_expr_tree_with_fn_mapper<F>(
@@ -373,6 +391,80 @@ fn handle_node_constant(
     Ok(())
 }
 
+/// Names of RPN functions that must never be constant-folded because their result depends on
+/// something other than their arguments (the current time, RNG state, ...). Kept as a name-based
+/// denylist since `RpnFnMeta` doesn't otherwise track determinism; new non-deterministic
+/// functions must be added here.
+const NON_FOLDABLE_FN_NAMES: &[&str] = &["rand", "rand_with_seed_first_gen", "uuid"];
+
+/// Post-processes a built `RpnExpression`, replacing every `FnCall` node whose arguments are all
+/// `Constant` nodes (and whose function is not in `NON_FOLDABLE_FN_NAMES`) with a single
+/// `Constant` node holding the precomputed result.
+///
+/// Folding is done bottom-up in one left-to-right pass: since RPN is already a post-order
+/// traversal, an argument run immediately preceding a `FnCall` node is itself already fully
+/// folded by the time that `FnCall` is visited, so nested constant subtrees (e.g. `2 * (3 + 4)`)
+/// collapse in a single pass.
+fn fold_constants(expr: RpnExpression, ctx: &mut EvalContext) -> RpnExpression {
+    let mut folded: Vec<RpnExpressionNode> = Vec::with_capacity(expr.len());
+    for node in expr.into_inner() {
+        match node {
+            RpnExpressionNode::FnCall {
+                func_meta,
+                args_len,
+                field_type,
+                metadata,
+            } => {
+                let args_start = folded.len() - args_len;
+                let all_args_constant = folded[args_start..].iter().all(|n| match n {
+                    RpnExpressionNode::Constant { .. } => true,
+                    _ => false,
+                });
+
+                if all_args_constant && !NON_FOLDABLE_FN_NAMES.contains(&func_meta.name) {
+                    let sub_expr_nodes: Vec<_> = folded.drain(args_start..).collect();
+                    let folded_field_type = field_type.clone();
+                    let sub_expr = RpnExpression::from(
+                        sub_expr_nodes
+                            .into_iter()
+                            .chain(std::iter::once(RpnExpressionNode::FnCall {
+                                func_meta,
+                                args_len,
+                                field_type,
+                                metadata,
+                            }))
+                            .collect::<Vec<_>>(),
+                    );
+                    // A `FnCall` evaluates to a one-row vector even when all its arguments are
+                    // constants, so the folded value is that vector's only row.
+                    let value = sub_expr
+                        .eval(ctx, &[], &mut LazyBatchColumnVec::empty(), &[], 1)
+                        .ok()
+                        .map(|result| result.get_logical_scalar_ref(0).to_owned());
+                    match value {
+                        Some(value) => folded.push(RpnExpressionNode::Constant {
+                            value,
+                            field_type: folded_field_type,
+                        }),
+                        // If evaluation fails, keep the unfolded nodes rather than losing the
+                        // expression or panicking here.
+                        None => folded.extend(sub_expr.into_inner()),
+                    }
+                } else {
+                    folded.push(RpnExpressionNode::FnCall {
+                        func_meta,
+                        args_len,
+                        field_type,
+                        metadata,
+                    });
+                }
+            }
+            other => folded.push(other),
+        }
+    }
+    RpnExpression::from(folded)
+}
+
 #[inline]
 fn get_scalar_value_null(eval_type: EvalType) -> ScalarValue {
     match_template_evaluable! {
@@ -809,6 +901,42 @@ mod tests {
         assert!(it.next().is_none())
     }
 
+    #[test]
+    fn test_build_from_expr_tree_with_const_fold() {
+        // col + (2 * 3): the `2 * 3` subtree is all constants and should fold into a single
+        // constant node, leaving `col`, the folded constant, and the `+` call.
+        let node = ExprDefBuilder::scalar_func(ScalarFuncSig::PlusInt, FieldTypeTp::LongLong)
+            .push_child(ExprDefBuilder::column_ref(0, FieldTypeTp::LongLong))
+            .push_child(
+                ExprDefBuilder::scalar_func(ScalarFuncSig::MultiplyInt, FieldTypeTp::LongLong)
+                    .push_child(ExprDefBuilder::constant_int(2))
+                    .push_child(ExprDefBuilder::constant_int(3)),
+            )
+            .build();
+
+        let mut ctx = EvalContext::default();
+        let exp =
+            RpnExpressionBuilder::build_from_expr_tree_with_const_fold(node, &mut ctx, 1).unwrap();
+        let nodes = exp.into_inner();
+
+        // `col`, the folded constant (replacing `2 * 3`), and the `+` call.
+        assert_eq!(nodes.len(), 3);
+        match &nodes[0] {
+            RpnExpressionNode::ColumnRef { offset } => assert_eq!(*offset, 0),
+            other => panic!("expected a column ref, got {:?}", other),
+        }
+        match &nodes[1] {
+            RpnExpressionNode::Constant { value, .. } => {
+                assert_eq!(*value.as_int(), Some(6));
+            }
+            other => panic!("expected a folded constant, got {:?}", other),
+        }
+        match &nodes[2] {
+            RpnExpressionNode::FnCall { .. } => {}
+            other => panic!("expected the `+` call, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_max_columns_check() {
         // Col offset = 0. The minimum success max_columns is 1.
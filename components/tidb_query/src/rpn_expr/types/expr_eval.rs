@@ -176,6 +176,78 @@ impl RpnExpression {
         )
     }
 
+    /// Evaluates the expression into `out`, reusing `out`'s existing allocation for the result
+    /// values (e.g. in an aggregate executor re-evaluating its argument expression once per input
+    /// batch). The expression is still evaluated through `eval`, whose own result vector is
+    /// allocated on every call; its values are then copied into `out`.
+    ///
+    /// `out` is cleared before being filled, so its `EvalType` is only significant in that it
+    /// must already match the expression's return type; its prior contents are discarded and its
+    /// capacity is reused for the `output_rows` values produced by this call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the expression is not valid.
+    ///
+    /// Panics if `out`'s `EvalType` does not match the expression's return type.
+    ///
+    /// Panics when referenced column does not have equal length as specified in `rows`.
+    pub fn eval_into(
+        &self,
+        ctx: &mut EvalContext,
+        schema: &[FieldType],
+        input_physical_columns: &mut LazyBatchColumnVec,
+        input_logical_rows: &[usize],
+        output_rows: usize,
+        out: &mut VectorValue,
+    ) -> Result<()> {
+        let result = self.eval(
+            ctx,
+            schema,
+            input_physical_columns,
+            input_logical_rows,
+            output_rows,
+        )?;
+        out.clear();
+        match result {
+            RpnStackNode::Scalar { value, .. } => {
+                for _ in 0..output_rows {
+                    out.push_scalar(value);
+                }
+            }
+            RpnStackNode::Vector { value, .. } => {
+                let physical_value = value.as_ref();
+                for &physical_row in value.logical_rows() {
+                    out.push_scalar(&physical_value.get_scalar_ref(physical_row).to_owned());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates the expression for a single row, returning an owned scalar instead of a vector
+    /// stack node. This is a thin convenience wrapper around `eval` for callers that only ever
+    /// have one row on hand (e.g. point lookups, or tests), so they don't need to construct a
+    /// single-element `logical_rows` slice themselves and unwrap the result from the batch
+    /// evaluation machinery.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the expression is not valid.
+    ///
+    /// Panics if `row_idx` is out of range of `input_physical_columns`.
+    pub fn eval_row(
+        &self,
+        ctx: &mut EvalContext,
+        schema: &[FieldType],
+        input_physical_columns: &mut LazyBatchColumnVec,
+        row_idx: usize,
+    ) -> Result<ScalarValue> {
+        let logical_rows = [row_idx];
+        let result = self.eval(ctx, schema, input_physical_columns, &logical_rows, 1)?;
+        Ok(result.get_logical_scalar_ref(0).to_owned())
+    }
+
     /// Decodes all referred columns which are not decoded. Then we ensure
     /// all referred columns are decoded.
     pub fn ensure_columns_decoded<'a>(
@@ -311,6 +383,32 @@ mod tests {
         assert_eq!(val.field_type().as_accessor().tp(), FieldTypeTp::Double);
     }
 
+    #[test]
+    fn test_eval_row() {
+        let physical_columns = LazyBatchColumnVec::from(vec![{
+            let mut col = LazyBatchColumn::decoded_with_capacity_and_tp(3, EvalType::Int);
+            col.mut_decoded().push_int(Some(1));
+            col.mut_decoded().push_int(None);
+            col.mut_decoded().push_int(Some(42));
+            col
+        }]);
+        let schema = [FieldTypeTp::LongLong.into()];
+        let exp = RpnExpressionBuilder::new().push_column_ref(0).build();
+        let mut ctx = EvalContext::default();
+
+        let mut columns = physical_columns.clone();
+        let val = exp.eval_row(&mut ctx, &schema, &mut columns, 0).unwrap();
+        assert_eq!(*val.as_int(), Some(1));
+
+        let mut columns = physical_columns.clone();
+        let val = exp.eval_row(&mut ctx, &schema, &mut columns, 1).unwrap();
+        assert_eq!(*val.as_int(), None);
+
+        let mut columns = physical_columns.clone();
+        let val = exp.eval_row(&mut ctx, &schema, &mut columns, 2).unwrap();
+        assert_eq!(*val.as_int(), Some(42));
+    }
+
     /// Creates fixture to be used in `test_eval_single_column_node_xxx`.
     fn new_single_column_node_fixture() -> (LazyBatchColumnVec, Vec<usize>, [FieldType; 2]) {
         let physical_columns = LazyBatchColumnVec::from(vec![
@@ -439,6 +537,45 @@ mod tests {
         assert_eq!(val.field_type().as_accessor().tp(), FieldTypeTp::LongLong);
     }
 
+    /// Calling `eval_into` twice with the same buffer should reuse its allocation and produce
+    /// the correct result each time, rather than leaking stale values from the previous call.
+    #[test]
+    fn test_eval_into_reuses_buffer() {
+        #[rpn_fn]
+        fn foo(v: &Option<Int>) -> Result<Option<Int>> {
+            Ok(v.map(|v| v * 2))
+        }
+
+        let physical_columns = LazyBatchColumnVec::from(vec![{
+            let mut col = LazyBatchColumn::decoded_with_capacity_and_tp(3, EvalType::Int);
+            col.mut_decoded().push_int(Some(1));
+            col.mut_decoded().push_int(None);
+            col.mut_decoded().push_int(Some(42));
+            col
+        }]);
+        let schema = [FieldTypeTp::LongLong.into()];
+        let exp = RpnExpressionBuilder::new()
+            .push_column_ref(0)
+            .push_fn_call(foo_fn_meta(), 1, FieldTypeTp::LongLong)
+            .build();
+        let mut ctx = EvalContext::default();
+
+        let mut out = VectorValue::with_capacity(0, EvalType::Int);
+
+        let mut columns = physical_columns.clone();
+        exp.eval_into(&mut ctx, &schema, &mut columns, &[0, 1, 2], 3, &mut out)
+            .unwrap();
+        assert_eq!(out.as_int_slice(), [Some(2), None, Some(84)]);
+
+        let reused_capacity = out.capacity();
+
+        let mut columns = physical_columns.clone();
+        exp.eval_into(&mut ctx, &schema, &mut columns, &[2, 0], 2, &mut out)
+            .unwrap();
+        assert_eq!(out.as_int_slice(), [Some(84), Some(2)]);
+        assert_eq!(out.capacity(), reused_capacity);
+    }
+
     /// Unary function (argument is scalar)
     #[test]
     fn test_eval_unary_function_scalar() {
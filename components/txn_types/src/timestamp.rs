@@ -32,6 +32,11 @@ impl TimeStamp {
         self.0 >> TSO_PHYSICAL_SHIFT_BITS
     }
 
+    /// Extracts logical part of a timestamp.
+    pub fn logical(self) -> u64 {
+        self.0 & ((1 << TSO_PHYSICAL_SHIFT_BITS) - 1)
+    }
+
     pub fn next(self) -> TimeStamp {
         TimeStamp(self.0 + 1)
     }
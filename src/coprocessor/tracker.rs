@@ -211,6 +211,7 @@ impl Tracker {
                 "scan_is_desc" => self.req_ctx.is_desc_scan,
                 "scan_iter_ops" => self.total_storage_stats.total_op_count(),
                 "scan_iter_processed" => self.total_storage_stats.total_processed(),
+                "scan_seek_next_ratio" => self.total_storage_stats.seek_next_ratio(),
                 "scan_ranges" => self.req_ctx.ranges_len,
                 "scan_first_range" => ?self.req_ctx.first_range,
                 self.total_perf_stats,
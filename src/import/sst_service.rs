@@ -24,6 +24,7 @@ use kvproto::raft_cmdpb::*;
 use crate::server::CONFIG_ROCKSDB_GAUGE;
 use engine_rocks::RocksEngine;
 use engine_traits::{SstExt, SstWriterBuilder};
+use raftstore::coprocessor::{RegionInfoAccessor, RegionInfoProvider};
 use raftstore::router::RaftStoreRouter;
 use raftstore::store::Callback;
 use security::{check_common_name, SecurityManager};
@@ -50,6 +51,7 @@ pub struct ImportSSTService<Router> {
     switcher: Arc<Mutex<ImportModeSwitcher>>,
     limiter: Limiter,
     security_mgr: Arc<SecurityManager>,
+    region_info_accessor: RegionInfoAccessor,
 }
 
 impl<Router: RaftStoreRouter> ImportSSTService<Router> {
@@ -59,6 +61,7 @@ impl<Router: RaftStoreRouter> ImportSSTService<Router> {
         engine: Arc<DB>,
         importer: Arc<SSTImporter>,
         security_mgr: Arc<SecurityManager>,
+        region_info_accessor: RegionInfoAccessor,
     ) -> ImportSSTService<Router> {
         let threads = Builder::new()
             .name_prefix("sst-importer")
@@ -73,6 +76,44 @@ impl<Router: RaftStoreRouter> ImportSSTService<Router> {
             switcher: Arc::new(Mutex::new(ImportModeSwitcher::new())),
             limiter: Limiter::new(INFINITY),
             security_mgr,
+            region_info_accessor,
+        }
+    }
+
+    /// Checks, without going through raft, whether the region a pending ingest targets still
+    /// exists locally. Used to fail fast when a region was destroyed between download and
+    /// ingest, instead of letting the ingest raft command fail deep in the apply path.
+    fn region_exists(&self, region_id: u64) -> bool {
+        let (tx, rx) = std::sync::mpsc::channel();
+        if let Err(e) = self.region_info_accessor.find_region_by_id(
+            region_id,
+            Box::new(move |region| match tx.send(region) {
+                Ok(()) => (),
+                Err(e) => error!(
+                    "ingest: find_region_by_id failed to send result";
+                    "err" => ?e
+                ),
+            }),
+        ) {
+            error!(
+                "ingest: failed to find_region_by_id from region_info_accessor";
+                "region_id" => region_id,
+                "err" => ?e
+            );
+            // Fail open: if the lookup itself can't be performed, fall back to letting the raft
+            // command path decide, rather than rejecting a possibly-valid ingest.
+            return true;
+        }
+        match rx.recv() {
+            Ok(region) => region.is_some(),
+            Err(e) => {
+                error!(
+                    "ingest: failed to find_region_by_id from region_info_accessor";
+                    "region_id" => region_id,
+                    "err" => ?e
+                );
+                true
+            }
         }
     }
 }
@@ -259,6 +300,24 @@ impl<Router: RaftStoreRouter> ImportSst for ImportSSTService<Router> {
             }));
             return;
         }
+        // If the region was destroyed between download and ingest, fail fast here instead of
+        // deep in the raft apply path: send_command would eventually report the same
+        // `region_not_found` error, but only after a full round trip through raftstore.
+        let region_id = req.get_context().get_region_id();
+        if !self.region_exists(region_id) {
+            let err = Error::RegionNotFound(region_id);
+            error_inc(&err);
+            let mut errorpb = errorpb::Error::default();
+            errorpb.set_message(err.to_string());
+            errorpb.mut_region_not_found().set_region_id(region_id);
+            let mut resp = IngestResponse::default();
+            resp.set_error(errorpb);
+            ctx.spawn(sink.success(resp).map_err(|e| {
+                warn!("send rpc failed"; "err" => %e);
+            }));
+            return;
+        }
+
         // Make ingest command.
         let mut ingest = Request::default();
         ingest.set_cmd_type(CmdType::IngestSst);
@@ -32,6 +32,11 @@ struct LockObserverState {
     /// specified max_ts) are monitored and collected. If there are too many stale locks or any
     /// error happens, `is_clean` must be set to `false`.
     is_clean: AtomicBool,
+
+    /// Number of observed locks that were never handed to the collector, e.g. because the
+    /// scheduling channel was full. Counted separately from `is_clean` so callers can gauge how
+    /// large the gap actually is instead of just knowing there is one.
+    dropped: AtomicU64,
 }
 
 impl LockObserverState {
@@ -54,6 +59,18 @@ impl LockObserverState {
     fn mark_dirty(&self) {
         self.is_clean.store(false, Ordering::Release);
     }
+
+    fn load_dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Acquire)
+    }
+
+    fn add_dropped(&self, count: u64) {
+        self.dropped.fetch_add(count, Ordering::AcqRel);
+    }
+
+    fn reset_dropped(&self) {
+        self.dropped.store(0, Ordering::Release);
+    }
 }
 
 pub type Callback<T> = Box<dyn FnOnce(Result<T>) + Send>;
@@ -69,7 +86,7 @@ enum LockCollectorTask {
     },
     GetCollectedLocks {
         max_ts: TimeStamp,
-        callback: Callback<(Vec<LockInfo>, bool)>,
+        callback: Callback<(Vec<LockInfo>, bool, u64)>,
     },
     StopCollecting {
         max_ts: TimeStamp,
@@ -130,6 +147,9 @@ impl LockObserver {
     }
 
     fn send(&self, locks: Vec<(Key, Lock)>) {
+        // Captured before `locks` is moved into the scheduled task (and potentially swapped out
+        // by the failpoint below), so it always reflects what was actually observed.
+        let locks_len = locks.len() as u64;
         let res = &mut self
             .sender
             .schedule(LockCollectorTask::ObservedLocks(locks));
@@ -149,10 +169,12 @@ impl LockObserver {
         match res {
             Ok(()) => (),
             Err(ScheduleError::Stopped(_)) => {
+                self.state.add_dropped(locks_len);
                 error!("lock observer failed to send locks because collector is stopped");
             }
             Err(ScheduleError::Full(_)) => {
                 self.state.mark_dirty();
+                self.state.add_dropped(locks_len);
                 warn!("cannot collect all applied lock because channel is full");
             }
         }
@@ -305,13 +327,14 @@ impl LockCollectorRunner {
                 // previous observing. It need to be solved, although it's very unlikely to happen and
                 // doesn't affect correctness of data.
                 self.observer_state.mark_clean();
+                self.observer_state.reset_dropped();
                 self.observer_state.store_max_ts(max_ts);
                 Ok(())
             }
         }
     }
 
-    fn get_collected_locks(&mut self, max_ts: TimeStamp) -> Result<(Vec<LockInfo>, bool)> {
+    fn get_collected_locks(&mut self, max_ts: TimeStamp) -> Result<(Vec<LockInfo>, bool, u64)> {
         let curr_max_ts = self.observer_state.load_max_ts();
         if curr_max_ts != max_ts {
             warn!(
@@ -334,7 +357,11 @@ impl LockCollectorRunner {
             })
             .collect();
 
-        Ok((locks?, self.observer_state.is_clean()))
+        Ok((
+            locks?,
+            self.observer_state.is_clean(),
+            self.observer_state.load_dropped(),
+        ))
     }
 
     fn stop_collecting(&mut self, max_ts: TimeStamp) -> Result<()> {
@@ -424,10 +451,13 @@ impl AppliedLockCollector {
     /// Collects at most `MAX_COLLECT_SIZE` locks. If there are (even potentially) more locks than
     /// `MAX_COLLECT_SIZE` or any error happens, the flag `is_clean` will be unset, which represents
     /// `AppliedLockCollector` cannot collect all locks.
+    /// The third element of the result is the number of observed locks that were dropped instead
+    /// of collected (e.g. because the collector's channel was full) since the last
+    /// `start_collecting`, so callers can gauge the size of the gap `is_clean` alone can't convey.
     pub fn get_collected_locks(
         &self,
         max_ts: TimeStamp,
-        callback: Callback<(Vec<LockInfo>, bool)>,
+        callback: Callback<(Vec<LockInfo>, bool, u64)>,
     ) -> Result<()> {
         self.scheduler
             .schedule(LockCollectorTask::GetCollectedLocks { max_ts, callback })
@@ -522,7 +552,10 @@ mod tests {
         rx.recv().unwrap()
     }
 
-    fn get_collected_locks(c: &AppliedLockCollector, max_ts: u64) -> Result<(Vec<LockInfo>, bool)> {
+    fn get_collected_locks(
+        c: &AppliedLockCollector,
+        max_ts: u64,
+    ) -> Result<(Vec<LockInfo>, bool, u64)> {
         let (tx, rx) = channel();
         c.get_collected_locks(max_ts.into(), Box::new(move |r| tx.send(r).unwrap()))
             .unwrap();
@@ -598,7 +631,7 @@ mod tests {
         let mut expected_result = vec![];
 
         start_collecting(&c, 100).unwrap();
-        assert_eq!(get_collected_locks(&c, 100).unwrap(), (vec![], true));
+        assert_eq!(get_collected_locks(&c, 100).unwrap(), (vec![], true, 0));
 
         // Only puts in lock cf will be monitered.
         let req = vec![
@@ -615,14 +648,14 @@ mod tests {
         expected_result.push(locks[0].clone());
         assert_eq!(
             get_collected_locks(&c, 100).unwrap(),
-            (expected_result.clone(), true)
+            (expected_result.clone(), true, 0)
         );
 
         // When start collecting with the same max_ts again, shouldn't clean up the observer state.
         start_collecting(&c, 100).unwrap();
         assert_eq!(
             get_collected_locks(&c, 100).unwrap(),
-            (expected_result.clone(), true)
+            (expected_result.clone(), true, 0)
         );
 
         // Only locks with ts <= 100 will be collected.
@@ -639,15 +672,15 @@ mod tests {
         coprocessor_host.post_apply(&Region::default(), &mut make_raft_cmd(req.clone()));
         assert_eq!(
             get_collected_locks(&c, 100).unwrap(),
-            (expected_result, true)
+            (expected_result, true, 0)
         );
 
         // When start_collecting is double-invoked again with larger ts, the previous results are
         // dropped.
         start_collecting(&c, 110).unwrap();
-        assert_eq!(get_collected_locks(&c, 110).unwrap(), (vec![], true));
+        assert_eq!(get_collected_locks(&c, 110).unwrap(), (vec![], true, 0));
         coprocessor_host.post_apply(&Region::default(), &mut make_raft_cmd(req));
-        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks, true));
+        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks, true, 0));
     }
 
     #[test]
@@ -685,7 +718,7 @@ mod tests {
             CF_DEFAULT,
             &lock_kvs,
         );
-        assert_eq!(get_collected_locks(&c, 100).unwrap(), (vec![], true));
+        assert_eq!(get_collected_locks(&c, 100).unwrap(), (vec![], true, 0));
 
         // Apply plain file to lock cf. Locks with ts before 100 will be collected.
         let expected_locks: Vec<_> = locks
@@ -696,12 +729,12 @@ mod tests {
         coprocessor_host.post_apply_plain_kvs_from_snapshot(&Region::default(), CF_LOCK, &lock_kvs);
         assert_eq!(
             get_collected_locks(&c, 100).unwrap(),
-            (expected_locks.clone(), true)
+            (expected_locks.clone(), true, 0)
         );
         // Fetch result twice gets the same result.
         assert_eq!(
             get_collected_locks(&c, 100).unwrap(),
-            (expected_locks.clone(), true)
+            (expected_locks.clone(), true, 0)
         );
 
         // When stale start_collecting request arrives, the previous collected results shouldn't
@@ -709,29 +742,29 @@ mod tests {
         start_collecting(&c, 100).unwrap();
         assert_eq!(
             get_collected_locks(&c, 100).unwrap(),
-            (expected_locks.clone(), true)
+            (expected_locks.clone(), true, 0)
         );
         start_collecting(&c, 90).unwrap_err();
         assert_eq!(
             get_collected_locks(&c, 100).unwrap(),
-            (expected_locks, true)
+            (expected_locks, true, 0)
         );
 
         // When start_collecting is double-invoked again with larger ts, the previous results are
         // dropped.
         start_collecting(&c, 110).unwrap();
-        assert_eq!(get_collected_locks(&c, 110).unwrap(), (vec![], true));
+        assert_eq!(get_collected_locks(&c, 110).unwrap(), (vec![], true, 0));
         coprocessor_host.post_apply_plain_kvs_from_snapshot(&Region::default(), CF_LOCK, &lock_kvs);
-        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks.clone(), true));
+        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks.clone(), true, 0));
 
         // Apply SST file to other cfs. Nothing happens.
         coprocessor_host.post_apply_sst_from_snapshot(&Region::default(), CF_DEFAULT, "");
-        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks.clone(), true));
+        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks.clone(), true, 0));
 
         // Apply SST file to lock cf is not supported. This will cause error and therefore
         // `is_clean` will be set to false.
         coprocessor_host.post_apply_sst_from_snapshot(&Region::default(), CF_LOCK, "");
-        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks, false));
+        assert_eq!(get_collected_locks(&c, 110).unwrap(), (locks, false, 0));
     }
 
     #[test]
@@ -743,20 +776,20 @@ mod tests {
         let (k, v) = (Key::from_raw(b"k1").into_encoded(), b"v1".to_vec());
         let req = make_apply_request(k.clone(), v.clone(), CF_LOCK, CmdType::Put);
         coprocessor_host.post_apply(&Region::default(), &mut make_raft_cmd(vec![req]));
-        assert_eq!(get_collected_locks(&c, 1).unwrap(), (vec![], false));
+        assert_eq!(get_collected_locks(&c, 1).unwrap(), (vec![], false, 0));
 
         // `is_clean` should be reset after invoking `start_collecting`.
         start_collecting(&c, 2).unwrap();
-        assert_eq!(get_collected_locks(&c, 2).unwrap(), (vec![], true));
+        assert_eq!(get_collected_locks(&c, 2).unwrap(), (vec![], true, 0));
         coprocessor_host.post_apply_plain_kvs_from_snapshot(
             &Region::default(),
             CF_LOCK,
             &[(keys::data_key(&k), v)],
         );
-        assert_eq!(get_collected_locks(&c, 2).unwrap(), (vec![], false));
+        assert_eq!(get_collected_locks(&c, 2).unwrap(), (vec![], false, 0));
 
         start_collecting(&c, 3).unwrap();
-        assert_eq!(get_collected_locks(&c, 3).unwrap(), (vec![], true));
+        assert_eq!(get_collected_locks(&c, 3).unwrap(), (vec![], true, 0));
 
         // If there are too many locks, `is_clean` should be set to false.
         let mut lock = LockInfo::default();
@@ -773,32 +806,67 @@ mod tests {
         };
 
         batch_generate_locks(MAX_COLLECT_SIZE - 1);
-        let (locks, is_clean) = get_collected_locks(&c, 3).unwrap();
+        let (locks, is_clean, _dropped) = get_collected_locks(&c, 3).unwrap();
         assert_eq!(locks.len(), MAX_COLLECT_SIZE - 1);
         assert!(is_clean);
 
         batch_generate_locks(1);
-        let (locks, is_clean) = get_collected_locks(&c, 3).unwrap();
+        let (locks, is_clean, _dropped) = get_collected_locks(&c, 3).unwrap();
         assert_eq!(locks.len(), MAX_COLLECT_SIZE);
         assert!(!is_clean);
 
         batch_generate_locks(1);
         // If there are more locks, they will be dropped.
-        let (locks, is_clean) = get_collected_locks(&c, 3).unwrap();
+        let (locks, is_clean, _dropped) = get_collected_locks(&c, 3).unwrap();
         assert_eq!(locks.len(), MAX_COLLECT_SIZE);
         assert!(!is_clean);
 
         start_collecting(&c, 4).unwrap();
-        assert_eq!(get_collected_locks(&c, 4).unwrap(), (vec![], true));
+        assert_eq!(get_collected_locks(&c, 4).unwrap(), (vec![], true, 0));
 
         batch_generate_locks(MAX_COLLECT_SIZE - 5);
-        let (locks, is_clean) = get_collected_locks(&c, 4).unwrap();
+        let (locks, is_clean, _dropped) = get_collected_locks(&c, 4).unwrap();
         assert_eq!(locks.len(), MAX_COLLECT_SIZE - 5);
         assert!(is_clean);
 
         batch_generate_locks(10);
-        let (locks, is_clean) = get_collected_locks(&c, 4).unwrap();
+        let (locks, is_clean, _dropped) = get_collected_locks(&c, 4).unwrap();
         assert_eq!(locks.len(), MAX_COLLECT_SIZE);
         assert!(!is_clean);
     }
+
+    #[cfg(feature = "failpoints")]
+    #[test]
+    fn test_dropped_count() {
+        let _guard = fail::FailScenario::setup();
+        let (c, coprocessor_host) = new_test_collector();
+        start_collecting(&c, 1).unwrap();
+        assert_eq!(get_collected_locks(&c, 1).unwrap(), (vec![], true, 0));
+
+        let mut lock = LockInfo::default();
+        lock.set_key(b"k1".to_vec());
+        lock.set_primary_lock(b"k1".to_vec());
+        lock.set_lock_type(Op::Put);
+        lock.set_lock_version(1);
+        let (k, v) = lock_info_to_kv(lock);
+        let req = make_apply_request(k, v, CF_LOCK, CmdType::Put);
+
+        // While the observer's send channel is forced full, every observed lock is dropped
+        // instead of collected, and the drop count keeps growing across multiple failures.
+        fail::cfg("lock_observer_send", "return").unwrap();
+        coprocessor_host.post_apply(&Region::default(), &mut make_raft_cmd(vec![req.clone()]));
+        let (locks, is_clean, dropped) = get_collected_locks(&c, 1).unwrap();
+        assert!(locks.is_empty());
+        assert!(!is_clean);
+        assert_eq!(dropped, 1);
+
+        coprocessor_host.post_apply(&Region::default(), &mut make_raft_cmd(vec![req.clone()]));
+        let (_, _, dropped) = get_collected_locks(&c, 1).unwrap();
+        assert_eq!(dropped, 2);
+        fail::remove("lock_observer_send");
+
+        // The counter resets when a fresh collection starts.
+        start_collecting(&c, 2).unwrap();
+        assert_eq!(get_collected_locks(&c, 2).unwrap(), (vec![], true, 0));
+    }
 }
@@ -2,12 +2,22 @@
 
 use configuration::{rollback_or, ConfigChange, ConfigManager, Configuration, RollbackCollector};
 use std::sync::Arc;
-use tikv_util::config::{ReadableSize, VersionTrack};
+use tikv_util::config::{ReadableDuration, ReadableSize, VersionTrack};
+
+use super::gc_worker::GC_MAX_EXECUTING_TASKS;
 
 const DEFAULT_GC_RATIO_THRESHOLD: f64 = 1.1;
 pub const DEFAULT_GC_BATCH_KEYS: usize = 512;
 // No limit
 const DEFAULT_GC_MAX_WRITE_BYTES_PER_SEC: u64 = 0;
+// The worker never throttles itself below this, even under sustained slow tasks.
+const DEFAULT_GC_MIN_CONCURRENCY: usize = 1;
+// By default GC uses the safe point as provided, without holding back for clock skew or
+// long-running transactions.
+const DEFAULT_GC_SAFE_POINT_BUFFER_SECS: u64 = 0;
+const DEFAULT_GC_TASK_SLOW_THRESHOLD_SECS: u64 = 30;
+// No hard limit: a stuck task only ever gets logged and counted as slow, never aborted.
+const DEFAULT_GC_TASK_TIMEOUT_SECS: u64 = 0;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Configuration)]
 #[serde(default)]
@@ -16,6 +26,28 @@ pub struct GcConfig {
     pub ratio_threshold: f64,
     pub batch_keys: usize,
     pub max_write_bytes_per_sec: ReadableSize,
+    /// Lower bound of the self-adjusted concurrency band. See `max_concurrency`.
+    pub min_concurrency: usize,
+    /// Upper bound of the self-adjusted concurrency band, and thus the max number of `Gc` tasks
+    /// the worker will execute at once. The worker starts at this concurrency and throttles
+    /// down towards `min_concurrency` while observed task latency stays high, growing back up
+    /// towards this bound once tasks are fast again. Defaults to `GC_MAX_EXECUTING_TASKS`;
+    /// deployments with more IO headroom can raise it here without recompiling.
+    pub max_concurrency: usize,
+    /// GC never scans past `safe_point - gc_safe_point_buffer`, so versions committed within
+    /// this window of the safe point survive even though the provided safe point technically
+    /// covers them. This guards against clock skew between PD and TiKV, and against
+    /// long-running transactions that started slightly before the safe point was computed.
+    /// A zero buffer (the default) reproduces the previous, unbuffered behavior.
+    pub gc_safe_point_buffer: ReadableDuration,
+    /// A task running longer than this is logged as slow and counted in
+    /// `tikv_gcworker_gc_task_slow_vec`. Purely observational; the task still runs to
+    /// completion.
+    pub task_slow_threshold: ReadableDuration,
+    /// A running `gc` task checks its own elapsed time between key batches and gives up with
+    /// `GcTaskTimedOut` once it's been running longer than this, so a huge range or a stalled
+    /// engine can't wedge the worker forever. Zero (the default) never aborts.
+    pub task_timeout: ReadableDuration,
 }
 
 impl Default for GcConfig {
@@ -24,6 +56,11 @@ impl Default for GcConfig {
             ratio_threshold: DEFAULT_GC_RATIO_THRESHOLD,
             batch_keys: DEFAULT_GC_BATCH_KEYS,
             max_write_bytes_per_sec: ReadableSize(DEFAULT_GC_MAX_WRITE_BYTES_PER_SEC),
+            min_concurrency: DEFAULT_GC_MIN_CONCURRENCY,
+            max_concurrency: GC_MAX_EXECUTING_TASKS,
+            gc_safe_point_buffer: ReadableDuration::secs(DEFAULT_GC_SAFE_POINT_BUFFER_SECS),
+            task_slow_threshold: ReadableDuration::secs(DEFAULT_GC_TASK_SLOW_THRESHOLD_SECS),
+            task_timeout: ReadableDuration::secs(DEFAULT_GC_TASK_TIMEOUT_SECS),
         }
     }
 }
@@ -42,6 +79,19 @@ impl GcConfig {
                 Err(("gc.batch_keys should not be 0.").into())
             })
         }
+        if self.min_concurrency == 0 || self.min_concurrency > self.max_concurrency {
+            rollback_or!(rb_collector, min_concurrency, {
+                Err(("gc.min-concurrency should be at least 1 and no more than gc.max-concurrency.")
+                    .into())
+            })
+        }
+        if self.task_timeout.as_millis() != 0
+            && self.task_timeout.as_millis() < self.task_slow_threshold.as_millis()
+        {
+            rollback_or!(rb_collector, task_timeout, {
+                Err(("gc.task-timeout should not be shorter than gc.task-slow-threshold.").into())
+            })
+        }
         Ok(())
     }
 }
@@ -5,7 +5,7 @@ use kvproto::metapb;
 use log_wrappers::DisplayValue;
 use raft::StateRole;
 use std::cmp::Ordering;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use std::thread::{self, Builder as ThreadBuilder, JoinHandle};
 use std::time::{Duration, Instant};
 use tikv_util::worker::FutureScheduler;
@@ -43,6 +43,13 @@ pub struct AutoGcConfig<S: GcSafePointProvider, R: RegionInfoProvider> {
     /// This will be called when a round of GC has finished and goes back to idle state.
     /// This field is for test purpose.
     pub post_a_round_of_gc: Option<Box<dyn Fn() + Send>>,
+
+    /// If set, the automatic GC loop skips any region for which this returns `true` (e.g. a
+    /// region kept on a differently-labeled, cold-tier store that should GC on its own separate
+    /// schedule). Skipped regions are simply passed over by the loop; an explicit `gc` call for
+    /// them is unaffected, since it never goes through `GcManager` at all. `None` means every
+    /// region owned by this TiKV is GC-ed automatically, matching the prior behavior.
+    pub exclude_from_auto_gc: Option<Arc<dyn Fn(&metapb::Region) -> bool + Send + Sync>>,
 }
 
 impl<S: GcSafePointProvider, R: RegionInfoProvider> AutoGcConfig<S, R> {
@@ -55,6 +62,7 @@ impl<S: GcSafePointProvider, R: RegionInfoProvider> AutoGcConfig<S, R> {
             poll_safe_point_interval: Duration::from_secs(POLL_SAFE_POINT_INTERVAL_SECS),
             always_check_safe_point: false,
             post_a_round_of_gc: None,
+            exclude_from_auto_gc: None,
         }
     }
 
@@ -72,6 +80,7 @@ impl<S: GcSafePointProvider, R: RegionInfoProvider> AutoGcConfig<S, R> {
             poll_safe_point_interval: Duration::from_millis(100),
             always_check_safe_point: true,
             post_a_round_of_gc: None,
+            exclude_from_auto_gc: None,
         }
     }
 }
@@ -551,6 +560,7 @@ impl<S: GcSafePointProvider, R: RegionInfoProvider> GcManager<S, R> {
     fn get_next_gc_context(&mut self, key: Key) -> (Option<Context>, Option<Key>) {
         let (tx, rx) = mpsc::channel();
         let store_id = self.cfg.self_store_id;
+        let exclude_from_auto_gc = self.cfg.exclude_from_auto_gc.clone();
 
         let res = self.cfg.region_info_provider.seek_region(
             key.as_encoded(),
@@ -560,8 +570,13 @@ impl<S: GcSafePointProvider, R: RegionInfoProvider> GcManager<S, R> {
                     scanned_regions += 1;
                     if info.role == StateRole::Leader {
                         if find_peer(&info.region, store_id).is_some() {
-                            let _ = tx.send((Some(info.region.clone()), scanned_regions));
-                            return;
+                            let excluded = exclude_from_auto_gc
+                                .as_ref()
+                                .map_or(false, |exclude| exclude(&info.region));
+                            if !excluded {
+                                let _ = tx.send((Some(info.region.clone()), scanned_regions));
+                                return;
+                            }
                         }
                     }
                 }
@@ -624,6 +639,8 @@ mod tests {
                 ref mut callback, ..
             } => callback,
             GcTask::PhysicalScanLock { .. } => unreachable!(),
+            GcTask::VerifyGc { .. } => unreachable!(),
+            GcTask::GcKey { .. } => unreachable!(),
             GcTask::Validate(_) => unreachable!(),
         };
         mem::replace(callback, Box::new(|_| {}))
@@ -794,6 +811,61 @@ mod tests {
         assert_eq!(ctx.get_region_epoch(), &epoch);
     }
 
+    #[test]
+    fn test_exclude_from_auto_gc() {
+        let mut make_region = |id: u64, start: &[u8], end: &[u8]| {
+            let mut r = metapb::Region::default();
+            r.set_id(id);
+            r.set_start_key(start.to_vec());
+            r.set_end_key(end.to_vec());
+            r.mut_peers().push(new_peer(1, 1));
+            RegionInfo::new(r, StateRole::Leader)
+        };
+        let mut regions = BTreeMap::new();
+        regions.insert(b"".to_vec(), make_region(1, b"", b"b"));
+        // Region 2 stands in for a region kept on a cold-tier, differently-labeled store.
+        regions.insert(b"b".to_vec(), make_region(2, b"b", b""));
+
+        let mut test_util = GcManagerTestUtil::new(regions);
+        test_util
+            .gc_manager
+            .as_mut()
+            .unwrap()
+            .cfg
+            .exclude_from_auto_gc = Some(Arc::new(|region: &metapb::Region| region.get_id() == 2));
+
+        test_util.add_next_safe_point(10);
+        test_util.gc_manager.as_mut().unwrap().initialize();
+        test_util.gc_manager.as_mut().unwrap().gc_a_round().unwrap();
+
+        let gc_tasks: Vec<_> = test_util
+            .collect_scheduled_tasks()
+            .iter()
+            .map(|task| match task {
+                GcTask::Gc { ctx, .. } => ctx.get_region_id(),
+                _ => unreachable!(),
+            })
+            .collect();
+        // The excluded region is skipped by the auto loop; only region 1 is GC-ed.
+        assert_eq!(gc_tasks, vec![1]);
+
+        // Explicit GC never goes through `GcManager`, so it's unaffected by the exclusion.
+        let mut ctx = Context::default();
+        ctx.set_region_id(2);
+        sync_gc(&test_util.worker.scheduler(), ctx, TimeStamp::from(10)).unwrap();
+        let gc_tasks: Vec<_> = test_util
+            .collect_scheduled_tasks()
+            .iter()
+            .map(|task| match task {
+                GcTask::Gc { ctx, .. } => ctx.get_region_id(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(gc_tasks, vec![2]);
+
+        test_util.stop();
+    }
+
     #[test]
     fn test_update_safe_point() {
         let mut test_util = GcManagerTestUtil::new(BTreeMap::new());
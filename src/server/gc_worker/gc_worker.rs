@@ -15,13 +15,16 @@ use engine_traits::{CF_DEFAULT, CF_LOCK, CF_WRITE};
 use futures::Future;
 use kvproto::kvrpcpb::{Context, IsolationLevel, LockInfo};
 use kvproto::metapb;
+use protobuf::Message;
 use tokio_core::reactor::Handle;
 
 use crate::server::metrics::*;
 use crate::storage::kv::{
     Engine, Error as EngineError, ErrorInner as EngineErrorInner, ScanMode, Statistics, WriteData,
 };
-use crate::storage::mvcc::{check_need_gc, Error as MvccError, MvccReader, MvccTxn};
+use crate::storage::mvcc::{
+    check_need_gc, Error as MvccError, GcDeletionAuditor, MvccReader, MvccTxn,
+};
 use pd_client::PdClient;
 use raftstore::coprocessor::{CoprocessorHost, RegionInfoAccessor, RegionInfoProvider};
 use raftstore::router::ServerRaftStoreRouter;
@@ -50,7 +53,97 @@ const GC_LOG_DELETED_VERSION_THRESHOLD: usize = 30;
 
 pub const GC_MAX_EXECUTING_TASKS: usize = 10;
 const GC_SNAPSHOT_TIMEOUT_SECS: u64 = 10;
-const GC_TASK_SLOW_SECONDS: u64 = 30;
+
+/// Extra `Gc` task slots reserved for `GcPriority::High` requests, on top of
+/// `effective_concurrency`. Lets an operator-triggered GC get in even when automatic GC has
+/// filled up the normal concurrency limit, without letting high-priority traffic grow unbounded.
+const GC_HIGH_PRIORITY_RESERVED_SLOTS: usize = 2;
+
+/// Priority of a `Gc` task, used to decide whether it may use the reserved slots above
+/// `effective_concurrency` when the worker is otherwise busy. `kvrpcpb::GcRequest` carries no
+/// priority field, so RPC-triggered GC always runs at `Normal`; callers that schedule GC
+/// directly (e.g. an operator tool) can request `High` to avoid being starved by automatic GC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GcPriority {
+    Normal,
+    High,
+}
+
+impl Default for GcPriority {
+    fn default() -> Self {
+        GcPriority::Normal
+    }
+}
+
+/// Below this observed task latency, the adaptive concurrency limit is grown towards
+/// `GcConfig::max_concurrency`.
+const GC_ADAPTIVE_FAST_LATENCY_MS: u64 = 100;
+/// At or above this observed task latency, the adaptive concurrency limit is shrunk towards
+/// `GcConfig::min_concurrency`.
+const GC_ADAPTIVE_SLOW_LATENCY_MS: u64 = 1000;
+/// Weight given to each new sample when updating the exponential moving average of recent task
+/// latency that drives adaptive concurrency. Smooths out one-off slow tasks so a single outlier
+/// doesn't immediately shrink the limit.
+const GC_ADAPTIVE_LATENCY_EWMA_WEIGHT: f64 = 0.2;
+
+/// Folds `elapsed` into the EWMA stored in `recent_latency_ms`, then grows or shrinks
+/// `effective_concurrency` within `[min_concurrency, max_concurrency]` depending on where the
+/// updated average falls relative to the fast/slow thresholds.
+fn update_adaptive_concurrency(
+    recent_latency_ms: &atomic::AtomicU64,
+    effective_concurrency: &atomic::AtomicUsize,
+    min_concurrency: usize,
+    max_concurrency: usize,
+    elapsed: Duration,
+) {
+    let sample_ms = elapsed.as_millis() as u64;
+    let prev_ms = recent_latency_ms.load(atomic::Ordering::SeqCst);
+    let ewma_ms = if prev_ms == 0 {
+        sample_ms
+    } else {
+        (sample_ms as f64 * GC_ADAPTIVE_LATENCY_EWMA_WEIGHT
+            + prev_ms as f64 * (1.0 - GC_ADAPTIVE_LATENCY_EWMA_WEIGHT)) as u64
+    };
+    recent_latency_ms.store(ewma_ms, atomic::Ordering::SeqCst);
+
+    let grow = if ewma_ms >= GC_ADAPTIVE_SLOW_LATENCY_MS {
+        false
+    } else if ewma_ms <= GC_ADAPTIVE_FAST_LATENCY_MS {
+        true
+    } else {
+        return;
+    };
+    loop {
+        let cur = effective_concurrency.load(atomic::Ordering::SeqCst);
+        let next = if grow {
+            (cur + 1).min(max_concurrency)
+        } else {
+            cur.saturating_sub(1).max(min_concurrency)
+        };
+        if next == cur
+            || effective_concurrency.compare_and_swap(cur, next, atomic::Ordering::SeqCst) == cur
+        {
+            break;
+        }
+    }
+}
+
+/// Summary of a single `Gc` task's work, reported to the caller on success. Useful for audit
+/// logging, since the plain `gc()` callback only says whether the task succeeded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GcStats {
+    /// Total number of stale MVCC versions removed across all keys the task scanned.
+    pub deleted_versions: u64,
+    /// Total number of keys the task scanned, whether or not any version of them was deleted.
+    pub scanned_keys: u64,
+}
+
+/// Receives batches of `(key, commit_ts)` for versions removed by GC, for compliance deployments
+/// that must retain a record of what was deleted, e.g. writing to a file or shipping over a
+/// channel. When no sink is configured, GC does not pay any extra cost to support this.
+pub trait GcAuditSink: Send + Sync {
+    fn audit(&self, batch: &[(Key, TimeStamp)]);
+}
 
 /// Provides safe point.
 /// TODO: Give it a better name?
@@ -72,7 +165,8 @@ pub enum GcTask {
     Gc {
         ctx: Context,
         safe_point: TimeStamp,
-        callback: Callback<()>,
+        priority: GcPriority,
+        callback: Callback<GcStats>,
     },
     UnsafeDestroyRange {
         ctx: Context,
@@ -85,8 +179,22 @@ pub enum GcTask {
         max_ts: TimeStamp,
         start_key: Key,
         limit: usize,
+        max_bytes: Option<u64>,
         callback: Callback<Vec<LockInfo>>,
     },
+    VerifyGc {
+        start_key: Key,
+        end_key: Key,
+        safe_point: TimeStamp,
+        limit: usize,
+        callback: Callback<VerifyGcReport>,
+    },
+    GcKey {
+        ctx: Context,
+        key: Key,
+        safe_point: TimeStamp,
+        callback: Callback<usize>,
+    },
     #[cfg(any(test, feature = "testexport"))]
     Validate(Box<dyn FnOnce(&GcConfig, &Limiter) + Send>),
 }
@@ -97,6 +205,8 @@ impl GcTask {
             GcTask::Gc { .. } => "gc",
             GcTask::UnsafeDestroyRange { .. } => "unsafe_destroy_range",
             GcTask::PhysicalScanLock { .. } => "physical_scan_lock",
+            GcTask::VerifyGc { .. } => "verify_gc",
+            GcTask::GcKey { .. } => "gc_key",
             #[cfg(any(test, feature = "testexport"))]
             GcTask::Validate(_) => "validate_config",
         }
@@ -127,12 +237,53 @@ impl Display for GcTask {
                 .debug_struct("PhysicalScanLock")
                 .field("max_ts", max_ts)
                 .finish(),
+            GcTask::VerifyGc {
+                start_key,
+                end_key,
+                safe_point,
+                ..
+            } => f
+                .debug_struct("VerifyGc")
+                .field("start_key", &format!("{}", start_key))
+                .field("end_key", &format!("{}", end_key))
+                .field("safe_point", safe_point)
+                .finish(),
+            GcTask::GcKey {
+                ctx,
+                key,
+                safe_point,
+                ..
+            } => f
+                .debug_struct("GcKey")
+                .field("region_id", &ctx.get_region_id())
+                .field("key", &format!("{}", key))
+                .field("safe_point", safe_point)
+                .finish(),
             #[cfg(any(test, feature = "testexport"))]
             GcTask::Validate(_) => write!(f, "Validate gc worker config"),
         }
     }
 }
 
+/// A single key that still has data at or below the safe point that `verify_gc` expected GC to
+/// have already removed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyGcInfo {
+    pub key: Key,
+    /// How many versions at or below the safe point are still present for this key. A correctly
+    /// GC-ed key has zero.
+    pub stale_versions: usize,
+}
+
+/// Result of a `verify_gc` consistency check over a key range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VerifyGcReport {
+    pub keys_scanned: usize,
+    /// Bounded by the `limit` passed to `verify_gc`; more offenders may exist than are listed
+    /// here.
+    pub stale_keys: Vec<VerifyGcInfo>,
+}
+
 /// Used to perform GC operations on the engine.
 struct GcRunner<E: Engine> {
     engine: E,
@@ -147,6 +298,12 @@ struct GcRunner<E: Engine> {
     cfg_tracker: Tracker<GcConfig>,
 
     stats: Statistics,
+
+    audit_sink: Option<Arc<dyn GcAuditSink>>,
+
+    /// Shared with `GcWorker::pending_tasks`; decremented once a `Gc` task is dequeued and
+    /// begins executing, i.e. it's no longer merely sitting in the worker's queue.
+    pending_tasks: Arc<atomic::AtomicUsize>,
 }
 
 impl<E: Engine> GcRunner<E> {
@@ -157,6 +314,8 @@ impl<E: Engine> GcRunner<E> {
         cfg_tracker: Tracker<GcConfig>,
         region_info_accessor: Option<RegionInfoAccessor>,
         cfg: GcConfig,
+        audit_sink: Option<Arc<dyn GcAuditSink>>,
+        pending_tasks: Arc<atomic::AtomicUsize>,
     ) -> Self {
         let limiter = Limiter::new(if cfg.max_write_bytes_per_sec.0 > 0 {
             cfg.max_write_bytes_per_sec.0 as f64
@@ -172,6 +331,8 @@ impl<E: Engine> GcRunner<E> {
             cfg,
             cfg_tracker,
             stats: Statistics::default(),
+            audit_sink,
+            pending_tasks,
         }
     }
 
@@ -314,7 +475,7 @@ impl<E: Engine> GcRunner<E> {
         safe_point: TimeStamp,
         keys: Vec<Key>,
         mut next_scan_key: Option<Key>,
-    ) -> Result<Option<Key>> {
+    ) -> Result<(Option<Key>, u64)> {
         let snapshot = self.get_snapshot(ctx)?;
         let mut txn = MvccTxn::for_scan(
             snapshot,
@@ -322,8 +483,20 @@ impl<E: Engine> GcRunner<E> {
             TimeStamp::zero(),
             !ctx.get_not_fill_cache(),
         );
+        // Only collect the deleted (key, commit_ts) pairs when an audit sink is configured, so
+        // that GC pays no extra cost when the feature is disabled.
+        let mut audited: Vec<(Key, TimeStamp)> = Vec::new();
+        let mut deleted_versions = 0;
         for k in keys {
-            let gc_info = txn.gc(k.clone(), safe_point)?;
+            let gc_info = if self.audit_sink.is_some() {
+                let mut collector = |key: &Key, commit_ts: TimeStamp| {
+                    audited.push((key.clone(), commit_ts));
+                };
+                txn.gc_with_audit(k.clone(), safe_point, Some(&mut collector))?
+            } else {
+                txn.gc(k.clone(), safe_point)?
+            };
+            deleted_versions += gc_info.deleted_versions as u64;
 
             if gc_info.found_versions >= GC_LOG_FOUND_VERSION_THRESHOLD {
                 debug!(
@@ -351,6 +524,12 @@ impl<E: Engine> GcRunner<E> {
         }
         self.stats.add(&txn.take_statistics());
 
+        if let Some(sink) = self.audit_sink.as_ref() {
+            if !audited.is_empty() {
+                sink.audit(&audited);
+            }
+        }
+
         let write_size = txn.write_size();
         let modifies = txn.into_modifies();
         if !modifies.is_empty() {
@@ -358,10 +537,66 @@ impl<E: Engine> GcRunner<E> {
             self.limiter.blocking_consume(write_size);
             self.engine.write(ctx, WriteData::from_modifies(modifies))?;
         }
-        Ok(next_scan_key)
+        Ok((next_scan_key, deleted_versions))
     }
 
-    fn gc(&mut self, ctx: &mut Context, safe_point: TimeStamp) -> Result<()> {
+    /// One-shot manual GC of a single user key, leaving every other key untouched. Unlike `gc`,
+    /// which sweeps a whole region, this exists for operators debugging one hot key. Returns how
+    /// many stale versions were deleted.
+    fn gc_key(&mut self, ctx: &mut Context, key: Key, safe_point: TimeStamp) -> Result<usize> {
+        let snapshot = self.get_snapshot(ctx)?;
+        let mut txn = MvccTxn::for_scan(
+            snapshot,
+            Some(ScanMode::Forward),
+            TimeStamp::zero(),
+            !ctx.get_not_fill_cache(),
+        );
+        let mut audited: Vec<(Key, TimeStamp)> = Vec::new();
+        let gc_info = if self.audit_sink.is_some() {
+            let mut collector = |key: &Key, commit_ts: TimeStamp| {
+                audited.push((key.clone(), commit_ts));
+            };
+            txn.gc_with_audit(key.clone(), safe_point, Some(&mut collector))?
+        } else {
+            txn.gc(key.clone(), safe_point)?
+        };
+        self.stats.add(&txn.take_statistics());
+
+        if let Some(sink) = self.audit_sink.as_ref() {
+            if !audited.is_empty() {
+                sink.audit(&audited);
+            }
+        }
+
+        let write_size = txn.write_size();
+        let modifies = txn.into_modifies();
+        if !modifies.is_empty() {
+            self.refresh_cfg();
+            self.limiter.blocking_consume(write_size);
+            self.engine.write(ctx, WriteData::from_modifies(modifies))?;
+        }
+        Ok(gc_info.deleted_versions as usize)
+    }
+
+    /// Pulls `safe_point` back by `GcConfig::gc_safe_point_buffer`, so callers of `gc` never
+    /// scan away versions committed within the buffer window even though `safe_point` itself
+    /// covers them. A zero buffer returns `safe_point` unchanged.
+    fn adjust_safe_point(&self, safe_point: TimeStamp) -> TimeStamp {
+        let buffer_ms = self.cfg.gc_safe_point_buffer.as_millis();
+        if buffer_ms == 0 {
+            return safe_point;
+        }
+        let physical = safe_point.physical().saturating_sub(buffer_ms);
+        TimeStamp::compose(physical, safe_point.logical())
+    }
+
+    fn gc(
+        &mut self,
+        ctx: &mut Context,
+        safe_point: TimeStamp,
+        deadline: Option<Instant>,
+    ) -> Result<GcStats> {
+        let safe_point = self.adjust_safe_point(safe_point);
         debug!(
             "start doing GC";
             "region_id" => ctx.get_region_id(),
@@ -370,11 +605,25 @@ impl<E: Engine> GcRunner<E> {
 
         if !self.need_gc(ctx, safe_point) {
             GC_SKIPPED_COUNTER.inc();
-            return Ok(());
+            return Ok(GcStats::default());
         }
 
+        let started_at = Instant::now();
         let mut next_key = None;
+        let mut gc_stats = GcStats::default();
         loop {
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    warn!(
+                        "gc task exceeded its hard time limit and was aborted";
+                        "region_id" => ctx.get_region_id(),
+                        "safe_point" => safe_point,
+                        "elapsed" => ?started_at.elapsed(),
+                    );
+                    return Err(Error::from(ErrorInner::GcTaskTimedOut(started_at.elapsed())));
+                }
+            }
+
             // Scans at most `GCConfig.batch_keys` keys
             let (keys, next) = self
                 .scan_keys(ctx, safe_point, next_key)
@@ -382,15 +631,32 @@ impl<E: Engine> GcRunner<E> {
                     warn!("gc scan_keys failed"; "region_id" => ctx.get_region_id(), "safe_point" => safe_point, "err" => ?e);
                     e
                 })?;
+            // The snapshot taken inside `scan_keys` can stall on its own, so check the deadline
+            // again before acting on the batch it returned.
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    warn!(
+                        "gc task exceeded its hard time limit and was aborted";
+                        "region_id" => ctx.get_region_id(),
+                        "safe_point" => safe_point,
+                        "elapsed" => ?started_at.elapsed(),
+                    );
+                    return Err(Error::from(ErrorInner::GcTaskTimedOut(started_at.elapsed())));
+                }
+            }
             if keys.is_empty() {
                 break;
             }
+            gc_stats.scanned_keys += keys.len() as u64;
 
             // Does the GC operation on all scanned keys
-            next_key = self.gc_keys(ctx, safe_point, keys, next).map_err(|e| {
-                warn!("gc gc_keys failed"; "region_id" => ctx.get_region_id(), "safe_point" => safe_point, "err" => ?e);
-                e
-            })?;
+            let (next, deleted_versions) =
+                self.gc_keys(ctx, safe_point, keys, next).map_err(|e| {
+                    warn!("gc gc_keys failed"; "region_id" => ctx.get_region_id(), "safe_point" => safe_point, "err" => ?e);
+                    e
+                })?;
+            gc_stats.deleted_versions += deleted_versions;
+            next_key = next;
             if next_key.is_none() {
                 break;
             }
@@ -401,7 +667,7 @@ impl<E: Engine> GcRunner<E> {
             "region_id" => ctx.get_region_id(),
             "safe_point" => safe_point
         );
-        Ok(())
+        Ok(gc_stats)
     }
 
     fn unsafe_destroy_range(&self, _: &Context, start_key: &Key, end_key: &Key) -> Result<()> {
@@ -488,12 +754,18 @@ impl<E: Engine> GcRunner<E> {
         Ok(())
     }
 
+    /// `max_bytes`, when set, additionally stops accumulating once the summed protobuf-encoded
+    /// size of the returned `LockInfo`s reaches it, so a handful of huge locks can't blow up the
+    /// response's memory footprint the way a plain count-based `limit` would allow. Whichever
+    /// lock crosses the cap is still included, so the response is never truncated to empty even
+    /// when the very first lock is already oversized.
     fn handle_physical_scan_lock(
         &self,
         _: &Context,
         max_ts: TimeStamp,
         start_key: &Key,
         limit: usize,
+        max_bytes: Option<u64>,
     ) -> Result<Vec<LockInfo>> {
         let db = self.local_storage.clone().ok_or_else::<Error, _>(|| {
             box_err!("local storage not set, physical scan lock not supported")
@@ -511,13 +783,74 @@ impl<E: Engine> GcRunner<E> {
         let (locks, _) = reader.scan_locks(Some(start_key), |l| l.ts <= max_ts, limit)?;
 
         let mut lock_infos = Vec::with_capacity(locks.len());
+        let mut accumulated_bytes = 0u64;
         for (key, lock) in locks {
             let raw_key = key.into_raw().map_err(MvccError::from)?;
-            lock_infos.push(lock.into_lock_info(raw_key));
+            let lock_info = lock.into_lock_info(raw_key);
+            accumulated_bytes += u64::from(lock_info.compute_size());
+            lock_infos.push(lock_info);
+            if let Some(max_bytes) = max_bytes {
+                if accumulated_bytes >= max_bytes {
+                    break;
+                }
+            }
         }
         Ok(lock_infos)
     }
 
+    /// Scans `[start_key, end_key)` for keys that still have versions at or below `safe_point`,
+    /// which a completed GC run should have removed. Read-only: it never modifies data, so it can
+    /// be run at any time to double-check that a previous GC pass actually finished.
+    fn verify_gc(
+        &self,
+        start_key: &Key,
+        end_key: &Key,
+        safe_point: TimeStamp,
+        limit: usize,
+    ) -> Result<VerifyGcReport> {
+        let db = self.local_storage.clone().ok_or_else::<Error, _>(|| {
+            box_err!("local storage not set, verify_gc not supported")
+        })?;
+
+        // Same trick as `handle_physical_scan_lock`: a fake region whose bounds are the caller's
+        // key range lets `RegionSnapshot` present the raw RocksDB data as if it were a normal,
+        // already-bounded region snapshot.
+        let mut fake_region = metapb::Region::default();
+        fake_region.mut_peers().push(metapb::Peer::default());
+        fake_region.set_start_key(start_key.as_encoded().clone());
+        fake_region.set_end_key(end_key.as_encoded().clone());
+        let snap = RegionSnapshot::<RocksEngine>::from_raw(db, fake_region);
+
+        let mut reader = MvccReader::new(snap, Some(ScanMode::Forward), false, IsolationLevel::Si);
+
+        let mut report = VerifyGcReport::default();
+        let mut next_key = Some(start_key.clone());
+        loop {
+            let (keys, next) = reader.scan_keys(next_key.take(), self.cfg.batch_keys)?;
+            if keys.is_empty() {
+                break;
+            }
+            for key in keys {
+                report.keys_scanned += 1;
+                let stale_versions = count_stale_versions(&mut reader, &key, safe_point)?;
+                if stale_versions > 0 {
+                    report.stale_keys.push(VerifyGcInfo {
+                        key,
+                        stale_versions,
+                    });
+                    if report.stale_keys.len() >= limit {
+                        return Ok(report);
+                    }
+                }
+            }
+            if next.is_none() {
+                break;
+            }
+            next_key = next;
+        }
+        Ok(report)
+    }
+
     fn update_statistics_metrics(&mut self) {
         let stats = mem::replace(&mut self.stats, Statistics::default());
         for (cf, details) in stats.details().iter() {
@@ -545,7 +878,7 @@ impl<E: Engine> FutureRunnable<GcTask> for GcRunner<E> {
         let label = task.get_label();
         GC_GCTASK_COUNTER_VEC.with_label_values(&[label]).inc();
 
-        let timer = SlowTimer::from_secs(GC_TASK_SLOW_SECONDS);
+        let timer = SlowTimer::from(self.cfg.task_slow_threshold.0);
         let update_metrics = |is_err| {
             GC_TASK_DURATION_HISTOGRAM_VEC
                 .with_label_values(&[label])
@@ -554,6 +887,9 @@ impl<E: Engine> FutureRunnable<GcTask> for GcRunner<E> {
             if is_err {
                 GC_GCTASK_FAIL_COUNTER_VEC.with_label_values(&[label]).inc();
             }
+            if timer.is_slow() {
+                GC_TASK_SLOW_COUNTER_VEC.with_label_values(&[label]).inc();
+            }
         };
 
         // Refresh config before handle task
@@ -563,9 +899,16 @@ impl<E: Engine> FutureRunnable<GcTask> for GcRunner<E> {
             GcTask::Gc {
                 mut ctx,
                 safe_point,
+                priority: _,
                 callback,
             } => {
-                let res = self.gc(&mut ctx, safe_point);
+                self.pending_tasks.fetch_sub(1, atomic::Ordering::SeqCst);
+                let deadline = if self.cfg.task_timeout.as_millis() == 0 {
+                    None
+                } else {
+                    Some(Instant::now() + self.cfg.task_timeout.0)
+                };
+                let res = self.gc(&mut ctx, safe_point, deadline);
                 update_metrics(res.is_err());
                 callback(res);
                 self.update_statistics_metrics();
@@ -598,9 +941,11 @@ impl<E: Engine> FutureRunnable<GcTask> for GcRunner<E> {
                 max_ts,
                 start_key,
                 limit,
+                max_bytes,
                 callback,
             } => {
-                let res = self.handle_physical_scan_lock(&ctx, max_ts, &start_key, limit);
+                let res =
+                    self.handle_physical_scan_lock(&ctx, max_ts, &start_key, limit, max_bytes);
                 update_metrics(res.is_err());
                 callback(res);
                 slow_log!(
@@ -611,6 +956,41 @@ impl<E: Engine> FutureRunnable<GcTask> for GcRunner<E> {
                     limit,
                 );
             }
+            GcTask::VerifyGc {
+                start_key,
+                end_key,
+                safe_point,
+                limit,
+                callback,
+            } => {
+                let res = self.verify_gc(&start_key, &end_key, safe_point, limit);
+                update_metrics(res.is_err());
+                callback(res);
+                slow_log!(
+                    T timer,
+                    "VerifyGc start_key {:?}, end_key {:?}, safe_point {}",
+                    start_key,
+                    end_key,
+                    safe_point,
+                );
+            }
+            GcTask::GcKey {
+                mut ctx,
+                key,
+                safe_point,
+                callback,
+            } => {
+                let res = self.gc_key(&mut ctx, key.clone(), safe_point);
+                update_metrics(res.is_err());
+                callback(res);
+                slow_log!(
+                    T timer,
+                    "GcKey on region {}, key {}, safe_point {}",
+                    ctx.get_region_id(),
+                    key,
+                    safe_point
+                );
+            }
             #[cfg(any(test, feature = "testexport"))]
             GcTask::Validate(f) => {
                 f(&self.cfg, &self.limiter);
@@ -619,6 +999,35 @@ impl<E: Engine> FutureRunnable<GcTask> for GcRunner<E> {
     }
 }
 
+/// Counts how many versions of `key` at or below `safe_point` are still present. A key that has
+/// been fully GC-ed has at most one: a single `Put` (everything else at or below the safe point,
+/// including a top `Delete`/`Rollback`/`Lock`, should have been removed).
+fn count_stale_versions<S: crate::storage::kv::Snapshot>(
+    reader: &mut MvccReader<S>,
+    key: &Key,
+    safe_point: TimeStamp,
+) -> Result<usize> {
+    use txn_types::WriteType;
+
+    let mut ts = safe_point;
+    let mut stale_versions = 0;
+    let mut is_first = true;
+    while let Some((commit, write)) = reader.seek_write(key, ts)? {
+        if is_first {
+            is_first = false;
+            if write.write_type != WriteType::Put {
+                // The only version a completed GC would keep is a `Put`; anything else at or
+                // below the safe point should already be gone.
+                stale_versions += 1;
+            }
+        } else {
+            stale_versions += 1;
+        }
+        ts = commit.prev();
+    }
+    Ok(stale_versions)
+}
+
 /// When we failed to schedule a `GcTask` to `GcRunner`, use this to handle the `ScheduleError`.
 fn handle_gc_task_schedule_error(e: FutureWorkerStopped<GcTask>) -> Result<()> {
     error!("failed to schedule gc task"; "err" => %e);
@@ -636,7 +1045,8 @@ fn schedule_gc(
         .schedule(GcTask::Gc {
             ctx,
             safe_point,
-            callback,
+            priority: GcPriority::Normal,
+            callback: Box::new(move |res| callback(res.map(|_: GcStats| ()))),
         })
         .or_else(handle_gc_task_schedule_error)
 }
@@ -670,6 +1080,19 @@ pub struct GcWorker<E: Engine> {
     /// How many requests are scheduled from outside and unfinished.
     scheduled_tasks: Arc<atomic::AtomicUsize>,
 
+    /// How many `Gc` tasks are sitting in the worker's queue, scheduled but not yet dequeued
+    /// for execution. Unlike `scheduled_tasks`, this drops as soon as a task starts running
+    /// rather than when it finishes, so it reflects backlog depth rather than concurrency.
+    pending_tasks: Arc<atomic::AtomicUsize>,
+
+    /// The concurrency currently allowed, self-adjusted within
+    /// `[GcConfig::min_concurrency, GcConfig::max_concurrency]` based on `recent_latency_ms`.
+    /// `check_is_busy` compares `scheduled_tasks` against this instead of the configured max.
+    effective_concurrency: Arc<atomic::AtomicUsize>,
+    /// Exponential moving average, in milliseconds, of how long recently finished tasks took.
+    /// Zero means no task has finished yet.
+    recent_latency_ms: Arc<atomic::AtomicU64>,
+
     /// How many strong references. The worker will be stopped
     /// once there are no more references.
     refs: Arc<atomic::AtomicUsize>,
@@ -679,6 +1102,10 @@ pub struct GcWorker<E: Engine> {
     applied_lock_collector: Option<Arc<AppliedLockCollector>>,
 
     gc_manager_handle: Arc<Mutex<Option<GcManagerHandle>>>,
+
+    /// Optional sink receiving every version GC deletes, for compliance audit logging. Not set
+    /// by default.
+    audit_sink: Option<Arc<dyn GcAuditSink>>,
 }
 
 impl<E: Engine> Clone for GcWorker<E> {
@@ -693,11 +1120,15 @@ impl<E: Engine> Clone for GcWorker<E> {
             config_manager: self.config_manager.clone(),
             region_info_accessor: self.region_info_accessor.clone(),
             scheduled_tasks: self.scheduled_tasks.clone(),
+            pending_tasks: self.pending_tasks.clone(),
+            effective_concurrency: self.effective_concurrency.clone(),
+            recent_latency_ms: self.recent_latency_ms.clone(),
             refs: self.refs.clone(),
             worker: self.worker.clone(),
             worker_scheduler: self.worker_scheduler.clone(),
             applied_lock_collector: self.applied_lock_collector.clone(),
             gc_manager_handle: self.gc_manager_handle.clone(),
+            audit_sink: self.audit_sink.clone(),
         }
     }
 }
@@ -728,6 +1159,7 @@ impl<E: Engine> GcWorker<E> {
     ) -> GcWorker<E> {
         let worker = Arc::new(Mutex::new(FutureWorker::new("gc-worker")));
         let worker_scheduler = worker.lock().unwrap().scheduler();
+        let initial_concurrency = cfg.max_concurrency;
         GcWorker {
             engine,
             local_storage,
@@ -735,14 +1167,23 @@ impl<E: Engine> GcWorker<E> {
             config_manager: GcWorkerConfigManager(Arc::new(VersionTrack::new(cfg))),
             region_info_accessor,
             scheduled_tasks: Arc::new(atomic::AtomicUsize::new(0)),
+            pending_tasks: Arc::new(atomic::AtomicUsize::new(0)),
+            effective_concurrency: Arc::new(atomic::AtomicUsize::new(initial_concurrency)),
+            recent_latency_ms: Arc::new(atomic::AtomicU64::new(0)),
             refs: Arc::new(atomic::AtomicUsize::new(1)),
             worker,
             worker_scheduler,
             applied_lock_collector: None,
             gc_manager_handle: Arc::new(Mutex::new(None)),
+            audit_sink: None,
         }
     }
 
+    /// Sets the sink that receives every version GC deletes. Must be called before `start()`.
+    pub fn set_audit_sink(&mut self, sink: Arc<dyn GcAuditSink>) {
+        self.audit_sink = Some(sink);
+    }
+
     pub fn start_auto_gc<S: GcSafePointProvider, R: RegionInfoProvider>(
         &self,
         cfg: AutoGcConfig<S, R>,
@@ -765,6 +1206,8 @@ impl<E: Engine> GcWorker<E> {
                 .tracker("gc-worker".to_owned()),
             self.region_info_accessor.take(),
             self.config_manager.value().clone(),
+            self.audit_sink.clone(),
+            self.pending_tasks.clone(),
         );
         self.worker
             .lock()
@@ -803,31 +1246,91 @@ impl<E: Engine> GcWorker<E> {
 
     /// Check whether GCWorker is busy. If busy, callback will be invoked with an error that
     /// indicates GCWorker is busy; otherwise, return a new callback that invokes the original
-    /// callback as well as decrease the scheduled task counter.
-    fn check_is_busy<T: 'static>(&self, callback: Callback<T>) -> Option<Callback<T>> {
-        if self.scheduled_tasks.fetch_add(1, atomic::Ordering::SeqCst) >= GC_MAX_EXECUTING_TASKS {
+    /// callback, decreases the scheduled task counter, and feeds the task's latency into the
+    /// adaptive concurrency limit.
+    ///
+    /// `priority` only affects admission: a `GcPriority::High` task is checked against
+    /// `effective_concurrency + GC_HIGH_PRIORITY_RESERVED_SLOTS` instead of the plain limit, so it
+    /// can get in when the worker would otherwise reject it. It does not jump ahead of tasks
+    /// already queued — the underlying scheduler is a single FIFO queue with no concept of
+    /// priority ordering.
+    fn check_is_busy<T: 'static>(
+        &self,
+        priority: GcPriority,
+        callback: Callback<T>,
+    ) -> Option<Callback<T>> {
+        let mut limit = self.effective_concurrency.load(atomic::Ordering::SeqCst);
+        if priority == GcPriority::High {
+            limit += GC_HIGH_PRIORITY_RESERVED_SLOTS;
+        }
+        if self.scheduled_tasks.fetch_add(1, atomic::Ordering::SeqCst) >= limit {
             self.scheduled_tasks.fetch_sub(1, atomic::Ordering::SeqCst);
             callback(Err(Error::from(ErrorInner::GcWorkerTooBusy)));
             return None;
         }
         let scheduled_tasks = Arc::clone(&self.scheduled_tasks);
+        let effective_concurrency = Arc::clone(&self.effective_concurrency);
+        let recent_latency_ms = Arc::clone(&self.recent_latency_ms);
+        let (min_concurrency, max_concurrency) = {
+            let cfg = self.config_manager.value();
+            (cfg.min_concurrency, cfg.max_concurrency)
+        };
+        let start = Instant::now();
         Some(Box::new(move |r| {
             scheduled_tasks.fetch_sub(1, atomic::Ordering::SeqCst);
+            update_adaptive_concurrency(
+                &recent_latency_ms,
+                &effective_concurrency,
+                min_concurrency,
+                max_concurrency,
+                start.elapsed(),
+            );
             callback(r);
         }))
     }
 
-    pub fn gc(&self, ctx: Context, safe_point: TimeStamp, callback: Callback<()>) -> Result<()> {
+    /// Schedules a `Gc` task. `priority` controls whether this request may use the reserved
+    /// slots above the normal concurrency limit; see `GcPriority`.
+    pub fn gc(
+        &self,
+        ctx: Context,
+        safe_point: TimeStamp,
+        priority: GcPriority,
+        callback: Callback<()>,
+    ) -> Result<()> {
+        self.gc_with_stats(
+            ctx,
+            safe_point,
+            priority,
+            Box::new(move |res| callback(res.map(|_| ()))),
+        )
+    }
+
+    /// Like `gc`, but the callback additionally receives a `GcStats` summary of the versions and
+    /// keys the task processed on success, e.g. for audit logging.
+    pub fn gc_with_stats(
+        &self,
+        ctx: Context,
+        safe_point: TimeStamp,
+        priority: GcPriority,
+        callback: Callback<GcStats>,
+    ) -> Result<()> {
         GC_COMMAND_COUNTER_VEC_STATIC.gc.inc();
-        self.check_is_busy(callback).map_or(Ok(()), |callback| {
-            self.worker_scheduler
-                .schedule(GcTask::Gc {
-                    ctx,
-                    safe_point,
-                    callback,
-                })
-                .or_else(handle_gc_task_schedule_error)
-        })
+        self.check_is_busy(priority, callback)
+            .map_or(Ok(()), |callback| {
+                self.pending_tasks.fetch_add(1, atomic::Ordering::SeqCst);
+                self.worker_scheduler
+                    .schedule(GcTask::Gc {
+                        ctx,
+                        safe_point,
+                        priority,
+                        callback,
+                    })
+                    .or_else(|e| {
+                        self.pending_tasks.fetch_sub(1, atomic::Ordering::SeqCst);
+                        handle_gc_task_schedule_error(e)
+                    })
+            })
     }
 
     /// Cleans up all keys in a range and quickly free the disk space. The range might span over
@@ -843,42 +1346,118 @@ impl<E: Engine> GcWorker<E> {
         callback: Callback<()>,
     ) -> Result<()> {
         GC_COMMAND_COUNTER_VEC_STATIC.unsafe_destroy_range.inc();
-        self.check_is_busy(callback).map_or(Ok(()), |callback| {
-            self.worker_scheduler
-                .schedule(GcTask::UnsafeDestroyRange {
-                    ctx,
-                    start_key,
-                    end_key,
-                    callback,
-                })
-                .or_else(handle_gc_task_schedule_error)
-        })
+        self.check_is_busy(GcPriority::Normal, callback)
+            .map_or(Ok(()), |callback| {
+                self.worker_scheduler
+                    .schedule(GcTask::UnsafeDestroyRange {
+                        ctx,
+                        start_key,
+                        end_key,
+                        callback,
+                    })
+                    .or_else(handle_gc_task_schedule_error)
+            })
     }
 
     pub fn get_config_manager(&self) -> GcWorkerConfigManager {
         self.config_manager.clone()
     }
 
+    /// Returns the concurrency currently allowed by the adaptive limit. See
+    /// `GcConfig::max_concurrency`.
+    pub fn get_effective_concurrency(&self) -> usize {
+        self.effective_concurrency.load(atomic::Ordering::SeqCst)
+    }
+
+    /// Returns the number of `Gc` tasks that have been accepted by `gc()` but haven't started
+    /// executing yet, i.e. how deep the worker's internal queue currently is. Operators can
+    /// alert on this climbing steadily as an early warning before `GcWorkerTooBusy` starts
+    /// being returned.
+    pub fn pending_tasks(&self) -> usize {
+        self.pending_tasks.load(atomic::Ordering::SeqCst)
+    }
+
+    /// `max_bytes`, when `Some`, caps the summed size of returned locks in addition to `limit`'s
+    /// cap on their count. See `handle_physical_scan_lock`.
     pub fn physical_scan_lock(
         &self,
         ctx: Context,
         max_ts: TimeStamp,
         start_key: Key,
         limit: usize,
+        max_bytes: Option<u64>,
         callback: Callback<Vec<LockInfo>>,
     ) -> Result<()> {
         GC_COMMAND_COUNTER_VEC_STATIC.physical_scan_lock.inc();
-        self.check_is_busy(callback).map_or(Ok(()), |callback| {
-            self.worker_scheduler
-                .schedule(GcTask::PhysicalScanLock {
-                    ctx,
-                    max_ts,
-                    start_key,
-                    limit,
-                    callback,
-                })
-                .or_else(handle_gc_task_schedule_error)
-        })
+        self.check_is_busy(GcPriority::Normal, callback)
+            .map_or(Ok(()), |callback| {
+                self.worker_scheduler
+                    .schedule(GcTask::PhysicalScanLock {
+                        ctx,
+                        max_ts,
+                        start_key,
+                        limit,
+                        max_bytes,
+                        callback,
+                    })
+                    .or_else(handle_gc_task_schedule_error)
+            })
+    }
+
+    /// Scans `[start_key, end_key)` and reports keys that still have versions at or below
+    /// `safe_point`, i.e. versions a completed GC run should have already removed. Read-only;
+    /// intended as an after-the-fact consistency check, not part of the regular GC path. The
+    /// report's `stale_keys` is bounded by `limit`.
+    ///
+    /// Like `unsafe_destroy_range`, `start_key`/`end_key` are the range bounds directly, without
+    /// going through raft or a particular region's `Context`; an empty key means unbounded on
+    /// that side.
+    pub fn verify_gc(
+        &self,
+        start_key: Key,
+        end_key: Key,
+        safe_point: TimeStamp,
+        limit: usize,
+        callback: Callback<VerifyGcReport>,
+    ) -> Result<()> {
+        GC_COMMAND_COUNTER_VEC_STATIC.verify_gc.inc();
+        self.check_is_busy(GcPriority::Normal, callback)
+            .map_or(Ok(()), |callback| {
+                self.worker_scheduler
+                    .schedule(GcTask::VerifyGc {
+                        start_key,
+                        end_key,
+                        safe_point,
+                        limit,
+                        callback,
+                    })
+                    .or_else(handle_gc_task_schedule_error)
+            })
+    }
+
+    /// GCs a single user key's versions at or below `safe_point`, one-shot, without waiting for
+    /// the key's region to come up in the regular GC sweep. Meant for operators debugging a
+    /// specific hot key; every other key is left untouched. On success the callback receives how
+    /// many stale versions were deleted.
+    pub fn gc_key(
+        &self,
+        ctx: Context,
+        key: Key,
+        safe_point: TimeStamp,
+        callback: Callback<usize>,
+    ) -> Result<()> {
+        GC_COMMAND_COUNTER_VEC_STATIC.gc_key.inc();
+        self.check_is_busy(GcPriority::Normal, callback)
+            .map_or(Ok(()), |callback| {
+                self.worker_scheduler
+                    .schedule(GcTask::GcKey {
+                        ctx,
+                        key,
+                        safe_point,
+                        callback,
+                    })
+                    .or_else(handle_gc_task_schedule_error)
+            })
     }
 
     pub fn start_collecting(
@@ -895,7 +1474,7 @@ impl<E: Engine> GcWorker<E> {
     pub fn get_collected_locks(
         &self,
         max_ts: TimeStamp,
-        callback: LockCollectorCallback<(Vec<LockInfo>, bool)>,
+        callback: LockCollectorCallback<(Vec<LockInfo>, bool, u64)>,
     ) -> Result<()> {
         self.applied_lock_collector
             .as_ref()
@@ -1192,7 +1771,7 @@ mod tests {
         let physical_scan_lock = |max_ts: u64, start_key, limit| {
             let (cb, f) = paired_future_callback();
             gc_worker
-                .physical_scan_lock(Context::default(), max_ts.into(), start_key, limit, cb)
+                .physical_scan_lock(Context::default(), max_ts.into(), start_key, limit, None, cb)
                 .unwrap();
             f.wait().unwrap()
         };
@@ -1244,5 +1823,22 @@ mod tests {
         let res = physical_scan_lock(11, Key::from_raw(&start_key), 6).unwrap();
         // expected_locks[3] is the key 4.
         assert_eq!(res[..], expected_lock_info[3..9]);
+
+        // A `max_bytes` cap tight enough to only fit the first lock truncates the rest, even
+        // though `limit` alone would have allowed all of them through.
+        let one_lock_size = u64::from(expected_lock_info[0].compute_size());
+        let (cb, f) = paired_future_callback();
+        gc_worker
+            .physical_scan_lock(
+                Context::default(),
+                11.into(),
+                Key::from_raw(b""),
+                50,
+                Some(one_lock_size),
+                cb,
+            )
+            .unwrap();
+        let res = f.wait().unwrap().unwrap();
+        assert_eq!(res, expected_lock_info[..1]);
     }
 }
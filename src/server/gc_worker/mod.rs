@@ -9,4 +9,7 @@ mod gc_worker;
 pub use crate::storage::{Callback, Error, ErrorInner, Result};
 pub use config::{GcConfig, GcWorkerConfigManager, DEFAULT_GC_BATCH_KEYS};
 pub use gc_manager::AutoGcConfig;
-pub use gc_worker::{GcSafePointProvider, GcTask, GcWorker, GC_MAX_EXECUTING_TASKS};
+pub use gc_worker::{
+    GcAuditSink, GcPriority, GcSafePointProvider, GcStats, GcTask, GcWorker, VerifyGcInfo,
+    VerifyGcReport, GC_MAX_EXECUTING_TASKS,
+};
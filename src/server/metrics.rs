@@ -51,8 +51,10 @@ make_static_metric! {
 
     pub label_enum GcCommandKind {
         gc,
+        gc_key,
         unsafe_destroy_range,
         physical_scan_lock,
+        verify_gc,
     }
 
     pub struct GrpcMsgHistogramVec: Histogram {
@@ -145,6 +147,12 @@ lazy_static! {
         &["task"]
     )
     .unwrap();
+    pub static ref GC_TASK_SLOW_COUNTER_VEC: IntCounterVec = register_int_counter_vec!(
+        "tikv_gcworker_gc_task_slow_vec",
+        "Counter of gc tasks whose execution time exceeded gc.task-slow-threshold",
+        &["task"]
+    )
+    .unwrap();
     pub static ref GC_TOO_BUSY_COUNTER: IntCounter = register_int_counter!(
         "tikv_gc_worker_too_busy",
         "Counter of occurrence of gc_worker being too busy"
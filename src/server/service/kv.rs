@@ -5,7 +5,7 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crate::coprocessor::Endpoint;
-use crate::server::gc_worker::GcWorker;
+use crate::server::gc_worker::{GcPriority, GcWorker};
 use crate::server::load_statistics::ThreadLoad;
 use crate::server::metrics::*;
 use crate::server::service::batch::ReqBatcher;
@@ -360,7 +360,17 @@ impl<T: RaftStoreRouter + 'static, E: Engine, L: LockManager> Tikv for Service<T
             .and_then(|v| {
                 let mut resp = CheckLockObserverResponse::default();
                 match v {
-                    Ok((locks, is_clean)) => {
+                    Ok((locks, is_clean, dropped)) => {
+                        // `CheckLockObserverResponse` has no field for the dropped-lock count, so
+                        // it can't be returned over the wire; log it here instead so the size of
+                        // an unclean gap is still visible to whoever is watching TiKV's logs.
+                        if dropped > 0 {
+                            warn!(
+                                "lock observer dropped some locks before they could be collected";
+                                "max_ts" => req.get_max_ts(),
+                                "dropped" => dropped,
+                            );
+                        }
                         resp.set_is_clean(is_clean);
                         resp.set_locks(locks.into());
                     }
@@ -430,11 +440,15 @@ impl<T: RaftStoreRouter + 'static, E: Engine, L: LockManager> Tikv for Service<T
             .start_coarse_timer();
 
         let (cb, f) = paired_future_callback();
+        // `PhysicalScanLockRequest` carries no byte-limit field, so RPC-triggered scans are
+        // always uncapped by size; `max_bytes` is only reachable through the in-process
+        // `GcWorker::physical_scan_lock` API.
         let res = self.gc_worker.physical_scan_lock(
             req.take_context(),
             req.get_max_ts().into(),
             Key::from_raw(req.get_start_key()),
             req.get_limit() as _,
+            None,
             cb,
         );
 
@@ -1182,7 +1196,14 @@ fn future_gc<E: Engine>(
     mut req: GcRequest,
 ) -> impl Future<Item = GcResponse, Error = Error> {
     let (cb, f) = paired_future_callback();
-    let res = gc_worker.gc(req.take_context(), req.get_safe_point().into(), cb);
+    // `GcRequest` carries no priority field, so RPC-triggered GC always runs at `Normal`;
+    // `GcPriority::High` is only reachable through the in-process `GcWorker::gc` API.
+    let res = gc_worker.gc(
+        req.take_context(),
+        req.get_safe_point().into(),
+        GcPriority::Normal,
+        cb,
+    );
 
     AndThenWith::new(res, f.map_err(Error::from)).map(|v| {
         let mut resp = GcResponse::default();
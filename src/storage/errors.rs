@@ -51,6 +51,10 @@ quick_error! {
         GcWorkerTooBusy {
             description("gc worker is too busy")
         }
+        GcTaskTimedOut(elapsed: std::time::Duration) {
+            description("gc task exceeded its hard time limit and was aborted")
+            display("gc task exceeded its hard time limit and was aborted after {:?}", elapsed)
+        }
         KeyTooLarge(size: usize, limit: usize) {
             description("max key size exceeded")
             display("max key size exceeded, size: {}, limit: {}", size, limit)
@@ -115,6 +119,7 @@ impl ErrorCodeExt for Error {
             ErrorInner::Io(_) => error_code::storage::IO,
             ErrorInner::SchedTooBusy => error_code::storage::SCHED_TOO_BUSY,
             ErrorInner::GcWorkerTooBusy => error_code::storage::GC_WORKER_TOO_BUSY,
+            ErrorInner::GcTaskTimedOut(_) => error_code::storage::GC_TASK_TIMED_OUT,
             ErrorInner::KeyTooLarge(_, _) => error_code::storage::KEY_TOO_LARGE,
             ErrorInner::InvalidCf(_) => error_code::storage::INVALID_CF,
             ErrorInner::PessimisticTxnNotEnabled => error_code::storage::PESSIMISTIC_TXN_NOT_ENABLE,
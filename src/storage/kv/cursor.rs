@@ -300,7 +300,10 @@ impl<I: Iterator> Cursor<I> {
         statistics.seek += 1;
         self.mark_unread();
         let before = PerfContext::get().internal_delete_skipped_count();
-        let res = self.iter.seek_to_first().expect("Invalid Iterator");
+        let iter = &mut self.iter;
+        let res = statistics
+            .time_read(|| iter.seek_to_first())
+            .expect("Invalid Iterator");
         statistics.seek_tombstone +=
             (PerfContext::get().internal_delete_skipped_count() - before) as usize;
         res
@@ -311,7 +314,10 @@ impl<I: Iterator> Cursor<I> {
         statistics.seek += 1;
         self.mark_unread();
         let before = PerfContext::get().internal_delete_skipped_count();
-        let res = self.iter.seek_to_last().expect("Invalid Iterator");
+        let iter = &mut self.iter;
+        let res = statistics
+            .time_read(|| iter.seek_to_last())
+            .expect("Invalid Iterator");
         statistics.seek_tombstone +=
             (PerfContext::get().internal_delete_skipped_count() - before) as usize;
         res
@@ -322,7 +328,8 @@ impl<I: Iterator> Cursor<I> {
         statistics.seek += 1;
         self.mark_unread();
         let before = PerfContext::get().internal_delete_skipped_count();
-        let res = self.iter.seek(key);
+        let iter = &mut self.iter;
+        let res = statistics.time_read(|| iter.seek(key));
         statistics.seek_tombstone +=
             (PerfContext::get().internal_delete_skipped_count() - before) as usize;
         res
@@ -337,7 +344,8 @@ impl<I: Iterator> Cursor<I> {
         statistics.seek_for_prev += 1;
         self.mark_unread();
         let before = PerfContext::get().internal_delete_skipped_count();
-        let res = self.iter.seek_for_prev(key);
+        let iter = &mut self.iter;
+        let res = statistics.time_read(|| iter.seek_for_prev(key));
         statistics.seek_for_prev_tombstone +=
             (PerfContext::get().internal_delete_skipped_count() - before) as usize;
         res
@@ -348,7 +356,10 @@ impl<I: Iterator> Cursor<I> {
         statistics.next += 1;
         self.mark_unread();
         let before = PerfContext::get().internal_delete_skipped_count();
-        let res = self.iter.next().expect("Invalid Iterator");
+        let iter = &mut self.iter;
+        let res = statistics
+            .time_read(|| iter.next())
+            .expect("Invalid Iterator");
         statistics.next_tombstone +=
             (PerfContext::get().internal_delete_skipped_count() - before) as usize;
         res
@@ -359,7 +370,10 @@ impl<I: Iterator> Cursor<I> {
         statistics.prev += 1;
         self.mark_unread();
         let before = PerfContext::get().internal_delete_skipped_count();
-        let res = self.iter.prev().expect("Invalid Iterator");
+        let iter = &mut self.iter;
+        let res = statistics
+            .time_read(|| iter.prev())
+            .expect("Invalid Iterator");
         statistics.prev_tombstone +=
             (PerfContext::get().internal_delete_skipped_count() - before) as usize;
         res
@@ -25,7 +25,7 @@ pub use self::cursor::{Cursor, CursorBuilder};
 pub use self::perf_context::{PerfStatisticsDelta, PerfStatisticsInstant};
 pub use self::rocksdb_engine::{RocksEngine, RocksSnapshot, TestEngineBuilder};
 pub use self::stats::{
-    CfStatistics, FlowStatistics, FlowStatsReporter, Statistics, StatisticsSummary,
+    CfStatistics, FlowStatistics, FlowStatsReporter, OperatorLabel, Statistics, StatisticsSummary,
 };
 use error_code::{self, ErrorCode, ErrorCodeExt};
 use into_other::IntoOther;
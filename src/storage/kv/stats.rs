@@ -1,10 +1,28 @@
 // Copyright 2019 TiKV Project Authors. Licensed under Apache-2.0.
 
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
 use engine_traits::{CF_DEFAULT, CF_LOCK, CF_WRITE};
 use kvproto::kvrpcpb::{ScanDetail, ScanInfo};
+use tikv_util::collections::HashMap;
 
 pub use raftstore::store::{FlowStatistics, FlowStatsReporter};
 
+/// Gates whether `CfStatistics::time_read` actually calls `Instant::now()` around the iterator
+/// operation it wraps. Off by default, since even a cheap timestamp read adds up across the
+/// hottest scan path; flip it on to diagnose a specific slow scan.
+static SAMPLE_READ_NANOS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_read_nanos_sampling(enabled: bool) {
+    SAMPLE_READ_NANOS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn read_nanos_sampling_enabled() -> bool {
+    SAMPLE_READ_NANOS.load(Ordering::Relaxed)
+}
+
 const STAT_TOTAL: &str = "total";
 const STAT_PROCESSED: &str = "processed";
 const STAT_GET: &str = "get";
@@ -17,9 +35,12 @@ const STAT_NEXT_TOMBSTONE: &str = "next_tombstone";
 const STAT_PREV_TOMBSTONE: &str = "prev_tombstone";
 const STAT_SEEK_TOMBSTONE: &str = "seek_tombstone";
 const STAT_SEEK_FOR_PREV_TOMBSTONE: &str = "seek_for_prev_tombstone";
+const STAT_PROCESSED_KEYS_BYTES: &str = "processed_keys_bytes";
+const STAT_PROCESSED_VALUES_BYTES: &str = "processed_values_bytes";
+const STAT_READ_NANOS: &str = "read_nanos";
 
 /// Statistics collects the ops taken when fetching data.
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, PartialEq)]
 pub struct CfStatistics {
     // How many keys that's effective to user. This counter should be increased
     // by the caller.
@@ -36,15 +57,74 @@ pub struct CfStatistics {
     pub prev_tombstone: usize,
     pub seek_tombstone: usize,
     pub seek_for_prev_tombstone: usize,
+
+    // Bytes of the keys/values actually returned to the caller, i.e. counted alongside
+    // `processed`, not every key/value merely scanned over. Incremented by the scanner as each
+    // one is consumed.
+    pub processed_keys_bytes: usize,
+    pub processed_values_bytes: usize,
+
+    // Wall-clock time spent inside RocksDB iterator calls for this CF, accumulated by
+    // `time_read` while sampling is enabled via `set_read_nanos_sampling`. Zero when sampling is
+    // off, which is the default.
+    pub read_nanos: u64,
 }
 
 impl CfStatistics {
+    /// Runs `f`, adding its wall-clock time to `read_nanos` when sampling is enabled. When
+    /// sampling is off, skips the `Instant::now()` calls entirely so callers on hot paths pay
+    /// nothing.
+    #[inline]
+    pub fn time_read<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        if !read_nanos_sampling_enabled() {
+            return f();
+        }
+        let start = Instant::now();
+        let result = f();
+        self.read_nanos = self
+            .read_nanos
+            .saturating_add(start.elapsed().as_nanos() as u64);
+        result
+    }
+
+    /// Zeroes every counter in place, so a long-lived `Statistics` can be reused across scans
+    /// within one request instead of being replaced with `Default::default()`.
+    pub fn clear(&mut self) {
+        self.processed = 0;
+        self.get = 0;
+        self.next = 0;
+        self.prev = 0;
+        self.seek = 0;
+        self.seek_for_prev = 0;
+        self.over_seek_bound = 0;
+        self.flow_stats.read_keys = 0;
+        self.flow_stats.read_bytes = 0;
+        self.next_tombstone = 0;
+        self.prev_tombstone = 0;
+        self.seek_tombstone = 0;
+        self.seek_for_prev_tombstone = 0;
+        self.processed_keys_bytes = 0;
+        self.processed_values_bytes = 0;
+        self.read_nanos = 0;
+    }
+
     #[inline]
     pub fn total_op_count(&self) -> usize {
         self.get + self.next + self.prev + self.seek + self.seek_for_prev
     }
 
-    pub fn details(&self) -> [(&'static str, usize); 12] {
+    /// The ratio of seeks to nexts, as a rough indicator of scan locality: a scan that seeks far
+    /// more than it nexts is likely restarting its range too often, or scanning through a lot of
+    /// versions/tombstones between useful keys. Returns `0.0` when there are no seeks.
+    #[inline]
+    pub fn seek_next_ratio(&self) -> f64 {
+        if self.seek == 0 {
+            return 0.0;
+        }
+        self.seek as f64 / self.next.max(1) as f64
+    }
+
+    pub fn details(&self) -> [(&'static str, usize); 15] {
         [
             (STAT_TOTAL, self.total_op_count()),
             (STAT_PROCESSED, self.processed),
@@ -58,6 +138,9 @@ impl CfStatistics {
             (STAT_PREV_TOMBSTONE, self.prev_tombstone),
             (STAT_SEEK_TOMBSTONE, self.seek_tombstone),
             (STAT_SEEK_FOR_PREV_TOMBSTONE, self.seek_for_prev_tombstone),
+            (STAT_PROCESSED_KEYS_BYTES, self.processed_keys_bytes),
+            (STAT_PROCESSED_VALUES_BYTES, self.processed_values_bytes),
+            (STAT_READ_NANOS, self.read_nanos as usize),
         ]
     }
 
@@ -76,21 +159,129 @@ impl CfStatistics {
         self.seek_for_prev_tombstone = self
             .seek_for_prev_tombstone
             .saturating_add(other.seek_for_prev_tombstone);
+        self.processed_keys_bytes = self
+            .processed_keys_bytes
+            .saturating_add(other.processed_keys_bytes);
+        self.processed_values_bytes = self
+            .processed_values_bytes
+            .saturating_add(other.processed_values_bytes);
+        self.read_nanos = self.read_nanos.saturating_add(other.read_nanos);
+    }
+
+    /// Returns the per-field difference `self - baseline`, using saturating subtraction. The
+    /// inverse of `add`: snapshot a `CfStatistics` before a phase, snapshot again after, and
+    /// `sub` the two to attribute the phase's own work.
+    pub fn sub(&self, baseline: &Self) -> Self {
+        CfStatistics {
+            processed: self.processed.saturating_sub(baseline.processed),
+            get: self.get.saturating_sub(baseline.get),
+            next: self.next.saturating_sub(baseline.next),
+            prev: self.prev.saturating_sub(baseline.prev),
+            seek: self.seek.saturating_sub(baseline.seek),
+            seek_for_prev: self.seek_for_prev.saturating_sub(baseline.seek_for_prev),
+            over_seek_bound: self.over_seek_bound.saturating_sub(baseline.over_seek_bound),
+            flow_stats: FlowStatistics {
+                read_keys: self
+                    .flow_stats
+                    .read_keys
+                    .saturating_sub(baseline.flow_stats.read_keys),
+                read_bytes: self
+                    .flow_stats
+                    .read_bytes
+                    .saturating_sub(baseline.flow_stats.read_bytes),
+            },
+            next_tombstone: self.next_tombstone.saturating_sub(baseline.next_tombstone),
+            prev_tombstone: self.prev_tombstone.saturating_sub(baseline.prev_tombstone),
+            seek_tombstone: self.seek_tombstone.saturating_sub(baseline.seek_tombstone),
+            seek_for_prev_tombstone: self
+                .seek_for_prev_tombstone
+                .saturating_sub(baseline.seek_for_prev_tombstone),
+            processed_keys_bytes: self
+                .processed_keys_bytes
+                .saturating_sub(baseline.processed_keys_bytes),
+            processed_values_bytes: self
+                .processed_values_bytes
+                .saturating_sub(baseline.processed_values_bytes),
+            read_nanos: self.read_nanos.saturating_sub(baseline.read_nanos),
+        }
+    }
+
+    /// Divides every numeric field by `count`. Used by `StatisticsSummary::average`, which
+    /// guards against `count` being zero before calling this.
+    fn divide(&self, count: u64) -> Self {
+        let count = count as usize;
+        CfStatistics {
+            processed: self.processed / count,
+            get: self.get / count,
+            next: self.next / count,
+            prev: self.prev / count,
+            seek: self.seek / count,
+            seek_for_prev: self.seek_for_prev / count,
+            over_seek_bound: self.over_seek_bound / count,
+            flow_stats: FlowStatistics {
+                read_keys: self.flow_stats.read_keys / count,
+                read_bytes: self.flow_stats.read_bytes / count,
+            },
+            next_tombstone: self.next_tombstone / count,
+            prev_tombstone: self.prev_tombstone / count,
+            seek_tombstone: self.seek_tombstone / count,
+            seek_for_prev_tombstone: self.seek_for_prev_tombstone / count,
+            processed_keys_bytes: self.processed_keys_bytes / count,
+            processed_values_bytes: self.processed_values_bytes / count,
+            read_nanos: self.read_nanos / count as u64,
+        }
     }
 
     pub fn scan_info(&self) -> ScanInfo {
         let mut info = ScanInfo::default();
         info.set_processed(self.processed as i64);
         info.set_total(self.total_op_count() as i64);
+        // `ScanInfo` in this tree's vendored `kvrpcpb` has no field for processed byte counts, so
+        // `processed_keys_bytes`/`processed_values_bytes` are only surfaced via `details()`.
         info
     }
 }
 
-#[derive(Default, Clone, Debug)]
+/// Prints only non-zero fields, so a failing test assertion shows a concise diff instead of a
+/// wall of zeros.
+impl fmt::Debug for CfStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("CfStatistics");
+        macro_rules! field {
+            ($name:ident) => {
+                if self.$name != 0 {
+                    s.field(stringify!($name), &self.$name);
+                }
+            };
+        }
+        field!(processed);
+        field!(get);
+        field!(next);
+        field!(prev);
+        field!(seek);
+        field!(seek_for_prev);
+        field!(over_seek_bound);
+        if self.flow_stats != FlowStatistics::default() {
+            s.field("flow_stats", &self.flow_stats);
+        }
+        field!(next_tombstone);
+        field!(prev_tombstone);
+        field!(seek_tombstone);
+        field!(seek_for_prev_tombstone);
+        field!(processed_keys_bytes);
+        field!(processed_values_bytes);
+        field!(read_nanos);
+        s.finish()
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
 pub struct Statistics {
     pub lock: CfStatistics,
     pub write: CfStatistics,
     pub data: CfStatistics,
+    // How many times a range scan restarted because the underlying region epoch changed.
+    pub scan_restarts: usize,
 }
 
 impl Statistics {
@@ -102,7 +293,24 @@ impl Statistics {
         self.lock.processed + self.write.processed + self.data.processed
     }
 
-    pub fn details(&self) -> [(&'static str, [(&'static str, usize); 12]); 3] {
+    /// Sums `read_nanos` across all three CFs. Zero unless sampling was enabled via
+    /// `set_read_nanos_sampling` while this `Statistics` was being collected.
+    pub fn total_read_nanos(&self) -> u64 {
+        self.lock.read_nanos + self.write.read_nanos + self.data.read_nanos
+    }
+
+    /// The ratio of seeks to nexts across all CFs combined, see `CfStatistics::seek_next_ratio`.
+    /// Returns `0.0` when there are no seeks in any CF.
+    pub fn seek_next_ratio(&self) -> f64 {
+        let seek = self.lock.seek + self.write.seek + self.data.seek;
+        if seek == 0 {
+            return 0.0;
+        }
+        let next = self.lock.next + self.write.next + self.data.next;
+        seek as f64 / next.max(1) as f64
+    }
+
+    pub fn details(&self) -> [(&'static str, [(&'static str, usize); 15]); 3] {
         [
             (CF_DEFAULT, self.data.details()),
             (CF_LOCK, self.lock.details()),
@@ -110,10 +318,60 @@ impl Statistics {
         ]
     }
 
+    /// Flattens `details()` into `"cf.field"` keys, e.g. `"lock.get"` or `"data.total"`,
+    /// suitable for attaching to a tracing span or a JSON log line where a nested fixed-size
+    /// array doesn't fit.
+    pub fn to_flat_map(&self) -> Vec<(String, usize)> {
+        let cfs: [(&'static str, &CfStatistics); 3] =
+            [("data", &self.data), ("lock", &self.lock), ("write", &self.write)];
+        cfs.iter()
+            .flat_map(|(cf, stat)| {
+                stat.details()
+                    .to_vec()
+                    .into_iter()
+                    .map(move |(field, value)| (format!("{}.{}", cf, field), value))
+            })
+            .collect()
+    }
+
     pub fn add(&mut self, other: &Self) {
         self.lock.add(&other.lock);
         self.write.add(&other.write);
         self.data.add(&other.data);
+        self.scan_restarts = self.scan_restarts.saturating_add(other.scan_restarts);
+    }
+
+    /// Returns the per-field difference `self - baseline`, using saturating subtraction across
+    /// all three CFs. The inverse of `add`: snapshot a `Statistics` before a request phase,
+    /// snapshot again after, and `sub` the two to attribute that phase's own work.
+    pub fn sub(&self, baseline: &Self) -> Self {
+        Statistics {
+            lock: self.lock.sub(&baseline.lock),
+            write: self.write.sub(&baseline.write),
+            data: self.data.sub(&baseline.data),
+            scan_restarts: self.scan_restarts.saturating_sub(baseline.scan_restarts),
+        }
+    }
+
+    /// Divides every numeric field by `count`. Used by `StatisticsSummary::average`, which
+    /// guards against `count` being zero before calling this.
+    fn divide(&self, count: u64) -> Self {
+        Statistics {
+            lock: self.lock.divide(count),
+            write: self.write.divide(count),
+            data: self.data.divide(count),
+            scan_restarts: self.scan_restarts / count as usize,
+        }
+    }
+
+    /// Zeroes every counter in all three CFs in place, so a long-lived `Statistics` can be
+    /// reused across scans within one request instead of being replaced with
+    /// `Default::default()`.
+    pub fn clear(&mut self) {
+        self.lock.clear();
+        self.write.clear();
+        self.data.clear();
+        self.scan_restarts = 0;
     }
 
     pub fn scan_detail(&self) -> ScanDetail {
@@ -137,15 +395,374 @@ impl Statistics {
     }
 }
 
+/// Prints only the CF sub-statistics (and `scan_restarts`) that are non-zero, so a failing test
+/// assertion shows a concise diff instead of a wall of zeros.
+impl fmt::Debug for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("Statistics");
+        if self.data != CfStatistics::default() {
+            s.field("data", &self.data);
+        }
+        if self.lock != CfStatistics::default() {
+            s.field("lock", &self.lock);
+        }
+        if self.write != CfStatistics::default() {
+            s.field("write", &self.write);
+        }
+        if self.scan_restarts != 0 {
+            s.field("scan_restarts", &self.scan_restarts);
+        }
+        s.finish()
+    }
+}
+
+/// Labels the coprocessor operator that produced a `Statistics` snapshot, so IO can be
+/// attributed to a specific part of a plan instead of only the request as a whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperatorLabel {
+    Scan,
+    Selection,
+    Aggregation,
+    TopN,
+    Limit,
+}
+
 #[derive(Default, Debug)]
 pub struct StatisticsSummary {
     pub stat: Statistics,
     pub count: u64,
+    /// Smallest and largest `Statistics::total_op_count()` seen across the requests summarized
+    /// so far. Both are `None` until the first `add_statistics` call.
+    pub min_op_count: Option<usize>,
+    pub max_op_count: Option<usize>,
+    /// Per-operator sub-summaries, populated only for calls to `add_statistics` that pass a
+    /// label. These never carry their own `per_label` breakdown; the label only attributes one
+    /// level of accumulation, matching `stat`/`count` at this summary's own level.
+    pub per_label: HashMap<OperatorLabel, StatisticsSummary>,
 }
 
 impl StatisticsSummary {
-    pub fn add_statistics(&mut self, v: &Statistics) {
-        self.stat.add(v);
-        self.count += 1;
+    /// Accumulates `v` into the overall totals, and additionally into `label`'s sub-summary
+    /// when one is given.
+    pub fn add_statistics(&mut self, label: Option<OperatorLabel>, v: &Statistics) {
+        Self::accumulate(self, v);
+        if let Some(label) = label {
+            let entry = self.per_label.entry(label).or_insert_with(Self::default);
+            Self::accumulate(entry, v);
+        }
+    }
+
+    fn accumulate(summary: &mut Self, v: &Statistics) {
+        summary.stat.add(v);
+        summary.count += 1;
+        let op_count = v.total_op_count();
+        summary.min_op_count = Some(summary.min_op_count.map_or(op_count, |m| m.min(op_count)));
+        summary.max_op_count = Some(summary.max_op_count.map_or(op_count, |m| m.max(op_count)));
+    }
+
+    /// The average `Statistics` across everything summarized so far, i.e. `stat` with every
+    /// numeric field divided by `count`. Returns a zeroed `Statistics` when nothing has been
+    /// summarized yet, rather than dividing by zero.
+    pub fn average(&self) -> Statistics {
+        if self.count == 0 {
+            return Statistics::default();
+        }
+        self.stat.divide(self.count)
+    }
+
+    /// Folds `other` into `self`, as if every `Statistics` ever added to `other` (directly or
+    /// through a labeled sub-summary) had instead been added directly to `self`. Useful for
+    /// combining per-region summaries into a store-level one.
+    pub fn merge(&mut self, other: &StatisticsSummary) {
+        self.stat.add(&other.stat);
+        self.count += other.count;
+        if let Some(min) = other.min_op_count {
+            self.min_op_count = Some(self.min_op_count.map_or(min, |m| m.min(min)));
+        }
+        if let Some(max) = other.max_op_count {
+            self.max_op_count = Some(self.max_op_count.map_or(max, |m| m.max(max)));
+        }
+        for (label, other_summary) in &other.per_label {
+            self.per_label
+                .entry(*label)
+                .or_insert_with(Self::default)
+                .merge(other_summary);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_restarts_add() {
+        let mut a = Statistics::default();
+        a.scan_restarts = 2;
+        let mut b = Statistics::default();
+        b.scan_restarts = 3;
+        a.add(&b);
+        assert_eq!(a.scan_restarts, 5);
+    }
+
+    #[test]
+    fn test_scan_restarts_in_summary() {
+        let mut summary = StatisticsSummary::default();
+        let mut stats = Statistics::default();
+        stats.scan_restarts = 4;
+        summary.add_statistics(None, &stats);
+        summary.add_statistics(None, &stats);
+        assert_eq!(summary.stat.scan_restarts, 8);
+        assert_eq!(summary.count, 2);
+    }
+
+    #[test]
+    fn test_summary_tracks_op_count_min_max() {
+        let mut summary = StatisticsSummary::default();
+
+        let mut small = Statistics::default();
+        small.data.get = 2;
+        let mut medium = Statistics::default();
+        medium.data.get = 5;
+        let mut large = Statistics::default();
+        large.data.get = 9;
+
+        summary.add_statistics(None, &medium);
+        summary.add_statistics(None, &small);
+        summary.add_statistics(None, &large);
+
+        assert_eq!(summary.min_op_count, Some(2));
+        assert_eq!(summary.max_op_count, Some(9));
+    }
+
+    #[test]
+    fn test_summary_per_operator_label() {
+        let mut summary = StatisticsSummary::default();
+
+        let mut scan_stats = Statistics::default();
+        scan_stats.data.get = 3;
+        let mut agg_stats = Statistics::default();
+        agg_stats.data.get = 5;
+
+        summary.add_statistics(Some(OperatorLabel::Scan), &scan_stats);
+        summary.add_statistics(Some(OperatorLabel::Scan), &scan_stats);
+        summary.add_statistics(Some(OperatorLabel::Aggregation), &agg_stats);
+
+        let scan = &summary.per_label[&OperatorLabel::Scan];
+        assert_eq!(scan.stat.data.get, 6);
+        assert_eq!(scan.count, 2);
+
+        let agg = &summary.per_label[&OperatorLabel::Aggregation];
+        assert_eq!(agg.stat.data.get, 5);
+        assert_eq!(agg.count, 1);
+
+        // The combined total covers both labels.
+        assert_eq!(summary.stat.data.get, 11);
+        assert_eq!(summary.count, 3);
+    }
+
+    #[test]
+    fn test_summary_average_of_identical_statistics() {
+        let mut one = Statistics::default();
+        one.data.get = 6;
+        one.data.next = 9;
+        one.scan_restarts = 3;
+
+        let mut summary = StatisticsSummary::default();
+        for _ in 0..3 {
+            summary.add_statistics(None, &one);
+        }
+
+        assert_eq!(summary.average(), one);
+    }
+
+    #[test]
+    fn test_summary_average_of_empty_is_zero() {
+        let summary = StatisticsSummary::default();
+        assert_eq!(summary.average(), Statistics::default());
+    }
+
+    #[test]
+    fn test_summary_merge_preserves_total_counts() {
+        let mut region_a = StatisticsSummary::default();
+        let mut stats_a = Statistics::default();
+        stats_a.data.get = 4;
+        region_a.add_statistics(Some(OperatorLabel::Scan), &stats_a);
+
+        let mut region_b = StatisticsSummary::default();
+        let mut stats_b = Statistics::default();
+        stats_b.data.get = 10;
+        region_b.add_statistics(Some(OperatorLabel::Scan), &stats_b);
+        region_b.add_statistics(None, &stats_b);
+
+        region_a.merge(&region_b);
+
+        assert_eq!(region_a.count, 3);
+        assert_eq!(region_a.stat.data.get, 24);
+        assert_eq!(region_a.min_op_count, Some(4));
+        assert_eq!(region_a.max_op_count, Some(10));
+
+        let scan = &region_a.per_label[&OperatorLabel::Scan];
+        assert_eq!(scan.count, 2);
+        assert_eq!(scan.stat.data.get, 14);
+    }
+
+    #[test]
+    fn test_seek_next_ratio_seek_heavy() {
+        let mut stat = CfStatistics::default();
+        stat.seek = 10;
+        stat.next = 2;
+        assert_eq!(stat.seek_next_ratio(), 5.0);
+    }
+
+    #[test]
+    fn test_seek_next_ratio_next_heavy() {
+        let mut stat = CfStatistics::default();
+        stat.seek = 1;
+        stat.next = 100;
+        assert_eq!(stat.seek_next_ratio(), 0.01);
+    }
+
+    #[test]
+    fn test_seek_next_ratio_empty() {
+        let stat = CfStatistics::default();
+        assert_eq!(stat.seek_next_ratio(), 0.0);
+
+        // No seeks at all, even with nexts, should still be 0.0.
+        let mut next_only = CfStatistics::default();
+        next_only.next = 5;
+        assert_eq!(next_only.seek_next_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_statistics_seek_next_ratio_aggregates_across_cfs() {
+        let mut stats = Statistics::default();
+        stats.data.seek = 4;
+        stats.data.next = 4;
+        stats.write.seek = 6;
+        stats.write.next = 2;
+        // Combined: 10 seeks, 6 nexts.
+        assert_eq!(stats.seek_next_ratio(), 10.0 / 6.0);
+    }
+
+    #[test]
+    fn test_processed_bytes_accumulate_across_add() {
+        let mut a = CfStatistics::default();
+        a.processed_keys_bytes = 10;
+        a.processed_values_bytes = 100;
+        let mut b = CfStatistics::default();
+        b.processed_keys_bytes = 5;
+        b.processed_values_bytes = 50;
+        a.add(&b);
+        assert_eq!(a.processed_keys_bytes, 15);
+        assert_eq!(a.processed_values_bytes, 150);
+
+        let details = a.details();
+        assert!(details.contains(&(STAT_PROCESSED_KEYS_BYTES, 15)));
+        assert!(details.contains(&(STAT_PROCESSED_VALUES_BYTES, 150)));
+    }
+
+    #[test]
+    fn test_read_nanos_accumulate_across_add() {
+        let mut a = Statistics::default();
+        a.data.read_nanos = 100;
+        a.write.read_nanos = 50;
+        let mut b = Statistics::default();
+        b.data.read_nanos = 25;
+        b.lock.read_nanos = 10;
+
+        a.add(&b);
+
+        assert_eq!(a.data.read_nanos, 125);
+        assert_eq!(a.write.read_nanos, 50);
+        assert_eq!(a.lock.read_nanos, 10);
+        assert_eq!(a.total_read_nanos(), 185);
+
+        let details = a.data.details();
+        assert!(details.contains(&(STAT_READ_NANOS, 125)));
+    }
+
+    #[test]
+    fn test_read_nanos_sampling_toggle() {
+        assert!(!read_nanos_sampling_enabled());
+
+        let mut stat = CfStatistics::default();
+        // Sampling is off by default, so `time_read` still runs the closure but never touches
+        // `read_nanos`.
+        let value = stat.time_read(|| 42);
+        assert_eq!(value, 42);
+        assert_eq!(stat.read_nanos, 0);
+
+        set_read_nanos_sampling(true);
+        stat.time_read(|| ());
+        set_read_nanos_sampling(false);
+        assert!(!read_nanos_sampling_enabled());
+    }
+
+    #[test]
+    fn test_partial_eq_and_pretty_debug() {
+        let mut a = Statistics::default();
+        let mut b = Statistics::default();
+        assert_eq!(a, b);
+
+        a.data.get = 3;
+        b.data.get = 5;
+        assert_ne!(a, b);
+
+        let debug_str = format!("{:?}", a);
+        assert!(debug_str.contains("get: 3"));
+        // Only the touched field should show up; everything else stayed at zero.
+        assert!(!debug_str.contains("next"));
+        assert!(!debug_str.contains("lock"));
+        assert!(!debug_str.contains("write"));
+        assert!(!debug_str.contains("scan_restarts"));
+    }
+
+    #[test]
+    fn test_to_flat_map() {
+        let mut stats = Statistics::default();
+        stats.lock.get = 3;
+        stats.data.next = 4;
+        stats.data.seek = 2;
+
+        let flat: HashMap<_, _> = stats.to_flat_map().into_iter().collect();
+        assert_eq!(flat.get("lock.get"), Some(&3));
+        assert_eq!(flat.get("data.total"), Some(&stats.data.total_op_count()));
+        assert_eq!(flat.get("write.get"), Some(&0));
+    }
+
+    #[test]
+    fn test_sub_is_inverse_of_add() {
+        let mut a = Statistics::default();
+        a.data.get = 3;
+        a.lock.seek = 1;
+        a.scan_restarts = 2;
+
+        let mut b = Statistics::default();
+        b.data.get = 5;
+        b.write.next = 4;
+        b.scan_restarts = 1;
+
+        let mut combined = a.clone();
+        combined.add(&b);
+
+        assert_eq!(combined.sub(&b), a);
+    }
+
+    #[test]
+    fn test_clear_zeroes_in_place() {
+        let mut stats = Statistics::default();
+        stats.data.get = 3;
+        stats.data.next = 4;
+        stats.write.seek = 2;
+        stats.lock.flow_stats.read_bytes = 10;
+        stats.scan_restarts = 1;
+
+        let stats_ptr = &stats as *const Statistics;
+        stats.clear();
+
+        assert_eq!(&stats as *const Statistics, stats_ptr);
+        assert_eq!(stats.total_op_count(), 0);
+        assert_eq!(stats, Statistics::default());
     }
 }
@@ -83,6 +83,10 @@ impl<S: Snapshot> MvccReader<S> {
             self.snapshot.get(&k)?
         };
         self.statistics.data.processed += 1;
+        self.statistics.data.processed_keys_bytes += k.as_encoded().len();
+        if let Some(ref val) = val {
+            self.statistics.data.processed_values_bytes += val.len();
+        }
 
         match val {
             Some(val) => Ok(val),
@@ -99,21 +103,30 @@ impl<S: Snapshot> MvccReader<S> {
             self.lock_cursor = Some(iter);
         }
 
+        let mut value_bytes = 0;
         let res = if let Some(ref mut cursor) = self.lock_cursor {
             match cursor.get(key, &mut self.statistics.lock)? {
-                Some(v) => Some(Lock::parse(v)?),
+                Some(v) => {
+                    value_bytes = v.len();
+                    Some(Lock::parse(v)?)
+                }
                 None => None,
             }
         } else {
             self.statistics.lock.get += 1;
             match self.snapshot.get_cf(CF_LOCK, key)? {
-                Some(v) => Some(Lock::parse(&v)?),
+                Some(v) => {
+                    value_bytes = v.len();
+                    Some(Lock::parse(&v)?)
+                }
                 None => None,
             }
         };
 
         if res.is_some() {
             self.statistics.lock.processed += 1;
+            self.statistics.lock.processed_keys_bytes += key.as_encoded().len();
+            self.statistics.lock.processed_values_bytes += value_bytes;
         }
 
         Ok(res)
@@ -155,8 +168,12 @@ impl<S: Snapshot> MvccReader<S> {
         if !Key::is_user_key_eq(write_key, key.as_encoded()) {
             return Ok(None);
         }
-        let write = WriteRef::parse(cursor.value(&mut self.statistics.write))?.to_owned();
+        let write_key_bytes = write_key.len();
+        let write_value = cursor.value(&mut self.statistics.write);
+        self.statistics.write.processed_values_bytes += write_value.len();
+        let write = WriteRef::parse(write_value)?.to_owned();
         self.statistics.write.processed += 1;
+        self.statistics.write.processed_keys_bytes += write_key_bytes;
         Ok(Some((commit_ts, write)))
     }
 
@@ -319,7 +319,10 @@ where
         ));
     }
     statistics.data.processed += 1;
-    Ok(default_cursor.value(&mut statistics.data).to_vec())
+    statistics.data.processed_keys_bytes += seek_key.as_encoded().len();
+    let value = default_cursor.value(&mut statistics.data).to_vec();
+    statistics.data.processed_values_bytes += value.len();
+    Ok(value)
 }
 
 /// Similar to `near_load_data_by_write`, but accepts a `BackwardCursor` and use
@@ -344,7 +347,10 @@ where
         ));
     }
     statistics.data.processed += 1;
-    Ok(default_cursor.value(&mut statistics.data).to_vec())
+    statistics.data.processed_keys_bytes += seek_key.as_encoded().len();
+    let value = default_cursor.value(&mut statistics.data).to_vec();
+    statistics.data.processed_values_bytes += value.len();
+    Ok(value)
 }
 
 pub fn has_data_in_range<S: Snapshot>(
@@ -1,6 +1,6 @@
 // Copyright 2020 TiKV Project Authors. Licensed under Apache-2.0.
 
-use std::sync::{mpsc::channel, Arc};
+use std::sync::{mpsc::channel, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
@@ -8,9 +8,14 @@ use grpcio::{ChannelBuilder, Environment};
 use kvproto::{kvrpcpb::*, tikvpb::TikvClient};
 use test_raftstore::*;
 use test_storage::new_raft_engine;
-use tikv::server::gc_worker::{GcWorker, GC_MAX_EXECUTING_TASKS};
+use tikv::server::gc_worker::{
+    GcAuditSink, GcConfig, GcPriority, GcStats, GcWorker, GC_MAX_EXECUTING_TASKS,
+};
 use tikv::storage;
+use tikv::storage::{txn::commands, TestStorageBuilder};
+use tikv_util::config::ReadableDuration;
 use tikv_util::{collections::HashMap, HandyRwLock};
+use txn_types::{Key, Mutation, TimeStamp};
 
 #[test]
 fn test_gcworker_busy() {
@@ -28,6 +33,7 @@ fn test_gcworker_busy() {
             .gc(
                 ctx.clone(),
                 1.into(),
+                GcPriority::Normal,
                 Box::new(move |res: storage::Result<()>| {
                     assert!(res.is_ok());
                     tx1.send(1).unwrap();
@@ -43,6 +49,7 @@ fn test_gcworker_busy() {
         .gc(
             ctx,
             1.into(),
+            GcPriority::Normal,
             Box::new(move |res: storage::Result<()>| {
                 assert!(res.is_ok());
                 tx1.send(1).unwrap();
@@ -56,6 +63,7 @@ fn test_gcworker_busy() {
         .gc(
             Context::default(),
             1.into(),
+            GcPriority::Normal,
             Box::new(move |res: storage::Result<()>| {
                 match res {
                     Err(storage::Error(box storage::ErrorInner::GcWorkerTooBusy)) => {}
@@ -73,6 +81,674 @@ fn test_gcworker_busy() {
     }
 }
 
+#[test]
+fn test_gcworker_busy_with_configured_limit() {
+    let snapshot_fp = "raftkv_async_snapshot";
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let max_executing_tasks = 2;
+    let mut gc_worker = GcWorker::new(
+        engine,
+        None,
+        None,
+        None,
+        GcConfig {
+            min_concurrency: max_executing_tasks,
+            max_concurrency: max_executing_tasks,
+            ..Default::default()
+        },
+    );
+    gc_worker.start().unwrap();
+
+    fail::cfg(snapshot_fp, "pause").unwrap();
+    let (tx1, rx1) = channel();
+    for _ in 0..max_executing_tasks {
+        let tx1 = tx1.clone();
+        gc_worker
+            .gc(
+                ctx.clone(),
+                1.into(),
+                GcPriority::Normal,
+                Box::new(move |res: storage::Result<()>| {
+                    assert!(res.is_ok());
+                    tx1.send(1).unwrap();
+                }),
+            )
+            .unwrap();
+    }
+    // Sleep to make sure both tasks are dequeued and blocked on the failpoint.
+    thread::sleep(Duration::from_millis(2000));
+
+    // A third concurrent task exceeds the configured limit and must fail fast.
+    let (tx2, rx2) = channel();
+    gc_worker
+        .gc(
+            Context::default(),
+            1.into(),
+            GcPriority::Normal,
+            Box::new(move |res: storage::Result<()>| {
+                match res {
+                    Err(storage::Error(box storage::ErrorInner::GcWorkerTooBusy)) => {}
+                    res => panic!("expect too busy, got {:?}", res),
+                }
+                tx2.send(1).unwrap();
+            }),
+        )
+        .unwrap();
+
+    rx2.recv().unwrap();
+    fail::remove(snapshot_fp);
+    for _ in 0..max_executing_tasks {
+        rx1.recv().unwrap();
+    }
+}
+
+#[test]
+fn test_gc_worker_high_priority_bypasses_busy_limit() {
+    let snapshot_fp = "raftkv_async_snapshot";
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let max_executing_tasks = 2;
+    let mut gc_worker = GcWorker::new(
+        engine,
+        None,
+        None,
+        None,
+        GcConfig {
+            min_concurrency: max_executing_tasks,
+            max_concurrency: max_executing_tasks,
+            ..Default::default()
+        },
+    );
+    gc_worker.start().unwrap();
+
+    fail::cfg(snapshot_fp, "pause").unwrap();
+    let (tx1, rx1) = channel();
+    // Fill the queue up to the configured concurrency limit with `Normal` tasks.
+    for _ in 0..max_executing_tasks {
+        let tx1 = tx1.clone();
+        gc_worker
+            .gc(
+                ctx.clone(),
+                1.into(),
+                GcPriority::Normal,
+                Box::new(move |res: storage::Result<()>| {
+                    assert!(res.is_ok());
+                    tx1.send(1).unwrap();
+                }),
+            )
+            .unwrap();
+    }
+    // Sleep to make sure the first task is dequeued and blocked on the failpoint, with the
+    // second still in the queue.
+    thread::sleep(Duration::from_millis(2000));
+
+    // A `Normal` task at this point would get `GcWorkerTooBusy` (see
+    // `test_gcworker_busy_with_configured_limit`). A `High` priority task uses the reserved
+    // slots instead and must be accepted.
+    let (tx2, rx2) = channel();
+    gc_worker
+        .gc(
+            ctx,
+            1.into(),
+            GcPriority::High,
+            Box::new(move |res: storage::Result<()>| {
+                assert!(res.is_ok());
+                tx2.send(1).unwrap();
+            }),
+        )
+        .unwrap();
+
+    fail::remove(snapshot_fp);
+    rx2.recv().unwrap();
+    for _ in 0..max_executing_tasks {
+        rx1.recv().unwrap();
+    }
+}
+
+#[test]
+fn test_gc_worker_pending_tasks() {
+    let snapshot_fp = "raftkv_async_snapshot";
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let mut gc_worker = GcWorker::new(engine, None, None, None, Default::default());
+    gc_worker.start().unwrap();
+
+    fail::cfg(snapshot_fp, "pause").unwrap();
+    let (tx, rx) = channel();
+    const TASK_COUNT: usize = 5;
+    for _ in 0..TASK_COUNT {
+        let tx = tx.clone();
+        gc_worker
+            .gc(
+                ctx.clone(),
+                1.into(),
+                GcPriority::Normal,
+                Box::new(move |res: storage::Result<()>| {
+                    assert!(res.is_ok());
+                    tx.send(()).unwrap();
+                }),
+            )
+            .unwrap();
+    }
+    // Sleep to make sure the first task has been dequeued and is blocked on the failpoint,
+    // leaving the rest sitting in the queue.
+    thread::sleep(Duration::from_millis(2000));
+    assert_eq!(gc_worker.pending_tasks(), TASK_COUNT - 1);
+
+    fail::remove(snapshot_fp);
+    for _ in 0..TASK_COUNT {
+        rx.recv().unwrap();
+    }
+    assert_eq!(gc_worker.pending_tasks(), 0);
+}
+
+#[test]
+fn test_gc_worker_adaptive_concurrency() {
+    let snapshot_fp = "raftkv_async_snapshot";
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let mut gc_worker = GcWorker::new(
+        engine,
+        None,
+        None,
+        None,
+        GcConfig {
+            min_concurrency: 1,
+            max_concurrency: 4,
+            ..Default::default()
+        },
+    );
+    gc_worker.start().unwrap();
+    assert_eq!(gc_worker.get_effective_concurrency(), 4);
+
+    let run_gc = |gc_worker: &GcWorker<_>, ctx: Context| {
+        let (tx, rx) = channel();
+        gc_worker
+            .gc(
+                ctx,
+                1.into(),
+                GcPriority::Normal,
+                Box::new(move |res: storage::Result<()>| {
+                    assert!(res.is_ok());
+                    tx.send(()).unwrap();
+                }),
+            )
+            .unwrap();
+        rx.recv_timeout(Duration::from_secs(10)).unwrap();
+    };
+
+    // Make every task take a lot longer than the "slow" latency threshold, and observe the
+    // effective concurrency ratchet down towards `min_concurrency` one task at a time.
+    fail::cfg(snapshot_fp, "sleep(1500)").unwrap();
+    for expected in (1..4).rev() {
+        run_gc(&gc_worker, ctx.clone());
+        assert_eq!(gc_worker.get_effective_concurrency(), expected);
+    }
+    // Already at the floor; further slow tasks must not push it any lower.
+    run_gc(&gc_worker, ctx.clone());
+    assert_eq!(gc_worker.get_effective_concurrency(), 1);
+    fail::remove(snapshot_fp);
+
+    // Once tasks are fast again, the effective concurrency climbs back towards
+    // `max_concurrency`. The latency average first has to decay from about 1500ms to the fast
+    // threshold (about 13 fast tasks at a sample weight of 0.2), so the first growth step takes
+    // several fast tasks; after that, each fast task adds one.
+    let mut fast_tasks = 0;
+    while gc_worker.get_effective_concurrency() == 1 {
+        assert!(fast_tasks < 30, "effective concurrency never grew back");
+        run_gc(&gc_worker, ctx.clone());
+        fast_tasks += 1;
+    }
+    assert_eq!(gc_worker.get_effective_concurrency(), 2);
+    for expected in 3..=4 {
+        run_gc(&gc_worker, ctx.clone());
+        assert_eq!(gc_worker.get_effective_concurrency(), expected);
+    }
+}
+
+#[test]
+fn test_gc_task_hard_timeout_aborts_task() {
+    let snapshot_fp = "raftkv_async_snapshot";
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let mut gc_worker = GcWorker::new(
+        engine,
+        None,
+        None,
+        None,
+        GcConfig {
+            task_timeout: ReadableDuration::millis(200),
+            ..Default::default()
+        },
+    );
+    gc_worker.start().unwrap();
+
+    // Make the very first snapshot fetch inside `gc()` take far longer than `task_timeout`, so
+    // the task is aborted before it can do any GC work.
+    fail::cfg(snapshot_fp, "sleep(1500)").unwrap();
+    let (tx, rx) = channel();
+    gc_worker
+        .gc(
+            ctx,
+            1.into(),
+            GcPriority::Normal,
+            Box::new(move |res: storage::Result<()>| {
+                tx.send(res).unwrap();
+            }),
+        )
+        .unwrap();
+    match rx.recv().unwrap() {
+        Err(storage::Error(box storage::ErrorInner::GcTaskTimedOut(_))) => {}
+        res => panic!("expect GcTaskTimedOut, got {:?}", res),
+    }
+    fail::remove(snapshot_fp);
+}
+
+#[test]
+fn test_gc_worker_audit_sink() {
+    struct CollectingSink {
+        deleted: Mutex<Vec<(Key, TimeStamp)>>,
+    }
+
+    impl GcAuditSink for CollectingSink {
+        fn audit(&self, batch: &[(Key, TimeStamp)]) {
+            self.deleted.lock().unwrap().extend_from_slice(batch);
+        }
+    }
+
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let storage = TestStorageBuilder::from_engine(engine.clone())
+        .build()
+        .unwrap();
+
+    let key = b"k1".to_vec();
+    // Write two versions of the same key: an old, now-stale version and a newer one that
+    // supersedes it. Only the older version should be GC-ed and reported to the audit sink.
+    for (start_ts, commit_ts, value) in &[(10u64, 11u64, b"v1".to_vec()), (20u64, 21u64, b"v2".to_vec())] {
+        let (prewrite_tx, prewrite_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Prewrite::with_defaults(
+                    vec![Mutation::Put((Key::from_raw(&key), value.clone()))],
+                    key.clone(),
+                    (*start_ts).into(),
+                ),
+                Box::new(move |res: storage::Result<_>| prewrite_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        prewrite_rx.recv().unwrap().unwrap();
+
+        let (commit_tx, commit_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Commit::new(
+                    vec![Key::from_raw(&key)],
+                    (*start_ts).into(),
+                    (*commit_ts).into(),
+                    Context::default(),
+                ),
+                Box::new(move |res: storage::Result<_>| commit_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        commit_rx.recv().unwrap().unwrap();
+    }
+
+    let mut gc_worker = GcWorker::new(engine, None, None, None, Default::default());
+    let sink = Arc::new(CollectingSink {
+        deleted: Mutex::new(Vec::new()),
+    });
+    gc_worker.set_audit_sink(sink.clone());
+    gc_worker.start().unwrap();
+
+    let snapshot_fp = "raftkv_async_snapshot";
+    fail::cfg(snapshot_fp, "pause").unwrap();
+    let (gc_tx, gc_rx) = channel();
+    gc_worker
+        .gc(
+            ctx,
+            30.into(),
+            GcPriority::Normal,
+            Box::new(move |res: storage::Result<()>| {
+                res.unwrap();
+                gc_tx.send(()).unwrap();
+            }),
+        )
+        .unwrap();
+    // Sleep to make sure the failpoint is triggered before releasing it.
+    thread::sleep(Duration::from_millis(2000));
+    fail::remove(snapshot_fp);
+    gc_rx.recv().unwrap();
+
+    let deleted = sink.deleted.lock().unwrap();
+    assert_eq!(*deleted, vec![(Key::from_raw(&key), TimeStamp::from(11))]);
+}
+
+#[test]
+fn test_gc_with_stats_reports_deleted_versions() {
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let storage = TestStorageBuilder::from_engine(engine.clone())
+        .build()
+        .unwrap();
+
+    let key = b"k1".to_vec();
+    // Write three versions of the same key; only the two older ones are stale as of the safe
+    // point used below, so `deleted_versions` should count exactly those two.
+    for (start_ts, commit_ts, value) in &[
+        (10u64, 11u64, b"v1".to_vec()),
+        (20u64, 21u64, b"v2".to_vec()),
+        (30u64, 31u64, b"v3".to_vec()),
+    ] {
+        let (prewrite_tx, prewrite_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Prewrite::with_defaults(
+                    vec![Mutation::Put((Key::from_raw(&key), value.clone()))],
+                    key.clone(),
+                    (*start_ts).into(),
+                ),
+                Box::new(move |res: storage::Result<_>| prewrite_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        prewrite_rx.recv().unwrap().unwrap();
+
+        let (commit_tx, commit_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Commit::new(
+                    vec![Key::from_raw(&key)],
+                    (*start_ts).into(),
+                    (*commit_ts).into(),
+                    Context::default(),
+                ),
+                Box::new(move |res: storage::Result<_>| commit_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        commit_rx.recv().unwrap().unwrap();
+    }
+
+    let mut gc_worker = GcWorker::new(engine, None, None, None, Default::default());
+    gc_worker.start().unwrap();
+
+    let (gc_tx, gc_rx) = channel();
+    gc_worker
+        .gc_with_stats(
+            ctx,
+            40.into(),
+            GcPriority::Normal,
+            Box::new(move |res: storage::Result<GcStats>| {
+                gc_tx.send(res).unwrap();
+            }),
+        )
+        .unwrap();
+    let stats = gc_rx.recv().unwrap().unwrap();
+
+    assert_eq!(stats.deleted_versions, 2);
+    assert_eq!(stats.scanned_keys, 1);
+}
+
+#[test]
+fn test_gc_safe_point_buffer_protects_recent_versions() {
+    struct CollectingSink {
+        deleted: Mutex<Vec<(Key, TimeStamp)>>,
+    }
+
+    impl GcAuditSink for CollectingSink {
+        fn audit(&self, batch: &[(Key, TimeStamp)]) {
+            self.deleted.lock().unwrap().extend_from_slice(batch);
+        }
+    }
+
+    let (_cluster, engine, ctx) = new_raft_engine(3, "");
+    let storage = TestStorageBuilder::from_engine(engine.clone())
+        .build()
+        .unwrap();
+
+    let key = b"k1".to_vec();
+    // Three versions of the same key, spread out in physical time: an old one that's always
+    // stale, a middle one that only survives when the buffer holds the safe point back, and a
+    // recent one that's never in scope for GC at all.
+    let versions: Vec<(u64, u64, Vec<u8>)> = vec![
+        (70_000, 71_000, b"v0".to_vec()),
+        (90_000, 91_000, b"v1".to_vec()),
+        (98_000, 99_000, b"v2".to_vec()),
+    ];
+    for (start_ms, commit_ms, value) in &versions {
+        let start_ts = TimeStamp::compose(*start_ms, 0);
+        let commit_ts = TimeStamp::compose(*commit_ms, 0);
+        let (prewrite_tx, prewrite_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Prewrite::with_defaults(
+                    vec![Mutation::Put((Key::from_raw(&key), value.clone()))],
+                    key.clone(),
+                    start_ts,
+                ),
+                Box::new(move |res: storage::Result<_>| prewrite_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        prewrite_rx.recv().unwrap().unwrap();
+
+        let (commit_tx, commit_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Commit::new(
+                    vec![Key::from_raw(&key)],
+                    start_ts,
+                    commit_ts,
+                    Context::default(),
+                ),
+                Box::new(move |res: storage::Result<_>| commit_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        commit_rx.recv().unwrap().unwrap();
+    }
+
+    let mut gc_worker = GcWorker::new(
+        engine,
+        None,
+        None,
+        None,
+        GcConfig {
+            gc_safe_point_buffer: ReadableDuration::secs(5),
+            ..Default::default()
+        },
+    );
+    let sink = Arc::new(CollectingSink {
+        deleted: Mutex::new(Vec::new()),
+    });
+    gc_worker.set_audit_sink(sink.clone());
+    gc_worker.start().unwrap();
+
+    // A 5 second buffer moves the effective safe point back to physical time 95_000, which is
+    // past `v1` (91_000) but short of `v2` (99_000).
+    let safe_point = TimeStamp::compose(100_000, 0);
+    let (gc_tx, gc_rx) = channel();
+    gc_worker
+        .gc(
+            ctx,
+            safe_point,
+            GcPriority::Normal,
+            Box::new(move |res: storage::Result<()>| {
+                res.unwrap();
+                gc_tx.send(()).unwrap();
+            }),
+        )
+        .unwrap();
+    gc_rx.recv().unwrap();
+
+    let deleted = sink.deleted.lock().unwrap();
+    assert_eq!(
+        *deleted,
+        vec![(Key::from_raw(&key), TimeStamp::compose(71_000, 0))]
+    );
+}
+
+#[test]
+fn test_verify_gc_detects_stale_version() {
+    let (cluster, engine, ctx) = new_raft_engine(3, "");
+    let storage = TestStorageBuilder::from_engine(engine.clone())
+        .build()
+        .unwrap();
+
+    let key = b"k1".to_vec();
+    // Put a value, then delete it. After GC at a safe point past the delete, nothing at or
+    // below the safe point should remain for this key.
+    let mutations: Vec<(u64, Mutation)> = vec![
+        (10, Mutation::Put((Key::from_raw(&key), b"v1".to_vec()))),
+        (20, Mutation::Delete(Key::from_raw(&key))),
+    ];
+    for (start_ts, mutation) in mutations {
+        let commit_ts = start_ts + 1;
+        let (prewrite_tx, prewrite_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Prewrite::with_defaults(
+                    vec![mutation],
+                    key.clone(),
+                    start_ts.into(),
+                ),
+                Box::new(move |res: storage::Result<_>| prewrite_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        prewrite_rx.recv().unwrap().unwrap();
+
+        let (commit_tx, commit_rx) = channel();
+        storage
+            .sched_txn_command(
+                commands::Commit::new(
+                    vec![Key::from_raw(&key)],
+                    start_ts.into(),
+                    commit_ts.into(),
+                    Context::default(),
+                ),
+                Box::new(move |res: storage::Result<_>| commit_tx.send(res).unwrap()),
+            )
+            .unwrap();
+        commit_rx.recv().unwrap().unwrap();
+    }
+
+    // `verify_gc` reads the leader's RocksDB directly, so the worker needs it as local storage.
+    let local_storage = cluster.get_engine(ctx.get_peer().get_store_id());
+    let mut gc_worker = GcWorker::new(engine, Some(local_storage), None, None, Default::default());
+    gc_worker.start().unwrap();
+
+    // Simulate an incomplete GC run that leaves the final delete behind.
+    let skip_delete_fp = "gc_skip_latest_delete";
+    fail::cfg(skip_delete_fp, "return").unwrap();
+    let (gc_tx, gc_rx) = channel();
+    gc_worker
+        .gc(
+            ctx,
+            30.into(),
+            GcPriority::Normal,
+            Box::new(move |res: storage::Result<()>| {
+                res.unwrap();
+                gc_tx.send(()).unwrap();
+            }),
+        )
+        .unwrap();
+    gc_rx.recv().unwrap();
+    fail::remove(skip_delete_fp);
+
+    let (verify_tx, verify_rx) = channel();
+    gc_worker
+        .verify_gc(
+            Key::from_encoded(Vec::new()),
+            Key::from_encoded(Vec::new()),
+            30.into(),
+            10,
+            Box::new(move |res| verify_tx.send(res).unwrap()),
+        )
+        .unwrap();
+    let report = verify_rx.recv().unwrap().unwrap();
+
+    assert_eq!(report.stale_keys.len(), 1);
+    assert_eq!(report.stale_keys[0].key, Key::from_raw(&key));
+    assert_eq!(report.stale_keys[0].stale_versions, 1);
+}
+
+#[test]
+fn test_gc_key_removes_only_target_key() {
+    let (cluster, engine, ctx) = new_raft_engine(3, "");
+    let storage = TestStorageBuilder::from_engine(engine.clone())
+        .build()
+        .unwrap();
+
+    let target_key = b"k1".to_vec();
+    let neighbor_key = b"k2".to_vec();
+    // Give both keys the same shape: an old, now-stale version and a newer one that supersedes
+    // it. Only `target_key`'s stale version should be removed by `gc_key`.
+    for key in &[target_key.clone(), neighbor_key.clone()] {
+        for (start_ts, commit_ts, value) in &[(10u64, 11u64, b"v1".to_vec()), (20u64, 21u64, b"v2".to_vec())] {
+            let (prewrite_tx, prewrite_rx) = channel();
+            storage
+                .sched_txn_command(
+                    commands::Prewrite::with_defaults(
+                        vec![Mutation::Put((Key::from_raw(key), value.clone()))],
+                        key.clone(),
+                        (*start_ts).into(),
+                    ),
+                    Box::new(move |res: storage::Result<_>| prewrite_tx.send(res).unwrap()),
+                )
+                .unwrap();
+            prewrite_rx.recv().unwrap().unwrap();
+
+            let (commit_tx, commit_rx) = channel();
+            storage
+                .sched_txn_command(
+                    commands::Commit::new(
+                        vec![Key::from_raw(key)],
+                        (*start_ts).into(),
+                        (*commit_ts).into(),
+                        Context::default(),
+                    ),
+                    Box::new(move |res: storage::Result<_>| commit_tx.send(res).unwrap()),
+                )
+                .unwrap();
+            commit_rx.recv().unwrap().unwrap();
+        }
+    }
+
+    // `verify_gc` below reads the leader's RocksDB directly, so the worker needs it as local
+    // storage.
+    let local_storage = cluster.get_engine(ctx.get_peer().get_store_id());
+    let mut gc_worker = GcWorker::new(engine, Some(local_storage), None, None, Default::default());
+    gc_worker.start().unwrap();
+
+    let snapshot_fp = "raftkv_async_snapshot";
+    fail::cfg(snapshot_fp, "pause").unwrap();
+    let (gc_tx, gc_rx) = channel();
+    gc_worker
+        .gc_key(
+            ctx,
+            Key::from_raw(&target_key),
+            30.into(),
+            Box::new(move |res: storage::Result<usize>| {
+                gc_tx.send(res).unwrap();
+            }),
+        )
+        .unwrap();
+    // Sleep to make sure the failpoint is triggered before releasing it.
+    thread::sleep(Duration::from_millis(2000));
+    fail::remove(snapshot_fp);
+    let deleted = gc_rx.recv().unwrap().unwrap();
+    assert_eq!(deleted, 1);
+
+    // Read back through `verify_gc`: the target key should have nothing stale left, while the
+    // untouched neighbor still carries its old version.
+    let (verify_tx, verify_rx) = channel();
+    gc_worker
+        .verify_gc(
+            Key::from_encoded(Vec::new()),
+            Key::from_encoded(Vec::new()),
+            30.into(),
+            10,
+            Box::new(move |res| verify_tx.send(res).unwrap()),
+        )
+        .unwrap();
+    let report = verify_rx.recv().unwrap().unwrap();
+
+    assert_eq!(report.stale_keys.len(), 1);
+    assert_eq!(report.stale_keys[0].key, Key::from_raw(&neighbor_key));
+    assert_eq!(report.stale_keys[0].stale_versions, 1);
+}
+
 // In theory, raft can propose conf change as long as there is no pending one. Replicas
 // don't apply logs synchronously, so it's possible the old leader is removed before the new
 // leader applies all logs.
@@ -158,13 +834,24 @@ fn test_observer_send_error() {
 
     let observer_send_fp = "lock_observer_send";
     fail::cfg(observer_send_fp, "return").unwrap();
+    // Several prewrites fail to reach the collector while the failpoint is active. The dropped
+    // count these produce is tracked internally (see `applied_lock_collector::test_dropped_count`
+    // for a direct assertion on it), but `CheckLockObserverResponse` has no field to carry it, so
+    // it can't be observed here over the wire — only `is_clean` flipping to false is.
     must_kv_prewrite(
         &client,
-        ctx,
+        ctx.clone(),
         vec![new_mutation(Op::Put, b"k2", b"v")],
         b"k1".to_vec(),
         10,
     );
+    must_kv_prewrite(
+        &client,
+        ctx,
+        vec![new_mutation(Op::Put, b"k3", b"v")],
+        b"k1".to_vec(),
+        10,
+    );
     let resp = check_lock_observer(&client, max_ts);
     assert!(resp.get_error().is_empty(), "{:?}", resp.get_error());
     // Should mark dirty if fails to send locks.
@@ -273,6 +273,35 @@ fn test_ingest_sst_region_not_found() {
     assert!(resp.get_error().has_region_not_found());
 }
 
+// Unlike `test_ingest_sst_region_not_found`, this uploads a real SST first, mirroring BR's
+// download-then-ingest flow where the region can be destroyed after the file is already staged
+// locally. The pre-ingest region check should reject it the same way, without ever attempting to
+// hand the file to raftstore.
+#[test]
+fn test_ingest_sst_region_not_found_with_uploaded_file() {
+    let (_cluster, mut ctx_not_found, _, import) = new_cluster_and_tikv_import_client();
+
+    let temp_dir = Builder::new()
+        .prefix("test_ingest_sst_region_not_found_with_uploaded_file")
+        .tempdir()
+        .unwrap();
+
+    ctx_not_found.set_region_id(1 << 31); // A large region id that must not exist.
+    let sst_path = temp_dir.path().join("test.sst");
+    let sst_range = (0, 100);
+    let (mut meta, data) = gen_sst_file(sst_path, sst_range);
+    meta.set_region_id(ctx_not_found.get_region_id());
+    meta.set_region_epoch(ctx_not_found.get_region_epoch().clone());
+
+    send_upload_sst(&import, &meta, &data).unwrap();
+
+    let mut ingest = IngestRequest::default();
+    ingest.set_context(ctx_not_found);
+    ingest.set_sst(meta);
+    let resp = import.ingest(&ingest).unwrap();
+    assert!(resp.get_error().has_region_not_found());
+}
+
 fn new_sst_meta(crc32: u32, length: u64) -> SstMeta {
     let mut m = SstMeta::default();
     m.set_uuid(Uuid::new_v4().as_bytes().to_vec());
@@ -1,9 +1,10 @@
 // Copyright 2017 TiKV Project Authors. Licensed under Apache-2.0.
 
+use std::process;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{mpsc, Arc};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures::Future;
 use futures_cpupool::Builder;
@@ -11,7 +12,10 @@ use grpcio::EnvBuilder;
 use kvproto::metapb;
 use kvproto::pdpb;
 
-use pd_client::{validate_endpoints, Error as PdError, PdClient, RegionStat, RpcClient};
+use pd_client::{
+    set_store_slow_score, validate_endpoints, wait_with_timeout, BackoffConfig, Error as PdError,
+    PdClient, PdFeature, RegionInfo, RegionStat, RpcClient,
+};
 use raftstore::store;
 use security::{SecurityConfig, SecurityManager};
 use tikv_util::config::ReadableDuration;
@@ -136,6 +140,39 @@ fn test_rpc_client() {
     client.scatter_region(region_info).unwrap();
 }
 
+#[test]
+fn test_ask_batch_split_at_validates_split_keys() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+    let client = new_client(eps, None);
+
+    let mut region = metapb::Region::default();
+    region.set_start_key(b"a".to_vec());
+    region.set_end_key(b"z".to_vec());
+
+    // An in-bounds split key is forwarded to PD like a normal `ask_batch_split`.
+    client
+        .ask_batch_split_at(region.clone(), vec![b"m".to_vec()])
+        .wait()
+        .unwrap();
+
+    // A key before the region's start, at its start (which would produce an empty leading
+    // region), and past its end are all rejected locally, without contacting PD.
+    for key in &[b"0".to_vec(), b"a".to_vec(), b"zz".to_vec()] {
+        match client
+            .ask_batch_split_at(region.clone(), vec![key.clone()])
+            .wait()
+        {
+            Err(PdError::WrongKeyRange(region_id, bad_key)) => {
+                assert_eq!(region_id, region.get_id());
+                assert_eq!(&bad_key, key);
+            }
+            other => panic!("expected WrongKeyRange, got {}", other.is_ok()),
+        }
+    }
+}
+
 #[test]
 fn test_get_tombstone_stores() {
     let eps_count = 1;
@@ -223,6 +260,28 @@ fn test_validate_endpoints() {
     assert!(validate_endpoints(env, &new_config(eps), mgr.clone()).is_err());
 }
 
+#[test]
+fn test_validate_endpoints_reports_cluster_mismatch() {
+    let eps_count = 3;
+    let server = MockServer::with_case(eps_count, Arc::new(Split::new()));
+    let env = Arc::new(
+        EnvBuilder::new()
+            .cq_count(1)
+            .name_prefix(thd_name!("test-pd"))
+            .build(),
+    );
+    let eps = server.bind_addrs();
+
+    let mgr = Arc::new(SecurityManager::new(&SecurityConfig::default()).unwrap());
+    match validate_endpoints(env, &new_config(eps), mgr) {
+        Err(PdError::EndpointClusterMismatch { .. }) => {}
+        other => panic!(
+            "expect Err(EndpointClusterMismatch {{ .. }}), got {:?}",
+            other
+        ),
+    }
+}
+
 fn test_retry<F: Fn(&RpcClient)>(func: F) {
     let eps_count = 1;
     // Retry mocker returns `Err(_)` for most request, here two thirds are `Err(_)`.
@@ -298,6 +357,215 @@ fn test_incompatible_version() {
     );
 }
 
+#[test]
+fn test_get_region_count() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+    let client = new_client(eps, None);
+
+    let store_id = client.alloc_id().unwrap();
+    let mut store = metapb::Store::default();
+    store.set_id(store_id);
+
+    // Regions: [b"", b"a"), [b"a", b"c"), [b"c", b"e"), [b"e", b"").
+    let bounds: Vec<(&[u8], &[u8])> = vec![
+        (b"", b"a"),
+        (b"a", b"c"),
+        (b"c", b"e"),
+        (b"e", b""),
+    ];
+    let mut region0 = metapb::Region::default();
+    region0.set_id(client.alloc_id().unwrap());
+    region0.set_end_key(b"a".to_vec());
+    client.bootstrap_cluster(store, region0).unwrap();
+
+    for &(start, end) in &bounds[1..] {
+        let mut region = metapb::Region::default();
+        region.set_id(client.alloc_id().unwrap());
+        region.set_start_key(start.to_vec());
+        region.set_end_key(end.to_vec());
+        server.default_handler().add_region(&region);
+    }
+
+    // The full key space is covered by all 4 regions.
+    assert_eq!(
+        client
+            .get_region_count(b"".to_vec(), b"".to_vec())
+            .wait()
+            .unwrap(),
+        4
+    );
+    // A sub-range aligned exactly with region boundaries overlaps exactly the regions in it,
+    // excluding the region that starts at the (exclusive) end boundary.
+    assert_eq!(
+        client
+            .get_region_count(b"a".to_vec(), b"e".to_vec())
+            .wait()
+            .unwrap(),
+        2
+    );
+    // A sub-range starting and ending mid-region still counts the regions it overlaps.
+    assert_eq!(
+        client
+            .get_region_count(b"b".to_vec(), b"d".to_vec())
+            .wait()
+            .unwrap(),
+        2
+    );
+    // An unbounded upper end reaches the last region.
+    assert_eq!(
+        client
+            .get_region_count(b"d".to_vec(), b"".to_vec())
+            .wait()
+            .unwrap(),
+        2
+    );
+}
+
+#[test]
+fn test_store_limit_exceeded() {
+    let store_limit = Arc::new(StoreLimit::new(7));
+    let server = MockServer::with_case(1, store_limit);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let resp = client.ask_batch_split(metapb::Region::default(), 2);
+    match resp.wait() {
+        Err(PdError::StoreLimitExceeded { store_id }) => assert_eq!(store_id, 7),
+        other => panic!("expect StoreLimitExceeded, got {:?}", other),
+    }
+
+    match client.scatter_region(RegionInfo::new(metapb::Region::default(), None)) {
+        Err(PdError::StoreLimitExceeded { store_id }) => assert_eq!(store_id, 7),
+        other => panic!("expect StoreLimitExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_pd_in_maintenance() {
+    let maintenance = Arc::new(Maintenance);
+    let server = MockServer::with_case(1, maintenance);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let mut store = metapb::Store::default();
+    store.set_id(client.alloc_id().unwrap());
+    let mut region = metapb::Region::default();
+    region.set_id(client.alloc_id().unwrap());
+    match client.bootstrap_cluster(store, region) {
+        Err(PdError::PdInMaintenance(_)) => (),
+        other => panic!("expect PdInMaintenance, got {:?}", other),
+    }
+
+    // Reads still go through while PD is in maintenance mode.
+    client.get_all_stores(false).unwrap();
+}
+
+#[test]
+fn test_supports_feature() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+    let client = new_client(eps, None);
+
+    let mut old_store = metapb::Store::default();
+    old_store.set_id(client.alloc_id().unwrap());
+    old_store.set_version("4.0.15".to_owned());
+    server.default_handler().add_store(old_store);
+
+    // Every store is still on 4.0.15, well short of the feature's 5.0.0 requirement.
+    assert!(!client.supports_feature(PdFeature::ExampleFutureFeature));
+
+    let mut new_store = metapb::Store::default();
+    new_store.set_id(client.alloc_id().unwrap());
+    new_store.set_version("5.0.0".to_owned());
+    server.default_handler().add_store(new_store);
+
+    // One store hasn't upgraded yet, so the cluster as a whole still can't use the feature.
+    assert!(!client.supports_feature(PdFeature::ExampleFutureFeature));
+
+    let stores = client.get_all_stores(false).unwrap();
+    for mut store in stores {
+        store.set_version("5.0.0".to_owned());
+        server.default_handler().add_store(store);
+    }
+
+    // Now every store has upgraded past the feature's minimum version.
+    assert!(client.supports_feature(PdFeature::ExampleFutureFeature));
+}
+
+#[test]
+fn test_get_stores_by_ids() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+    let client = new_client(eps, None);
+
+    let mut store1 = metapb::Store::default();
+    store1.set_id(client.alloc_id().unwrap());
+    server.default_handler().add_store(store1.clone());
+
+    let mut store2 = metapb::Store::default();
+    store2.set_id(client.alloc_id().unwrap());
+    server.default_handler().add_store(store2.clone());
+
+    let nonexistent_id = client.alloc_id().unwrap();
+
+    let result = client
+        .get_stores_by_ids(&[store2.get_id(), nonexistent_id, store1.get_id()], false)
+        .unwrap();
+    assert_eq!(result.len(), 3);
+    assert_eq!(result[0].as_ref().unwrap().get_id(), store2.get_id());
+    assert!(result[1].is_none());
+    assert_eq!(result[2].as_ref().unwrap().get_id(), store1.get_id());
+}
+
+#[test]
+fn test_get_scheduler_config() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+    let client = new_client(eps, None);
+
+    let mut store1 = metapb::Store::default();
+    store1.set_id(client.alloc_id().unwrap());
+    server.default_handler().add_store(store1);
+
+    let limits = client.get_scheduler_config().wait().unwrap();
+    assert!(limits.max_store_operator_limit > 0);
+    let single_store_batch = limits.recommended_batch_size();
+    assert!(single_store_batch > 0);
+
+    let mut store2 = metapb::Store::default();
+    store2.set_id(client.alloc_id().unwrap());
+    server.default_handler().add_store(store2);
+
+    // With another store online, the cluster-wide limits scale up, giving a caller a larger safe
+    // batch size to pace itself with.
+    let limits = client.get_scheduler_config().wait().unwrap();
+    assert_eq!(limits.recommended_batch_size(), single_store_batch * 2);
+}
+
+#[test]
+fn test_get_tso_retries_flaky() {
+    let eps_count = 1;
+    // Fail the first two attempts, well within the bounded retry, and succeed the third.
+    let flaky = Arc::new(FlakyTso::new(2));
+    let server = MockServer::with_case(eps_count, flaky);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let start = Instant::now();
+    let ts = client.get_tso().wait().unwrap();
+    assert!(!ts.is_zero());
+    // Two jittered delays must have elapsed before the call could succeed.
+    assert!(start.elapsed() >= Duration::from_millis(5));
+}
+
 fn restart_leader(mgr: SecurityManager) {
     let mgr = Arc::new(mgr);
     // Service has only one GetMembersResponse, so the leader never changes.
@@ -352,6 +620,52 @@ fn test_restart_leader_secure() {
     restart_leader(mgr)
 }
 
+#[test]
+fn test_reconnect_backoff() {
+    let eps_count = 1;
+    let mgr = Arc::new(SecurityManager::new(&SecurityConfig::default()).unwrap());
+    let mut server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let backoff = BackoffConfig {
+        base_interval: ReadableDuration::millis(50),
+        max_interval: ReadableDuration::millis(200),
+        multiplier: 2.0,
+    };
+    let client =
+        new_client_with_reconnect_backoff(eps.clone(), Some(Arc::clone(&mgr)), backoff);
+
+    server.stop();
+
+    // The throttle counts from the client's initial connect, so wait out the 50ms base interval;
+    // the first attempt after that dials, fails, and grows the backoff to 100ms.
+    thread::sleep(Duration::from_millis(60));
+    client.reconnect().unwrap_err();
+    // Calling again right away is throttled by the grown backoff rather than dialing again: a
+    // throttled call returns `Ok(())` without dialing, while a dial to the stopped PD would fail.
+    let throttled_start = Instant::now();
+    client.reconnect().unwrap();
+    assert!(throttled_start.elapsed() < Duration::from_millis(50));
+
+    // Wait out the 100ms backoff so the next call actually attempts and grows it again, this
+    // time clamped to max_interval (200ms).
+    thread::sleep(Duration::from_millis(120));
+    client.reconnect().unwrap_err();
+
+    // Restarting PD lets the next attempt, once its backoff elapses, succeed and reset the
+    // backoff back to base_interval.
+    server.start(&mgr, eps.clone());
+    thread::sleep(Duration::from_millis(220));
+    client.reconnect().unwrap();
+
+    // With the backoff reset to base_interval, an attempt shortly after (well under the
+    // previously grown 200ms, but past the 50ms base) dials again rather than being throttled,
+    // and fails because PD is stopped again.
+    server.stop();
+    thread::sleep(Duration::from_millis(60));
+    client.reconnect().unwrap_err();
+}
+
 #[test]
 fn test_change_leader_async() {
     let eps_count = 3;
@@ -381,6 +695,67 @@ fn test_change_leader_async() {
     panic!("failed, leader should changed");
 }
 
+#[test]
+fn test_add_leader_change_listener() {
+    let eps_count = 3;
+    let server = MockServer::with_case(eps_count, Arc::new(LeaderChange::new()));
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let seen1 = Arc::new(Mutex::new(Vec::new()));
+    let seen2 = Arc::new(Mutex::new(Vec::new()));
+    let seen1_clone = Arc::clone(&seen1);
+    let seen2_clone = Arc::clone(&seen2);
+    client.add_leader_change_listener(Box::new(move |addr: &str| {
+        seen1_clone.lock().unwrap().push(addr.to_owned());
+    }));
+    client.add_leader_change_listener(Box::new(move |addr: &str| {
+        seen2_clone.lock().unwrap().push(addr.to_owned());
+    }));
+
+    let leader = client.get_leader();
+
+    for _ in 0..5 {
+        let region = client.get_region_by_id(1);
+        region.wait().ok();
+
+        let new = client.get_leader();
+        if new != leader {
+            let new_url = new.get_client_urls()[0].clone();
+            assert_eq!(seen1.lock().unwrap().last(), Some(&new_url));
+            assert_eq!(seen2.lock().unwrap().last(), Some(&new_url));
+            return;
+        }
+        thread::sleep(LeaderChange::get_leader_interval());
+    }
+
+    panic!("failed, leader should changed");
+}
+
+#[test]
+fn test_force_reconnect_leader() {
+    let eps_count = 3;
+    let server = MockServer::with_case(eps_count, Arc::new(LeaderChange::new()));
+    let eps = server.bind_addrs();
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let client = new_client(eps, None);
+    let counter1 = Arc::clone(&counter);
+    client.handle_reconnect(move || {
+        counter1.fetch_add(1, Ordering::SeqCst);
+    });
+    let leader = client.get_leader();
+
+    // Wait for the mock server to rotate its leader, without issuing any business RPC that
+    // would otherwise lazily discover the change.
+    thread::sleep(LeaderChange::get_leader_interval());
+
+    client.force_reconnect_leader().unwrap();
+    assert!(counter.load(Ordering::SeqCst) >= 1);
+    assert_ne!(client.get_leader(), leader);
+}
+
 #[test]
 fn test_region_heartbeat_on_leader_change() {
     let eps_count = 3;
@@ -444,6 +819,67 @@ fn test_region_heartbeat_on_leader_change() {
     heartbeat_on_leader_change(2);
 }
 
+// Unlike `test_region_heartbeat_on_leader_change`, this never re-registers the callback via
+// `handle_region_heartbeat_response` after the initial call: the stream it returns is expected to
+// auto-resubscribe internally across leader changes, so a single registration keeps delivering
+// responses for as long as the caller keeps sending heartbeats.
+#[test]
+fn test_region_heartbeat_response_stream_auto_resubscribe() {
+    let eps_count = 3;
+    let server = MockServer::with_case(eps_count, Arc::new(LeaderChange::new()));
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+    let poller = Builder::new()
+        .pool_size(1)
+        .name_prefix(thd_name!("poller"))
+        .create();
+    let (tx, rx) = mpsc::channel();
+    // Registered exactly once for the whole test.
+    let f = client.handle_region_heartbeat_response(1, move |resp| {
+        tx.send(resp).unwrap();
+    });
+    poller.spawn(f).forget();
+
+    let region = metapb::Region::default();
+    let peer = metapb::Peer::default();
+    let stat = RegionStat::default();
+    let send_heartbeat = || {
+        poller
+            .spawn(client.region_heartbeat(
+                store::RAFT_INIT_LOG_TERM,
+                region.clone(),
+                peer.clone(),
+                stat.clone(),
+            ))
+            .forget();
+    };
+
+    send_heartbeat();
+    rx.recv_timeout(LeaderChange::get_leader_interval())
+        .unwrap();
+
+    for _ in 0..2 {
+        let mut leader = client.get_leader();
+        loop {
+            let _ = client.get_region_by_id(1).wait();
+            let new = client.get_leader();
+            if leader != new {
+                leader = new;
+                info!("leader changed!");
+                break;
+            }
+            thread::sleep(LeaderChange::get_leader_interval());
+        }
+
+        // No call to `handle_region_heartbeat_response` here: the same callback registered above
+        // must still be the one receiving this heartbeat's response.
+        send_heartbeat();
+        rx.recv_timeout(LeaderChange::get_leader_interval())
+            .unwrap();
+    }
+}
+
 #[test]
 fn test_periodical_update() {
     let eps_count = 3;
@@ -469,3 +905,384 @@ fn test_periodical_update() {
 
     panic!("failed, leader should changed");
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_dropping_client_does_not_leak_threads() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    // Warm up so any lazily-created global state (e.g. the gRPC completion queue) settles before
+    // the baseline count is taken.
+    {
+        let client = new_client(eps.clone(), None);
+        client.get_leader();
+    }
+    thread::sleep(Duration::from_millis(100));
+    let baseline = tikv_util::metrics::get_thread_ids(process::id() as i32)
+        .unwrap()
+        .len();
+
+    for _ in 0..20 {
+        let client = new_client(eps.clone(), None);
+        assert_ne!(client.get_cluster_id().unwrap(), 0);
+        drop(client);
+    }
+    thread::sleep(Duration::from_millis(200));
+
+    let after = tikv_util::metrics::get_thread_ids(process::id() as i32)
+        .unwrap()
+        .len();
+    assert!(
+        after <= baseline + 2,
+        "expected creating and dropping clients not to leak threads, baseline {}, after {}",
+        baseline,
+        after
+    );
+}
+
+#[test]
+fn test_get_tso_parts() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let ts = client.get_tso().wait().unwrap();
+    let (physical, logical) = client.get_tso_parts().wait().unwrap();
+
+    // Not from the same allocation, so only the physical part (millisecond wall-clock) is
+    // expected to line up; assert it is close to the current time rather than exactly equal.
+    let now_millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    assert!((physical as i64 - now_millis as i64).abs() < 5000);
+
+    // The combined form built from the split parts must equal what `TimeStamp::compose` produces,
+    // proving `get_tso_parts` is just `get_tso` decomposed rather than a separate code path.
+    assert_eq!(TimeStamp::compose(physical, logical).physical(), ts.physical());
+}
+
+#[test]
+fn test_store_heartbeat_batch() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let mut stats1 = pdpb::StoreStats::default();
+    stats1.set_store_id(1);
+    let mut stats2 = pdpb::StoreStats::default();
+    stats2.set_store_id(2);
+
+    let resps = client
+        .store_heartbeat_batch(vec![stats1, stats2])
+        .wait()
+        .unwrap();
+    assert_eq!(resps.len(), 2);
+
+    // The mock records store ids in arrival order; asserting against it (rather than just the
+    // response count) proves the batch was pipelined and collected back in the order it was
+    // sent, not reordered or deduplicated along the way.
+    assert_eq!(server.default_handler().store_heartbeats(), vec![1, 2]);
+}
+
+#[test]
+fn test_get_store_slow_scores() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let mut quiet_store = metapb::Store::default();
+    quiet_store.set_id(client.alloc_id().unwrap());
+    server.default_handler().add_store(quiet_store.clone());
+
+    let mut busy_store = metapb::Store::default();
+    busy_store.set_id(client.alloc_id().unwrap());
+    server.default_handler().add_store(busy_store.clone());
+
+    let mut quiet_stats = pdpb::StoreStats::default();
+    quiet_stats.set_store_id(quiet_store.get_id());
+    set_store_slow_score(&mut quiet_stats, 1);
+    client.store_heartbeat(quiet_stats).wait().unwrap();
+
+    let mut busy_stats = pdpb::StoreStats::default();
+    busy_stats.set_store_id(busy_store.get_id());
+    set_store_slow_score(&mut busy_stats, 100);
+    client.store_heartbeat(busy_stats).wait().unwrap();
+
+    let scores = client.get_store_slow_scores().wait().unwrap();
+    assert_eq!(scores.get(&quiet_store.get_id()), Some(&1));
+    assert_eq!(scores.get(&busy_store.get_id()), Some(&100));
+}
+
+#[test]
+fn test_get_hot_regions() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let store_id = client.alloc_id().unwrap();
+    let mut store = metapb::Store::default();
+    store.set_id(store_id);
+
+    let peer_id = client.alloc_id().unwrap();
+    let mut peer = metapb::Peer::default();
+    peer.set_id(peer_id);
+    peer.set_store_id(store_id);
+
+    let hot_region_id = client.alloc_id().unwrap();
+    let mut hot_region = metapb::Region::default();
+    hot_region.set_id(hot_region_id);
+    hot_region.mut_peers().push(peer.clone());
+
+    client
+        .bootstrap_cluster(store.clone(), hot_region.clone())
+        .unwrap();
+
+    // No heartbeats reported yet: nothing is hot.
+    assert!(client.get_hot_regions(store_id).wait().unwrap().is_empty());
+
+    let quiet_region_id = client.alloc_id().unwrap();
+    let mut quiet_region = metapb::Region::default();
+    quiet_region.set_id(quiet_region_id);
+    quiet_region.mut_peers().push(peer.clone());
+
+    let mut quiet_stat = RegionStat::default();
+    quiet_stat.read_keys = 1;
+    client
+        .region_heartbeat(
+            store::RAFT_INIT_LOG_TERM,
+            quiet_region.clone(),
+            peer.clone(),
+            quiet_stat,
+        )
+        .wait()
+        .unwrap();
+
+    // Heartbeating high read QPS for `hot_region` should make it, and only it, appear as hot.
+    let mut hot_stat = RegionStat::default();
+    hot_stat.read_keys = 10_000;
+    hot_stat.read_bytes = 10 * 1024 * 1024;
+    client
+        .region_heartbeat(
+            store::RAFT_INIT_LOG_TERM,
+            hot_region.clone(),
+            peer.clone(),
+            hot_stat,
+        )
+        .wait()
+        .unwrap();
+
+    let hot = client.get_hot_regions(store_id).wait().unwrap();
+    assert_eq!(hot.len(), 1);
+    assert_eq!(hot[0].region_id, hot_region_id);
+}
+
+#[test]
+fn test_region_cache_eviction() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let store_id = client.alloc_id().unwrap();
+    let mut store = metapb::Store::default();
+    store.set_id(store_id);
+
+    let peer_id = client.alloc_id().unwrap();
+    let mut peer = metapb::Peer::default();
+    peer.set_id(peer_id);
+    peer.set_store_id(store_id);
+
+    let region_id = client.alloc_id().unwrap();
+    let mut region = metapb::Region::default();
+    region.set_id(region_id);
+    region.mut_peers().push(peer);
+
+    client.bootstrap_cluster(store, region.clone()).unwrap();
+    assert_eq!(client.region_cache_len(), 0);
+
+    client.get_region(region.get_start_key()).unwrap();
+    assert_eq!(client.region_cache_len(), 1);
+
+    client.evict_region_from_cache(region_id);
+    assert_eq!(client.region_cache_len(), 0);
+}
+
+#[test]
+fn test_scatter_regions_forwards_group() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let store_id = client.alloc_id().unwrap();
+    let mut store = metapb::Store::default();
+    store.set_id(store_id);
+
+    let region_id = client.alloc_id().unwrap();
+    let mut region = metapb::Region::default();
+    region.set_id(region_id);
+
+    client.bootstrap_cluster(store, region).unwrap();
+
+    let region_ids = [region_id, region_id + 1, region_id + 2];
+    client.scatter_regions(&region_ids, "bulk-load").unwrap();
+
+    let req = server.default_handler().last_scatter_region().unwrap();
+    assert_eq!(req.get_group(), "bulk-load");
+    assert_eq!(req.get_region_ids(), &region_ids[..]);
+}
+
+#[test]
+fn test_region_heartbeat_stat_builder() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let store_id = client.alloc_id().unwrap();
+    let mut store = metapb::Store::default();
+    store.set_id(store_id);
+
+    let peer_id = client.alloc_id().unwrap();
+    let mut peer = metapb::Peer::default();
+    peer.set_id(peer_id);
+    peer.set_store_id(store_id);
+
+    let region_id = client.alloc_id().unwrap();
+    let mut region = metapb::Region::default();
+    region.set_id(region_id);
+    region.mut_peers().push(peer.clone());
+
+    client.bootstrap_cluster(store, region.clone()).unwrap();
+
+    let stat = pd_client::RegionStatBuilder::new()
+        .read_bytes(1024)
+        .read_keys(7)
+        .build();
+    client
+        .region_heartbeat(store::RAFT_INIT_LOG_TERM, region.clone(), peer, stat)
+        .wait()
+        .unwrap();
+
+    let req = server
+        .default_handler()
+        .region_heartbeat_stats(region_id)
+        .unwrap();
+    assert_eq!(req.get_bytes_read(), 1024);
+    assert_eq!(req.get_keys_read(), 7);
+    // Fields left unset by the builder should keep their zero default.
+    assert_eq!(req.get_bytes_written(), 0);
+}
+
+#[test]
+fn test_wait_with_timeout() {
+    let eps_count = 1;
+    let server = MockServer::with_case(eps_count, Arc::new(Pause));
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let start = Instant::now();
+    let result = wait_with_timeout(client.get_region_by_id(1), Duration::from_millis(100));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    match result {
+        Err(PdError::Timeout(_)) => {}
+        other => panic!("expect Err(Timeout(_)), got {:?}", other),
+    }
+}
+
+#[test]
+fn test_get_all_store_stats() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+    let client = new_client(eps, None);
+
+    let store_id = client.alloc_id().unwrap();
+    let mut store = metapb::Store::default();
+    store.set_id(store_id);
+    let region_id = client.alloc_id().unwrap();
+    let mut region = metapb::Region::default();
+    region.set_id(region_id);
+    client.bootstrap_cluster(store, region).unwrap();
+
+    let store2_id = client.alloc_id().unwrap();
+    let mut store2 = metapb::Store::default();
+    store2.set_id(store2_id);
+    server.default_handler().add_store(store2);
+
+    for id in &[store_id, store2_id] {
+        let mut stats = pdpb::StoreStats::default();
+        stats.set_store_id(*id);
+        client.store_heartbeat(stats).wait().unwrap();
+    }
+
+    let stats = client.get_all_store_stats().unwrap();
+    assert_eq!(stats.len(), 2);
+    let mut ids: Vec<u64> = stats.iter().map(|s| s.get_store_id()).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![store_id, store2_id]);
+}
+
+#[test]
+fn test_get_region_by_id_timeout() {
+    let eps_count = 1;
+    // A retry count PD never reaches within `LEADER_CHANGE_RETRY` attempts, so every attempt
+    // sleeps for `RECONNECT_INTERVAL_SEC` and returns an error, keeping the request well past
+    // our short timeout below.
+    let retry = Arc::new(Retry::new(1000));
+    let server = MockServer::with_case(eps_count, retry);
+    let eps = server.bind_addrs();
+
+    let client = new_client(eps, None);
+
+    let start = Instant::now();
+    let result = client.get_region_by_id_timeout(1, Duration::from_millis(100));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    match result {
+        Err(PdError::Timeout(_)) => {}
+        other => panic!("expect Err(Timeout(_)), got {:?}", other),
+    }
+}
+
+#[test]
+fn test_alloc_id_batch() {
+    let eps_count = 1;
+    let server = MockServer::new(eps_count);
+    let eps = server.bind_addrs();
+    let client = new_client(eps, None);
+
+    let batch = client.alloc_id_batch(1000).unwrap();
+    assert_eq!(batch.end - batch.start, 1000);
+
+    let mut ids: Vec<u64> = batch.collect();
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), 1000);
+    for window in ids.windows(2) {
+        assert_eq!(window[1], window[0] + 1);
+    }
+
+    // Single-id allocations keep handing out increasing ids, refilling their own cache from
+    // more batches as needed, without colliding with the batch allocated above.
+    let mut prev_id = ids[ids.len() - 1];
+    for _ in 0..10 {
+        let id = client.alloc_id().unwrap();
+        assert!(id > prev_id);
+        prev_id = id;
+    }
+}